@@ -2,7 +2,7 @@
 mod tests {
 
     use bbqueue::{BBQueue, StaticStorageProvider};
-    use futures::executor::block_on;
+    use futures::{executor::block_on, future::join};
 
     #[test]
     fn frame_wrong_size() {
@@ -28,6 +28,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn full_size() {
         block_on(async {
             let bb: BBQueue<StaticStorageProvider<256>> = BBQueue::new_static();
@@ -169,7 +170,32 @@ mod tests {
                 }
             }
 
-            assert!(cons.read().is_none());
+            assert!(cons.try_read().is_none());
         });
     }
+
+    #[test]
+    fn frame_release_wakes_writer() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        // Fill the queue
+        prod.grant(63).unwrap().commit(63);
+
+        // Both releasing and dropping with auto release must wake the writer
+        let write_fut = async {
+            for _ in 0..3 {
+                prod.grant_async(31).await.unwrap().commit(31);
+            }
+        };
+        let read_fut = async {
+            cons.read_async().await.unwrap().release();
+            cons.read_async().await.unwrap().auto_release(true);
+            cons.read_async().await.unwrap().auto_release(true);
+            cons.read_async().await.unwrap().release();
+        };
+
+        block_on(join(write_fut, read_fut));
+        assert!(cons.try_read().is_none());
+    }
 }
@@ -1,8 +1,30 @@
 #[cfg(test)]
 mod tests {
-    use bbqueue::{BBQueue, StaticStorageProvider};
     use bbqueue::Error;
-    use futures::{executor::block_on, future::join};
+    use bbqueue::{BBQueue, StaticStorageProvider};
+    use futures::{
+        executor::block_on,
+        future::join,
+        task::{waker, ArcWake},
+        FutureExt, SinkExt, StreamExt,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+
+    /// A waker that counts how many times it has been woken
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl ArcWake for CountingWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
 
     #[test]
     fn test_read() {
@@ -89,7 +111,7 @@ mod tests {
         let (mut prod, mut _cons) = bb.try_split().unwrap();
         let w_grant_res = block_on(async { prod.grant_exact_async(8).await });
 
-        assert_eq!(w_grant_res.unwrap_err(), Error::InsufficientSize);
+        assert_eq!(w_grant_res.unwrap_err(), Error::RequestTooLarge);
     }
 
     #[test]
@@ -135,12 +157,133 @@ mod tests {
             r_grant.release(4);
         };
 
+        // Doesn't fit around the write pointer, but the ring starts over once empty
         let write_fut = async {
-            let w_grant = prod.grant_exact_async(4).await;
-            assert_eq!(w_grant.unwrap_err(), Error::InsufficientSize);
+            let w_grant = prod.grant_exact_async(4).await.unwrap();
+            w_grant.commit(4);
         };
 
         block_on(join(write_fut, read_fut));
+        assert_eq!(cons.read().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn grant_exact_timeout() {
+        use futures::channel::oneshot;
+
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        prod.grant_exact(4).unwrap().commit(4);
+
+        // The timer fires before anything is released
+        let (timer_tx, timer_rx) = oneshot::channel::<()>();
+        let mut w_grant_fut = Box::pin(prod.grant_exact_timeout(4, timer_rx));
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        timer_tx.send(()).unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            w_grant_fut.poll_unpin(&mut cx),
+            Poll::Ready(Err(Error::Timeout))
+        ));
+        drop(w_grant_fut);
+
+        // A later release doesn't wake the task which gave up
+        cons.read().unwrap().release(1);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        // The space is released before the timer fires
+        let (_timer_tx, timer_rx) = oneshot::channel::<()>();
+        let mut w_grant_fut = Box::pin(prod.grant_exact_timeout(4, timer_rx));
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        cons.read().unwrap().release(3);
+        assert_eq!(count.0.load(Ordering::SeqCst), 2);
+        match w_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => assert_eq!(grant.len(), 4),
+            _ => panic!("expected a write grant"),
+        };
+    }
+
+    #[test]
+    fn grant_exact_timeout_cancelled() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        prod.grant_exact(4).unwrap().commit(4);
+
+        // Dropped while waiting, with a timer that never fires. The future can be
+        // moved to another thread, like the grant futures
+        fn assert_send<T: Send>(fut: T) -> T {
+            fut
+        }
+        let mut w_grant_fut = Box::pin(assert_send(
+            prod.grant_exact_timeout(4, futures::future::pending::<()>()),
+        ));
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(w_grant_fut);
+
+        cons.read().unwrap().release(4);
+        assert_eq!(count.0.load(Ordering::SeqCst), 0);
+
+        // Ready right away, the timer is not needed
+        let w_grant = block_on(prod.grant_exact_timeout(2, futures::future::ready(()))).unwrap();
+        assert_eq!(w_grant.len(), 2);
+    }
+
+    #[test]
+    fn grant_exact_waits_for_drain() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // The write pointer is in the middle of the ring
+        prod.push_slice_all(&[1, 2, 3]).unwrap();
+
+        let mut w_grant_fut = prod.grant_exact_async(5);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // Releasing part of the bytes isn't enough
+        cons.read().unwrap().release(2);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // Once drained, the grant is given from the start of the ring
+        cons.read().unwrap().release(1);
+        assert_eq!(count.0.load(Ordering::SeqCst), 2);
+        match w_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(mut grant)) => {
+                grant.copy_from_slice(&[4, 5, 6, 7, 8]);
+                grant.commit(5);
+            }
+            _ => panic!("expected a write grant"),
+        };
+
+        assert_eq!(&*cons.read().unwrap(), &[4, 5, 6, 7, 8]);
+        assert_eq!(bb.debug_positions().read, 0);
+    }
+
+    #[test]
+    fn grant_exact_whole_ring() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice_all(&[1, 2]).unwrap();
+        cons.read().unwrap().release(2);
+
+        // The whole capacity can be granted once the ring starts over
+        let w_grant = block_on(prod.grant_exact_async(6)).unwrap();
+        assert_eq!(w_grant.len(), 6);
     }
 
     #[test]
@@ -150,7 +293,7 @@ mod tests {
         let w_grant_fut = prod.grant_exact_async(6);
         drop(w_grant_fut);
         let r_grant = cons.read();
-        assert_eq!(r_grant.unwrap_err(), Error::InsufficientSize);
+        assert!(matches!(r_grant, Err(Error::InsufficientSize { .. })));
     }
 
     #[test]
@@ -164,6 +307,661 @@ mod tests {
         drop(r_grant_fut);
 
         let w_grant = prod.grant_max_remaining(4);
-        assert_eq!(w_grant.unwrap_err(), Error::InsufficientSize);
+        assert!(matches!(w_grant, Err(Error::InsufficientSize { .. })));
+    }
+
+    #[test]
+    fn commit_all_wakes_reader() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut r_grant_fut = cons.read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        prod.grant_exact(4).unwrap().commit_all();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => assert_eq!(grant.len(), 4),
+            _ => panic!("expected a read grant"),
+        };
+    }
+
+    #[test]
+    fn release_all_wakes_writer() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        prod.grant_exact(6).unwrap().commit(6);
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut w_grant_fut = prod.grant_max_remaining_async(4);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        cons.read().unwrap().release_all();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        match w_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => assert_eq!(grant.len(), 4),
+            _ => panic!("expected a write grant"),
+        };
+    }
+
+    #[test]
+    fn to_all_on_drop() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let mut w_grant = prod.grant_exact(4).unwrap();
+        w_grant.to_commit_all();
+        drop(w_grant);
+
+        let mut r_grant = cons.read().unwrap();
+        assert_eq!(r_grant.len(), 4);
+        r_grant.to_release_all();
+        drop(r_grant);
+
+        assert!(matches!(cons.read(), Err(Error::InsufficientSize { .. })));
+    }
+
+    #[test]
+    fn read_exact_waits_for_enough() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut r_grant_fut = cons.read_exact_async(3);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // Not enough yet
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(count.0.load(Ordering::SeqCst), 2);
+        match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => assert_eq!(grant.len(), 3),
+            _ => panic!("expected a read grant"),
+        };
+    }
+
+    #[test]
+    fn overwrite_wakes_reader() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        prod.grant_exact(4).unwrap().commit(4);
+        let mut r_grant_fut = cons.read_exact_async(5);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // The overwrite discards the bytes, even if nothing is committed afterwards
+        drop(prod.grant_exact_overwrite(3).unwrap());
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn read_exact_impossible() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Larger than the capacity
+        let r_grant_res = block_on(cons.read_exact_async(7));
+        assert_eq!(r_grant_res.unwrap_err(), Error::RequestTooLarge);
+
+        // Only four bytes before the end of the ring, and the writer wrapped around
+        prod.grant_exact(4).unwrap().commit(4);
+        cons.read().unwrap().release(2);
+        prod.grant_exact(2).unwrap().commit(2);
+        prod.grant_exact(1).unwrap().commit(1);
+        let r_grant_res = block_on(cons.read_exact_async(5));
+        assert!(matches!(r_grant_res, Err(Error::InsufficientSize { .. })));
+
+        // Once the end of the ring is released, the wrapped bytes can be read
+        cons.read().unwrap().release(4);
+        let r_grant = block_on(cons.read_exact_async(1)).unwrap();
+        assert_eq!(r_grant.len(), 1);
+    }
+
+    #[test]
+    fn read_until_waits_for_delimiter() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut r_grant_fut = cons.read_until_async(b'\n');
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // No delimiter yet
+        prod.push_slice_all(b"AT").unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        prod.push_slice_all(b"\nOK").unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 2);
+        match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => {
+                assert_eq!(&*grant, b"AT\n");
+                grant.release_all();
+            }
+            _ => panic!("expected a read grant"),
+        };
+        drop(r_grant_fut);
+
+        // The rest of the record is still available
+        prod.push_slice_all(b"\n").unwrap();
+        let r_grant = block_on(cons.read_until_async(b'\n')).unwrap();
+        assert_eq!(&*r_grant, b"OK\n");
+    }
+
+    #[test]
+    fn read_until_wrapped() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // The delimiter is only committed after the writer wrapped around
+        prod.push_slice_all(b"abcd").unwrap();
+        cons.read().unwrap().release(2);
+        prod.push_slice_all(b"ef").unwrap();
+        prod.push_slice_all(b"\n").unwrap();
+        let r_grant_res = block_on(cons.read_until_async(b'\n'));
+        assert!(matches!(
+            r_grant_res,
+            Err(Error::InsufficientSize {
+                requested: 5,
+                available: 4
+            })
+        ));
+
+        // Once the end of the ring is released, the wrapped bytes can be read
+        cons.read().unwrap().release(4);
+        let r_grant = block_on(cons.read_until_async(b'\n')).unwrap();
+        assert_eq!(&*r_grant, b"\n");
+    }
+
+    #[test]
+    fn split_read_exact_across_wrap() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // Only two bytes before the end of the ring, and the writer wrapped around
+        prod.push_slice_all(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+        cons.read().unwrap().release(5);
+        prod.push_slice_all(&[8, 9]).unwrap();
+
+        // Contiguous reads starve, as the bytes before the end of the ring never grow
+        let r_grant_res = block_on(cons.read_exact_async(4));
+        assert!(matches!(r_grant_res, Err(Error::InsufficientSize { .. })));
+
+        // Not enough yet, even combined
+        let mut r_grant_fut = cons.split_read_exact_async(5);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        prod.push_slice_all(&[10, 11]).unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        let r_grant = match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => grant,
+            _ => panic!("expected a split read grant"),
+        };
+        drop(r_grant_fut);
+
+        // The combined length is exactly the requested size
+        assert_eq!(r_grant.bufs(), (&[6, 7][..], &[8, 9, 10][..]));
+        r_grant.release(5);
+
+        let r_grant = block_on(cons.split_read_exact_async(1)).unwrap();
+        assert_eq!(r_grant.bufs(), (&[11][..], &[][..]));
+        r_grant.release(1);
+    }
+
+    #[test]
+    fn split_read_exact_impossible() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Larger than the capacity
+        let r_grant_res = block_on(cons.split_read_exact_async(7));
+        assert_eq!(r_grant_res.unwrap_err(), Error::RequestTooLarge);
+
+        // The writer must stay behind the read pointer once wrapped around, so
+        // the whole capacity can only be read from the start of the ring
+        prod.grant_exact(4).unwrap().commit(4);
+        cons.read().unwrap().release(1);
+        let r_grant_res = block_on(cons.split_read_exact_async(6));
+        assert!(matches!(r_grant_res, Err(Error::InsufficientSize { .. })));
+
+        // But one byte less is fine
+        prod.grant_exact(2).unwrap().commit(2);
+        let r_grant = block_on(cons.split_read_exact_async(5)).unwrap();
+        assert_eq!(r_grant.combined_len(), 5);
+    }
+
+    #[test]
+    fn read_watermark() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        assert_eq!(bb.read_watermark(), 1);
+        assert!(matches!(
+            bb.set_read_watermark(7),
+            Err(Error::InsufficientSize { .. })
+        ));
+        bb.set_read_watermark(4).unwrap();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut r_grant_fut = cons.read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // No wakeups below the watermark
+        prod.grant_exact(2).unwrap().commit(2);
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(count.0.load(Ordering::SeqCst), 0);
+
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => grant.release(3),
+            _ => panic!("expected a read grant"),
+        };
+        drop(r_grant_fut);
+
+        // Back below the watermark
+        assert!(cons.read_async().poll_unpin(&mut cx).is_ready());
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn wait_for_occupied() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(
+            block_on(cons.wait_for_occupied(7)),
+            Err(Error::RequestTooLarge)
+        );
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // Several small commits cumulatively cross the threshold
+        let mut fut = cons.wait_for_occupied(4);
+        assert!(fut.poll_unpin(&mut cx).is_pending());
+        for _ in 0..3 {
+            prod.grant_exact(1).unwrap().commit(1);
+            assert!(fut.poll_unpin(&mut cx).is_pending());
+        }
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(fut.poll_unpin(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(count.0.load(Ordering::SeqCst), 4);
+        drop(fut);
+
+        // The bytes don't need to be contiguous
+        cons.read().unwrap().release(3);
+        prod.grant_exact(2).unwrap().commit(2);
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(block_on(cons.wait_for_occupied(5)), Ok(()));
+        assert_eq!(cons.read().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn split_read_release_wakes_writer() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        prod.grant_exact(6).unwrap().commit(6);
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut w_grant_fut = prod.grant_exact_async(4);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        cons.split_read().unwrap().release(5);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        match w_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => assert_eq!(grant.len(), 4),
+            _ => panic!("expected a write grant"),
+        };
+    }
+
+    #[test]
+    fn zero_commit_release_dont_wake() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // Zero byte commits don't wake the reader
+        let mut r_grant_fut = cons.read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        drop(prod.grant_exact(4).unwrap());
+        prod.grant_exact(4).unwrap().abort();
+        prod.grant_exact(4).unwrap().commit(0);
+        assert_eq!(count.0.load(Ordering::SeqCst), 0);
+
+        prod.grant_exact(4).unwrap().commit(2);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        match r_grant_fut.poll_unpin(&mut cx) {
+            Poll::Ready(Ok(grant)) => grant.abort(),
+            _ => panic!("expected a read grant"),
+        };
+        drop(r_grant_fut);
+
+        // Zero byte releases don't wake the writer
+        prod.grant_exact(4).unwrap().commit(4);
+        let mut w_grant_fut = prod.grant_exact_async(2);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        drop(cons.read().unwrap());
+        cons.read().unwrap().abort();
+        cons.read().unwrap().release(0);
+        cons.split_read().unwrap().release(0);
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        cons.read().unwrap().release(3);
+        assert_eq!(count.0.load(Ordering::SeqCst), 2);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn write_futures_cancelled_while_pending() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        prod.grant_exact(6).unwrap().commit(6);
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut w_grant_fut = prod.grant_exact_async(4);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(w_grant_fut);
+
+        let mut w_grant_fut = prod.grant_exact_async(5);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(w_grant_fut);
+
+        // No grant was leaked, the consumer can read and release everything
+        cons.read().unwrap().release(6);
+
+        // The producer is still usable synchronously...
+        prod.grant_exact(4).unwrap().commit(4);
+        assert_eq!(cons.read().unwrap().len(), 4);
+        cons.read().unwrap().release(4);
+
+        // ...and asynchronously
+        block_on(prod.grant_exact_async(2)).unwrap().commit(2);
+        let w_grant = block_on(prod.grant_max_remaining_async(6)).unwrap();
+        assert_eq!(w_grant.len(), 3);
+        w_grant.commit(3);
+        assert_eq!(block_on(cons.read_async()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn cancelled_futures_forget_waker() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let cancelled = Arc::new(CountingWaker::default());
+        let cancelled_waker = waker(cancelled.clone());
+        let surviving = Arc::new(CountingWaker::default());
+        let surviving_waker = waker(surviving.clone());
+
+        // A read is cancelled, for instance by a `select` picking another branch
+        let mut r_grant_fut = cons.read_async();
+        let mut cx = Context::from_waker(&cancelled_waker);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(r_grant_fut);
+
+        // Nobody is waiting anymore
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(cancelled.0.load(Ordering::SeqCst), 0);
+        cons.read().unwrap().release(1);
+
+        // Another task waits next, and is the only one woken up
+        let mut cx = Context::from_waker(&cancelled_waker);
+        let mut r_grant_fut = cons.read_exact_async(2);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(r_grant_fut);
+        let mut cx = Context::from_waker(&surviving_waker);
+        let mut r_grant_fut = cons.read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(cancelled.0.load(Ordering::SeqCst), 0);
+        assert_eq!(surviving.0.load(Ordering::SeqCst), 1);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_ready());
+        drop(r_grant_fut);
+
+        // Same for the producer
+        let mut w_grant_fut = prod.grant_exact_async(6);
+        let mut cx = Context::from_waker(&cancelled_waker);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(w_grant_fut);
+        let mut w_grant_fut = prod.grant_exact_async(5);
+        let mut cx = Context::from_waker(&surviving_waker);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        cons.read().unwrap().release(2);
+        assert_eq!(cancelled.0.load(Ordering::SeqCst), 0);
+        assert_eq!(surviving.0.load(Ordering::SeqCst), 2);
+        assert!(w_grant_fut.poll_unpin(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn read_futures_cancelled_while_pending() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut r_grant_fut = cons.read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(r_grant_fut);
+
+        let mut r_grant_fut = cons.split_read_async();
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+        drop(r_grant_fut);
+
+        // The consumer is still usable synchronously...
+        prod.grant_exact(4).unwrap().commit(4);
+        let r_grant = cons.read().unwrap();
+        assert_eq!(r_grant.len(), 4);
+        r_grant.release(2);
+        assert_eq!(cons.split_read().unwrap().combined_len(), 2);
+
+        // ...and asynchronously
+        let r_grant = block_on(cons.read_async()).unwrap();
+        assert_eq!(r_grant.len(), 2);
+        r_grant.release(2);
+        prod.grant_exact(2).unwrap().commit(2);
+        let r_grant = block_on(cons.split_read_async()).unwrap();
+        assert_eq!(r_grant.combined_len(), 2);
+        r_grant.release(2);
+
+        // No grant was leaked, the producer can wrap around and reuse the released space
+        assert_eq!(prod.grant_max_remaining(6).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn frame_stream() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        prod.commit_frames(&[&[1], &[2, 3]]).unwrap();
+
+        // Each frame is released once dropped by the combinator
+        let frames = block_on(
+            (&mut cons)
+                .map(|frame| frame.to_vec())
+                .take(2)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(frames, [vec![1], vec![2, 3]]);
+        assert_eq!(bb.occupied_len(), 0);
+
+        // Pending until the next frame is committed
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+        assert!(cons.poll_next_unpin(&mut cx).is_pending());
+
+        prod.commit_frames(&[&[4, 5, 6]]).unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+        // A frame still held keeps the stream pending
+        let frame = match cons.poll_next_unpin(&mut cx) {
+            Poll::Ready(Some(frame)) => frame,
+            _ => panic!("expected a frame"),
+        };
+        assert_eq!(&*frame, &[4, 5, 6]);
+        assert!(cons.poll_next_unpin(&mut cx).is_pending());
+        drop(frame);
+        assert_eq!(bb.occupied_len(), 0);
+    }
+
+    #[test]
+    fn frame_sink() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        // Forward a whole stream of slices
+        let frames = futures::stream::iter([Ok(&[1, 2][..]), Ok(&[3][..])]);
+        block_on(frames.forward(prod.sink(4))).unwrap();
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2]);
+        rgr.release();
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[3]);
+        rgr.release();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut sink = prod.sink(4);
+
+        // Frames larger than the maximum size are refused, the space stays reserved
+        assert!(matches!(
+            sink.poll_ready_unpin(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(
+            sink.start_send_unpin(&[0; 5]),
+            Err(Error::InsufficientSize {
+                requested: 5,
+                available: 4
+            })
+        );
+        sink.start_send_unpin(&[4, 5, 6, 7]).unwrap();
+
+        // Each frame waits for room for the maximum size, even if it is smaller
+        assert!(matches!(
+            sink.poll_ready_unpin(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        sink.start_send_unpin(&[8]).unwrap();
+        assert!(sink.poll_ready_unpin(&mut cx).is_pending());
+        assert_eq!(count.0.load(Ordering::SeqCst), 0);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[4, 5, 6, 7]);
+        rgr.release();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            sink.poll_ready_unpin(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+
+        // Closing gives back the reserved space
+        block_on(sink.close()).unwrap();
+        drop(sink);
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[8]);
+        rgr.release();
+        assert!(cons.read().is_err());
+    }
+
+    #[test]
+    fn close_wakes_reader() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = waker(count.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        prod.push_slice_all(&[1, 2]).unwrap();
+        let mut r_grant_fut = cons.read_exact_async(3);
+        assert!(r_grant_fut.poll_unpin(&mut cx).is_pending());
+
+        // Requests which can't be satisfied anymore fail, instead of waiting forever
+        prod.close().unwrap();
+        assert_eq!(count.0.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            r_grant_fut.poll_unpin(&mut cx),
+            Poll::Ready(Err(Error::Closed))
+        );
+        drop(r_grant_fut);
+        assert_eq!(
+            cons.wait_for_occupied(3).poll_unpin(&mut cx),
+            Poll::Ready(Err(Error::Closed))
+        );
+        assert_eq!(
+            cons.read_until_async(b'\n').poll_unpin(&mut cx),
+            Poll::Ready(Err(Error::Closed))
+        );
+
+        // The bytes committed before closing can still be read
+        block_on(cons.read_async()).unwrap().release(1);
+        let r_grant = block_on(cons.split_read_exact_async(1)).unwrap();
+        assert_eq!(r_grant.bufs(), (&[2][..], &[][..]));
+        r_grant.release(1);
+        assert_eq!(block_on(cons.read_async()), Err(Error::Closed));
+        assert_eq!(block_on(cons.split_read_async()), Err(Error::Closed));
+    }
+
+    #[test]
+    fn frame_stream_ends_when_closed() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, cons) = bb.try_split_framed().unwrap();
+
+        prod.commit_frames(&[&[1], &[2, 3]]).unwrap();
+        prod.close();
+
+        let frames = block_on(cons.map(|frame| frame.to_vec()).collect::<Vec<_>>());
+        assert_eq!(frames, [vec![1], vec![2, 3]]);
     }
 }
@@ -1,4 +1,4 @@
-use bbqueue::BBQueue;
+use bbqueue::{BBQueue, StaticStorageProvider};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::cmp::min;
 
@@ -17,7 +17,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 
     c.bench_function("bbq 2048/4096", |bench| bench.iter(|| chunky(&data, 2048)));
 
-    let mut buffy: BBQueue<65536>> = BBQueue::new_static();
+    let buffy: BBQueue<StaticStorageProvider<65536>> = BBQueue::new_static();
     let (mut prod, mut cons) = buffy.try_split().unwrap();
 
     c.bench_function("bbq 8192/65536", |bench| {
@@ -63,7 +63,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("std channels 8192 unbounded", |bench| {
         bench.iter(|| {
             use std::sync::mpsc::{Receiver, Sender};
-            let (mut prod, mut cons): (Sender<[u8; 8192]>, Receiver<[u8; 8192]>) =
+            let (prod, cons): (Sender<[u8; 8192]>, Receiver<[u8; 8192]>) =
                 std::sync::mpsc::channel();
             let rdata = &data;
 
@@ -82,7 +82,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
                 sc.spawn(move |_| {
                     rdata.chunks(8192).for_each(|ch| {
                         let x = cons.recv().unwrap();
-                        assert_eq!(&x[..], &ch[..]);
+                        assert_eq!(&x[..], ch);
                     });
                 });
             })
@@ -93,8 +93,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("xbeam channels 8192/65536", |bench| {
         bench.iter(|| {
             use crossbeam::{bounded, Receiver, Sender};
-            let (mut prod, mut cons): (Sender<[u8; 8192]>, Receiver<[u8; 8192]>) =
-                bounded(65536 / 8192);
+            let (prod, cons): (Sender<[u8; 8192]>, Receiver<[u8; 8192]>) = bounded(65536 / 8192);
             let rdata = &data;
 
             thread::scope(|sc| {
@@ -112,7 +111,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
                 sc.spawn(move |_| {
                     rdata.chunks(8192).for_each(|ch| {
                         let x = cons.recv().unwrap();
-                        assert_eq!(&x[..], &ch[..]);
+                        assert_eq!(&x[..], ch);
                     });
                 });
             })
@@ -183,7 +182,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
                 sc.spawn(|_| {
                     data.chunks(8192).for_each(|ch| loop {
                         if let Some(x) = cons.dequeue() {
-                            assert_eq!(&x[..], &ch[..]);
+                            assert_eq!(&x[..], ch);
                             break;
                         }
                     });
@@ -196,11 +195,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 
 use crossbeam_utils::thread;
 fn chunky(data: &[u8], chunksz: usize) {
-    let mut buffy: BBQueue<4096>> = BBQueue::new_static();
+    let buffy: BBQueue<StaticStorageProvider<4096>> = BBQueue::new_static();
     let (mut prod, mut cons) = buffy.try_split().unwrap();
 
     thread::scope(|sc| {
-        let pjh = sc.spawn(|_| {
+        let _pjh = sc.spawn(|_| {
             data.chunks(chunksz).for_each(|ch| loop {
                 if let Ok(mut wgr) = prod.grant_exact(chunksz) {
                     wgr.copy_from_slice(ch);
@@ -210,7 +209,7 @@ fn chunky(data: &[u8], chunksz: usize) {
             });
         });
 
-        let cjh = sc.spawn(|_| {
+        let _cjh = sc.spawn(|_| {
             data.chunks(chunksz).for_each(|ch| {
                 let mut st = 0;
                 loop {
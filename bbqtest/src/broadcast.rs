@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{broadcast::BroadcastBBQueue, Error as BBQError, StaticStorageProvider};
+    use std::thread::spawn;
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT_NODATA: Duration = Duration::from_millis(10_000);
+
+    #[test]
+    fn slowest_consumer_holds_space() {
+        let bb: BroadcastBBQueue<StaticStorageProvider<8>, 3> = BroadcastBBQueue::new_static();
+        let (mut prod, [mut c0, mut c1, mut c2]) = bb.try_split_broadcast().unwrap();
+
+        prod.grant_exact(6).unwrap().commit(6);
+        assert_eq!(c0.len(), 6);
+        assert_eq!(c2.len(), 6);
+
+        c0.read().unwrap().release(6);
+        c1.read().unwrap().release(4);
+        assert_eq!(c0.len(), 0);
+        assert_eq!(c1.len(), 2);
+
+        // c2 has not released anything, so we can't wrap around
+        assert!(matches!(
+            prod.grant_exact(3),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        c2.read().unwrap().release(6);
+
+        // c1 is now the slowest consumer, and has released the first four bytes
+        let mut wgr = prod.grant_exact(3).unwrap();
+        assert_eq!(wgr.len(), 3);
+        wgr.copy_from_slice(&[5, 6, 7]);
+        wgr.commit(3);
+
+        let rgr = c1.read().unwrap();
+        assert_eq!(rgr.len(), 2);
+        rgr.release(2);
+
+        for c in [&mut c0, &mut c1, &mut c2] {
+            let rgr = c.read().unwrap();
+            assert_eq!(&*rgr, &[5, 6, 7]);
+            rgr.release(3);
+            assert!(matches!(c.read(), Err(BBQError::InsufficientSize { .. })));
+        }
+    }
+
+    #[test]
+    fn grant_in_progress_per_consumer() {
+        let bb: BroadcastBBQueue<StaticStorageProvider<6>, 2> = BroadcastBBQueue::new_static();
+        let (mut prod, [mut c0, mut c1]) = bb.try_split_broadcast().unwrap();
+
+        prod.grant_exact(2).unwrap().commit(2);
+
+        let rgr0 = c0.read().unwrap();
+        assert_eq!(c0.read().unwrap_err(), BBQError::GrantInProgress);
+
+        // Other consumers are not affected by an active grant
+        let rgr1 = c1.read().unwrap();
+        assert_eq!(&*rgr0, &*rgr1);
+        drop(rgr0);
+        drop(rgr1);
+
+        // Dropped grants release nothing
+        assert_eq!(c0.len(), 2);
+        assert_eq!(c1.len(), 2);
+    }
+
+    #[test]
+    fn release_and_resplit() {
+        let bb: BroadcastBBQueue<StaticStorageProvider<6>, 2> = BroadcastBBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_broadcast().unwrap();
+        assert!(bb.try_split_broadcast().is_err());
+
+        prod.grant_exact(3).unwrap().commit(3);
+        cons[0].read().unwrap().release(3);
+
+        // Not all consumers read the committed bytes
+        let (prod, mut cons) = bb.try_release_broadcast(prod, cons).unwrap_err();
+        cons[1].read().unwrap().release(3);
+
+        // Consumers in the wrong order are rejected
+        cons.swap(0, 1);
+        let (prod, mut cons) = bb.try_release_broadcast(prod, cons).unwrap_err();
+        cons.swap(0, 1);
+        assert!(bb.try_release_broadcast(prod, cons).is_ok());
+
+        let (_prod, [mut c0, _c1]) = bb.try_split_broadcast().unwrap();
+        assert!(matches!(c0.read(), Err(BBQError::InsufficientSize { .. })));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn producer_never_rewinds() {
+        use futures::{task::noop_waker, FutureExt};
+        use std::task::Context;
+
+        let bb: BroadcastBBQueue<StaticStorageProvider<6>, 2> = BroadcastBBQueue::new_static();
+        let (mut prod, [mut c0, mut c1]) = bb.try_split_broadcast().unwrap();
+
+        prod.grant_exact(3).unwrap().commit(3);
+        c0.read().unwrap().release(3);
+        c1.read().unwrap().release(3);
+
+        // The queue is empty, but the cursors can't be moved back to the start
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(prod.grant_exact_async(5).poll_unpin(&mut cx).is_pending());
+
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(c0.read().unwrap().len(), 2);
+        assert_eq!(c1.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn wraparound() {
+        let bb: BroadcastBBQueue<StaticStorageProvider<7>, 2> = BroadcastBBQueue::new_static();
+        let (mut prod, [mut c0, mut c1]) = bb.try_split_broadcast().unwrap();
+
+        let mut next = 0u8;
+        let mut exp0 = 0u8;
+        let mut exp1 = 0u8;
+
+        for i in 0..1000 {
+            if let Ok(mut wgr) = prod.grant_exact(1 + (i % 4)) {
+                for by in wgr.iter_mut() {
+                    *by = next;
+                    next = next.wrapping_add(1);
+                }
+                let len = wgr.len();
+                wgr.commit(len);
+            }
+
+            // c0 keeps up, c1 lags behind
+            while let Ok(rgr) = c0.read() {
+                for by in rgr.iter() {
+                    assert_eq!(*by, exp0);
+                    exp0 = exp0.wrapping_add(1);
+                }
+                let len = rgr.len();
+                rgr.release(len);
+            }
+
+            if i % 3 == 0 {
+                if let Ok(rgr) = c1.read() {
+                    for by in rgr.iter() {
+                        assert_eq!(*by, exp1);
+                        exp1 = exp1.wrapping_add(1);
+                    }
+                    let len = rgr.len();
+                    rgr.release(len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multi_thread_identical_streams() {
+        const BYTES: usize = 250_000;
+
+        static BB: BroadcastBBQueue<StaticStorageProvider<1024>, 2> =
+            BroadcastBBQueue::new_static();
+        let (mut tx, [rx0, rx1]) = BB.try_split_broadcast().unwrap();
+
+        let data: Vec<u8> = (0..BYTES).map(|_| rand::random::<u8>()).collect();
+        let data_tx = data.clone();
+
+        let tx_thr = spawn(move || {
+            let mut sent = 0;
+            let mut last_tx = Instant::now();
+            while sent < BYTES {
+                let sz = (BYTES - sent).min(1 + sent % 200);
+                if let Ok(mut wgr) = tx.grant_max_remaining(sz) {
+                    let len = wgr.len();
+                    wgr.copy_from_slice(&data_tx[sent..sent + len]);
+                    wgr.commit(len);
+                    sent += len;
+                    last_tx = Instant::now();
+                } else if last_tx.elapsed() > TIMEOUT_NODATA {
+                    panic!("tx timeout");
+                }
+            }
+        });
+
+        let rx_thrs: Vec<_> = vec![rx0, rx1]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, mut rx)| {
+                spawn(move || {
+                    let mut received = Vec::with_capacity(BYTES);
+                    let mut last_rx = Instant::now();
+                    while received.len() < BYTES {
+                        match rx.read() {
+                            Ok(rgr) => {
+                                // Vary the amount released, so the consumers drift apart
+                                let len = rgr.len().min(1 + (received.len() * (idx + 1)) % 97);
+                                received.extend_from_slice(&rgr[..len]);
+                                rgr.release(len);
+                                last_rx = Instant::now();
+                            }
+                            Err(BBQError::InsufficientSize { .. }) => {
+                                if last_rx.elapsed() > TIMEOUT_NODATA {
+                                    panic!("rx timeout");
+                                }
+                            }
+                            Err(e) => panic!("{:?}", e),
+                        }
+                    }
+                    received
+                })
+            })
+            .collect();
+
+        tx_thr.join().unwrap();
+        for thr in rx_thrs {
+            assert!(thr.join().unwrap() == data);
+        }
+    }
+}
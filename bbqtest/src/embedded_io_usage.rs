@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{BBQueue, IoError, StaticStorageProvider};
+    use embedded_io::{Error as _, ErrorKind, Read, ReadReady, Write, WriteReady};
+
+    #[test]
+    fn write_until_full() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert!(prod.write_ready().unwrap());
+        assert_eq!(prod.write(&[]), Ok(0));
+        assert_eq!(prod.write(&[1, 2, 3, 4]), Ok(4));
+        assert_eq!(prod.write(&[5, 6, 7]), Ok(2));
+
+        assert!(!prod.write_ready().unwrap());
+        assert_eq!(prod.write(&[7]), Err(IoError::Full));
+        assert_eq!(IoError::Full.kind(), ErrorKind::WriteZero);
+        assert_eq!(prod.flush(), Ok(()));
+
+        let grant = cons.read().unwrap();
+        assert_eq!(&*grant, &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn read_until_would_block() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut buf = [0u8; 4];
+
+        // `Consumer::read` shadows the trait method, so call it explicitly.
+        assert!(!cons.read_ready().unwrap());
+        assert_eq!(Read::read(&mut cons, &mut buf), Err(IoError::WouldBlock));
+
+        prod.write_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert!(cons.read_ready().unwrap());
+        assert_eq!(Read::read(&mut cons, &mut buf), Ok(4));
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(Read::read(&mut cons, &mut buf), Ok(2));
+        assert_eq!(&buf[..2], &[5, 6]);
+        assert_eq!(Read::read(&mut cons, &mut buf), Err(IoError::WouldBlock));
+    }
+
+    #[test]
+    fn grant_in_progress() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let w_grant = prod.grant_exact(1).unwrap();
+        assert_eq!(prod.write(&[1]), Err(IoError::GrantInProgress));
+        w_grant.commit(1);
+
+        let r_grant = cons.read().unwrap();
+        assert_eq!(
+            Read::read(&mut cons, &mut [0u8; 1]),
+            Err(IoError::GrantInProgress)
+        );
+        r_grant.release(1);
+    }
+
+    /// Pushes `data` through any `embedded-io` writer/reader pair, in chunks
+    fn round_trip<W: Write, R: Read>(w: &mut W, r: &mut R, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut buf = [0u8; 5];
+        for chunk in data.chunks(3) {
+            w.write_all(chunk).unwrap();
+            let len = r.read(&mut buf).unwrap();
+            out.extend_from_slice(&buf[..len]);
+        }
+        while out.len() < data.len() {
+            let len = r.read(&mut buf).unwrap();
+            out.extend_from_slice(&buf[..len]);
+        }
+        out
+    }
+
+    #[test]
+    fn generic_round_trip() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Enough data to wrap around the ring several times
+        let data: Vec<u8> = (0..40).collect();
+        assert_eq!(round_trip(&mut prod, &mut cons, &data), data);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_write_read() {
+        use embedded_io_async::{Read as AsyncRead, Write as AsyncWrite};
+        use futures::{executor::block_on, future::join};
+
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let data: Vec<u8> = (0..64).collect();
+
+        // The queue is much smaller than the data, so both sides
+        // must wait for each other to make progress
+        let write_fut = async {
+            AsyncWrite::write_all(&mut prod, &data).await.unwrap();
+            AsyncWrite::flush(&mut prod).await.unwrap();
+        };
+
+        let read_fut = async {
+            let mut out = vec![0u8; data.len()];
+            AsyncRead::read_exact(&mut cons, &mut out).await.unwrap();
+            out
+        };
+
+        let ((), out) = block_on(join(write_fut, read_fut));
+        assert_eq!(out, data);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_read_until_closed() {
+        use embedded_io_async::{Read as AsyncRead, Write as AsyncWrite};
+        use futures::{executor::block_on, future::join};
+
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let data: Vec<u8> = (0..20).collect();
+
+        let write_fut = async {
+            AsyncWrite::write_all(&mut prod, &data).await.unwrap();
+            prod.close().unwrap();
+        };
+
+        // Read until the end of the stream, without knowing the length
+        let read_fut = async {
+            let mut out = Vec::new();
+            let mut buf = [0; 4];
+            loop {
+                match AsyncRead::read(&mut cons, &mut buf).await.unwrap() {
+                    0 => break out,
+                    len => out.extend_from_slice(&buf[..len]),
+                }
+            }
+        };
+
+        let ((), out) = block_on(join(write_fut, read_fut));
+        assert_eq!(out, data);
+    }
+}
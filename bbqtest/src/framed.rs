@@ -1,6 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use bbqueue::{BBQueue, StaticStorageProvider};
+    use bbqueue::{
+        framed::{
+            frame_header_len, max_frame_payload, FrameGrantR, FrameGrantW, FrameProducer,
+            TaggedFrameProducer,
+        },
+        BBQueue, Error, StaticStorageProvider,
+    };
 
     #[test]
     fn frame_wrong_size() {
@@ -24,6 +30,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn full_size() {
         let bb: BBQueue<StaticStorageProvider<256>> = BBQueue::new_static();
         let (mut prod, mut cons) = bb.try_split_framed().unwrap();
@@ -92,6 +99,42 @@ mod tests {
     }
 
     #[test]
+    fn frame_try_commit() {
+        let bb: BBQueue<StaticStorageProvider<256>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        // Overcommitting is rejected, and the grant is given back untouched
+        let mut wgr = prod.grant(128).unwrap();
+        for (i, by) in wgr.iter_mut().enumerate() {
+            *by = i as u8;
+        }
+        let (wgr, err) = wgr.try_commit(255).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InsufficientSize {
+                requested: 255,
+                available: 128
+            }
+        );
+        assert!(cons.read().is_err());
+        assert_eq!(wgr.len(), 128);
+
+        // A frame smaller than the grant is accepted
+        wgr.try_commit(100).unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr.len(), 100);
+        assert!(rgr.iter().copied().eq(0..100));
+        rgr.release();
+
+        // So is the exact size of the grant
+        prod.grant(64).unwrap().try_commit(64).unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr.len(), 64);
+        rgr.release();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn frame_undercommit() {
         let bb: BBQueue<StaticStorageProvider<512>> = BBQueue::new_static();
         let (mut prod, mut cons) = bb.try_split_framed().unwrap();
@@ -158,6 +201,659 @@ mod tests {
             }
         }
 
-        assert!(cons.read().is_none());
+        assert!(cons.try_read().is_none());
+        assert!(matches!(cons.read(), Err(Error::InsufficientSize { .. })));
+
+        prod.grant(4).unwrap().commit(4);
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.read().unwrap_err(), Error::GrantInProgress);
+        assert!(cons.try_read().is_none());
+        rgr.release();
+        assert!(matches!(cons.read(), Err(Error::InsufficientSize { .. })));
+    }
+
+    #[test]
+    #[allow(clippy::type_complexity)]
+    fn frame_drop_semantics() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        // Write grants, the length of the committed frame, if any
+        let cases: [(fn(&mut FrameGrantW<_>), Option<usize>); 8] = [
+            (|_| {}, None),
+            (|w| w.to_commit(5), Some(5)),
+            (|w| w.to_commit(0), Some(0)),
+            (|w| w.to_commit(100), Some(8)),
+            (|w| w.to_commit_all(), Some(8)),
+            (|w| w.auto_commit(true), Some(8)),
+            (|w| w.auto_commit(false), None),
+            (
+                |w| {
+                    w.to_commit(5);
+                    w.auto_commit(false);
+                },
+                None,
+            ),
+        ];
+
+        for (configure, expected) in cases.iter() {
+            let mut wgr = prod.grant(8).unwrap();
+            wgr.fill(0xAA);
+            configure(&mut wgr);
+            drop(wgr);
+
+            match expected {
+                Some(len) => {
+                    let rgr = cons.read().unwrap();
+                    assert_eq!(&*rgr, &[0xAA; 8][..*len]);
+                    rgr.release();
+                }
+                None => assert!(cons.try_read().is_none()),
+            }
+            assert!(cons.try_read().is_none());
+        }
+
+        // Read grants, whether the frame is released
+        let cases: [(fn(&mut FrameGrantR<_>), bool); 5] = [
+            (|_| {}, false),
+            (|r| r.to_release_all(), true),
+            (|r| r.auto_release(true), true),
+            (|r| r.auto_release(false), false),
+            (
+                |r| {
+                    r.to_release_all();
+                    r.auto_release(false);
+                },
+                false,
+            ),
+        ];
+
+        for (configure, released) in cases.iter() {
+            prod.grant(4).unwrap().commit(4);
+
+            let mut rgr = cons.read().unwrap();
+            configure(&mut rgr);
+            drop(rgr);
+
+            if !released {
+                assert_eq!(cons.read().unwrap().len(), 4);
+                cons.read().unwrap().release();
+            }
+            assert!(cons.try_read().is_none());
+        }
+    }
+
+    #[test]
+    fn frame_peek() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        assert!(cons.peek().is_none());
+
+        let mut wgr = prod.grant(8).unwrap();
+        wgr[..3].copy_from_slice(&[1, 2, 3]);
+        wgr.commit(3);
+        let mut wgr = prod.grant(8).unwrap();
+        wgr[..2].copy_from_slice(&[4, 5]);
+        wgr.commit(2);
+
+        // Peeking doesn't consume the frame, or block reading it
+        assert_eq!(cons.peek(), Some(&[1, 2, 3][..]));
+        assert_eq!(cons.peek(), Some(&[1, 2, 3][..]));
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        rgr.release();
+
+        assert_eq!(cons.peek(), Some(&[4, 5][..]));
+        cons.read().unwrap().release();
+        assert!(cons.peek().is_none());
+
+        // The producer can still write while peeking
+        let mut wgr = prod.grant(8).unwrap();
+        wgr[..1].copy_from_slice(&[6]);
+        wgr.commit(1);
+        assert_eq!(cons.peek(), Some(&[6][..]));
+        let mut wgr = prod.grant(8).unwrap();
+        wgr[..1].copy_from_slice(&[7]);
+        wgr.commit(1);
+        assert_eq!(cons.peek(), Some(&[6][..]));
+    }
+
+    #[test]
+    fn frame_forward_raw() {
+        let bb1: BBQueue<StaticStorageProvider<1024>> = BBQueue::new_static();
+        let bb2: BBQueue<StaticStorageProvider<1024>> = BBQueue::new_static();
+        let (mut prod1, mut cons1) = bb1.try_split_framed().unwrap();
+        let (mut prod2, mut cons2) = bb2.try_split_framed().unwrap();
+
+        for i in 0..1_000usize {
+            // Alternate between one and two byte headers, with undercommitted frames
+            let max_sz = if i % 2 == 0 { 100 } else { 200 };
+            let used = i % 50;
+
+            let mut wgr = prod1.grant(max_sz).unwrap();
+            for (j, by) in wgr.iter_mut().enumerate() {
+                *by = (i + j) as u8;
+            }
+            wgr.commit(used);
+
+            // Forward the exact wire representation
+            let rgr = cons1.read().unwrap();
+            let hdr_len = if max_sz < 128 { 1 } else { 2 };
+            assert_eq!(rgr.frame_len(), hdr_len + used);
+            assert_eq!(&rgr.raw()[hdr_len..], &*rgr);
+            prod2.push_raw(rgr.raw()).unwrap();
+            rgr.release();
+
+            let rgr = cons2.read().unwrap();
+            assert_eq!(rgr.frame_len(), hdr_len + used);
+            assert_eq!(rgr.len(), used);
+            for (j, by) in rgr.iter().enumerate() {
+                assert_eq!((i + j) as u8, *by);
+            }
+            rgr.release();
+        }
+    }
+
+    #[test]
+    fn commit_frames() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        let mut read_frames = |expected: &[&[u8]]| {
+            for frame in expected {
+                let rgr = cons.read().unwrap();
+                assert_eq!(&*rgr, *frame);
+                rgr.release();
+            }
+            assert!(cons.read().is_err());
+        };
+
+        assert_eq!(prod.commit_frames(&[]), Ok(0));
+
+        // All frames fit
+        let frames: &[&[u8]] = &[&[1, 2, 3], &[4], &[], &[5, 6]];
+        assert_eq!(prod.commit_frames(frames), Ok(4));
+        read_frames(frames);
+
+        // Not even one frame fits before the end of the ring, so the
+        // first frame wraps around to the start
+        assert_eq!(prod.commit_frames(&[&[7; 4]]), Ok(1));
+        assert_eq!(prod.commit_frames(&[&[8; 5], &[9; 5]]), Ok(1));
+
+        // Not even one frame fits before the read pointer
+        assert!(matches!(
+            prod.commit_frames(&[&[9; 5]]),
+            Err(Error::InsufficientSize { .. })
+        ));
+        read_frames(&[&[7; 4], &[8; 5]]);
+
+        // Only the first frame fits before the end of the ring
+        assert_eq!(prod.commit_frames(&[&[9; 5], &[10; 5]]), Ok(1));
+        read_frames(&[&[9; 5]]);
+    }
+
+    #[test]
+    fn frames_available() {
+        let bb: BBQueue<StaticStorageProvider<32>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        assert_eq!(cons.frames_available(), 0);
+
+        // A grant in progress is not counted
+        let wgr = prod.grant(6).unwrap();
+        assert_eq!(cons.frames_available(), 0);
+        wgr.commit(6);
+        assert_eq!(cons.frames_available(), 1);
+
+        prod.grant(6).unwrap().commit(2);
+        prod.grant(6).unwrap().commit(0);
+        prod.grant(6).unwrap().commit(6);
+        assert_eq!(cons.frames_available(), 4);
+
+        // A frame held by a read grant is still counted
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.frames_available(), 4);
+        rgr.release();
+        assert_eq!(cons.frames_available(), 3);
+        cons.read().unwrap().release();
+        assert_eq!(cons.frames_available(), 2);
+
+        // Wrap around, skipping the end of the ring
+        prod.grant(10).unwrap().commit(10);
+        assert_eq!(cons.frames_available(), 3);
+        prod.grant(6).unwrap().commit(6);
+        assert_eq!(cons.frames_available(), 4);
+
+        for expected in (0..4).rev() {
+            cons.read().unwrap().release();
+            assert_eq!(cons.frames_available(), expected);
+        }
+    }
+
+    #[test]
+    fn frame_iter() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+        let mut push = |i: u8| {
+            let mut wgr = prod.grant(4).unwrap();
+            wgr[0] = i;
+            wgr.commit(i.into());
+        };
+
+        for i in 0..5 {
+            push(i);
+        }
+        let mut count = 0;
+        for (i, frame) in cons.frames().enumerate() {
+            assert_eq!(frame.len(), i);
+            count += 1;
+        }
+        assert_eq!(count, 5);
+        assert_eq!(cons.frames_available(), 0);
+        assert!(cons.try_read().is_none());
+
+        // A partially consumed iterator leaves the remaining frames intact
+        for i in 1..5 {
+            push(i);
+        }
+        let mut frames = cons.frames();
+        assert_eq!(frames.next().unwrap()[0], 1);
+        let frame = frames.next().unwrap();
+        assert_eq!(frame[0], 2);
+
+        // Holding a frame stops the iteration
+        assert!(frames.next().is_none());
+        drop(frame);
+        assert_eq!(cons.frames_available(), 2);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!((rgr.len(), rgr[0]), (3, 3));
+        rgr.release();
+        assert_eq!(cons.frames().count(), 1);
+        assert_eq!(cons.frames().count(), 0);
+    }
+
+    #[test]
+    fn drain() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        prod.commit_frames(&[&[1], &[2, 2], &[3, 3, 3]]).unwrap();
+
+        // Only the frames available when draining starts are yielded
+        let mut seen = Vec::new();
+        for frame in cons.drain() {
+            seen.push(frame.to_vec());
+            prod.commit_frames(&[&[4]]).unwrap();
+        }
+        assert_eq!(seen, [vec![1], vec![2, 2], vec![3, 3, 3]]);
+        assert_eq!(cons.frames_available(), 3);
+
+        // Holding a frame stops the iteration, the remaining frames are kept
+        let mut drain = cons.drain();
+        let frame = drain.next().unwrap();
+        assert!(drain.next().is_none());
+        drop(frame);
+        assert_eq!(cons.frames_available(), 2);
+        assert_eq!(cons.drain().count(), 2);
+        assert!(cons.try_read().is_none());
+
+        // Nothing to drain
+        assert_eq!(cons.drain().count(), 0);
+    }
+
+    #[test]
+    fn capacity_too_small() {
+        let bb: BBQueue<StaticStorageProvider<1>> = BBQueue::new_static();
+        assert!(matches!(
+            bb.try_split_framed(),
+            Err(Error::CapacityTooSmall)
+        ));
+
+        // The queue remains available in other modes
+        assert!(bb.try_split().is_ok());
+
+        // Just enough for a frame of one byte
+        let bb: BBQueue<StaticStorageProvider<2>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+        prod.commit_frames(&[&[1]]).unwrap();
+        assert_eq!(&*cons.read().unwrap(), &[1]);
+    }
+
+    #[test]
+    fn frame_overhead() {
+        type Prod<'a> = FrameProducer<'a, StaticStorageProvider<65536>>;
+
+        assert_eq!(Prod::frame_overhead(0), 1);
+        assert_eq!(Prod::frame_overhead(127), 1);
+        assert_eq!(Prod::frame_overhead(128), 2);
+        assert_eq!(Prod::frame_overhead(16383), 2);
+        assert_eq!(Prod::frame_overhead(16384), 3);
+
+        // The largest frame that fits, see `frame_big_little`
+        assert_eq!(Prod::frame_len(65533), 65536);
+        assert_eq!(Prod::frame_len(65534), 65537);
+
+        // A batch of frames fits exactly
+        let bb: BBQueue<StaticStorageProvider<300>> = BBQueue::new_static();
+        let (mut prod, cons) = bb.try_split_framed().unwrap();
+        let sizes = [10, 100, 127, 50];
+        let total: usize = sizes
+            .iter()
+            .map(|sz| FrameProducer::<StaticStorageProvider<300>>::frame_len(*sz))
+            .sum();
+        assert_eq!(total, 291);
+
+        for sz in sizes.iter() {
+            prod.grant(*sz).unwrap().commit(*sz);
+        }
+        assert!(prod.grant(9).is_err());
+        prod.grant(8).unwrap().commit(8);
+        assert_eq!(cons.frames_available(), 5);
+    }
+
+    #[test]
+    fn frame_wrap_counts() {
+        let bb: BBQueue<StaticStorageProvider<22>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+        let counts = |cons: &bbqueue::framed::FrameConsumer<_>| {
+            (cons.frames_available(), cons.queued_bytes())
+        };
+
+        assert_eq!(counts(&cons), (0, 0));
+
+        // 10 + 1 used
+        prod.grant(10).unwrap().commit(10);
+        assert_eq!(counts(&cons), (1, 10));
+
+        // 20 + 2 used, undercommitted
+        prod.grant(10).unwrap().commit(4);
+        assert_eq!(counts(&cons), (2, 14));
+
+        cons.read().unwrap().release();
+        assert_eq!(counts(&cons), (1, 4));
+
+        // Wraps around, 9 + 1 used at the start of the ring, undercommitted
+        prod.grant(9).unwrap().commit(5);
+        assert_eq!(counts(&cons), (2, 9));
+
+        // A frame held by a read grant is still counted
+        let rgrant = cons.read().unwrap();
+        assert_eq!(rgrant.len(), 4);
+        assert_eq!(counts(&cons), (2, 9));
+        rgrant.release();
+        assert_eq!(counts(&cons), (1, 5));
+
+        // Empty frames are counted too
+        prod.grant(3).unwrap().commit(0);
+        assert_eq!(counts(&cons), (2, 5));
+
+        cons.read().unwrap().release();
+        assert_eq!(counts(&cons), (1, 0));
+        cons.read().unwrap().release();
+        assert_eq!(counts(&cons), (0, 0));
+        assert!(cons.try_read().is_none());
+    }
+
+    #[test]
+    fn frame_wire_format() {
+        let bb: BBQueue<StaticStorageProvider<20000>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        let mut check = |max_sz: usize, used: usize, header: &[u8]| {
+            let mut wgrant = prod.grant(max_sz).unwrap();
+            wgrant[..used].fill(0xAA);
+            wgrant.commit(used);
+
+            let rgrant = cons.read().unwrap();
+            assert_eq!(rgrant.len(), used);
+            assert_eq!(&rgrant.raw()[..header.len()], header);
+            assert_eq!(rgrant.raw().len(), header.len() + used);
+            rgrant.release();
+        };
+
+        // One byte headers
+        check(0, 0, &[0x01]);
+        check(3, 3, &[0x07]);
+        check(127, 127, &[0xFF]);
+
+        // Two byte headers, sized by the requested size
+        check(128, 128, &[0x02, 0x02]);
+        check(128, 3, &[0x0E, 0x00]);
+        check(16383, 16383, &[0xFE, 0xFF]);
+
+        // Three byte headers
+        check(16384, 16384, &[0x04, 0x00, 0x02]);
+    }
+
+    #[test]
+    fn frame_header_len_values() {
+        assert_eq!(frame_header_len(0), 1);
+        assert_eq!(frame_header_len(127), 1);
+        assert_eq!(frame_header_len(128), 2);
+        assert_eq!(frame_header_len((1 << 14) - 1), 2);
+        assert_eq!(frame_header_len(1 << 14), 3);
+        assert_eq!(frame_header_len((1 << 28) - 1), 4);
+        assert_eq!(frame_header_len(1 << 28), 5);
+
+        // Only representable on 64-bit platforms, but encoded the same everywhere
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(frame_header_len((1 << 56) - 1), 8);
+            assert_eq!(frame_header_len(1 << 56), 9);
+            assert_eq!(frame_header_len(usize::MAX), 9);
+        }
+
+        // Usable in const contexts
+        const HEADER: usize = frame_header_len(1000);
+        assert_eq!(HEADER, 2);
+    }
+
+    #[test]
+    fn max_frame_payload_boundaries() {
+        assert_eq!(max_frame_payload(0), 0);
+        assert_eq!(max_frame_payload(1), 0);
+        assert_eq!(max_frame_payload(2), 1);
+        assert_eq!(max_frame_payload(128), 127);
+        // A 128 byte payload needs a two byte header
+        assert_eq!(max_frame_payload(129), 127);
+        assert_eq!(max_frame_payload(130), 128);
+        assert_eq!(max_frame_payload(65536), 65533);
+
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(max_frame_payload(usize::MAX), usize::MAX - 9);
+
+        // The largest payload can be granted, but nothing larger
+        const CAPACITY: usize = 130;
+        let bb: BBQueue<StaticStorageProvider<CAPACITY>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split_framed().unwrap();
+        assert!(prod.grant(max_frame_payload(CAPACITY) + 1).is_err());
+        let wgrant = prod.grant(max_frame_payload(CAPACITY)).unwrap();
+        assert_eq!(wgrant.len(), 128);
+    }
+
+    #[test]
+    fn frame_forward_raw_many() {
+        use bbqueue::{framed::FrameConsumer, StorageProvider};
+
+        // Forward all the frames available, through plain byte grants
+        fn forward<B: StorageProvider>(
+            cons: &mut FrameConsumer<'_, B>,
+            prod: &mut FrameProducer<'_, B>,
+        ) {
+            let rgr = cons.read_raw().unwrap();
+            let mut wgr = prod.grant_raw(rgr.len()).unwrap();
+            wgr.copy_from_slice(&rgr);
+            wgr.commit(rgr.len());
+            rgr.release_all();
+        }
+
+        let bb1: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let bb2: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod1, mut cons1) = bb1.try_split_framed().unwrap();
+        let (mut prod2, mut cons2) = bb2.try_split_framed().unwrap();
+
+        let frames: [&[u8]; 3] = [&[1, 2, 3], &[4], &[5, 6]];
+        assert_eq!(prod1.commit_frames(&frames), Ok(3));
+        forward(&mut cons1, &mut prod2);
+        assert!(matches!(
+            cons1.read_raw(),
+            Err(Error::InsufficientSize { .. })
+        ));
+
+        for frame in frames {
+            let rgr = cons2.read().unwrap();
+            assert_eq!(&*rgr, frame);
+            rgr.release();
+        }
+
+        // Only the frames before the end of the ring are read at once
+        assert_eq!(prod1.commit_frames(&[&[7; 3], &[8; 2]]), Ok(2));
+        let mut wgr = prod1.grant(5).unwrap();
+        wgr.copy_from_slice(&[9; 5]);
+        wgr.commit(5);
+
+        let rgr = cons1.read_raw().unwrap();
+        assert_eq!(rgr.len(), 7);
+        drop(rgr);
+        forward(&mut cons1, &mut prod2);
+        forward(&mut cons1, &mut prod2);
+
+        for frame in [&[7; 3][..], &[8; 2], &[9; 5]] {
+            let rgr = cons2.read().unwrap();
+            assert_eq!(&*rgr, frame);
+            rgr.release();
+        }
+        assert!(cons2.read().is_err());
+    }
+
+    #[test]
+    fn frame_writer() {
+        use core::fmt::Write;
+
+        let bb: BBQueue<StaticStorageProvider<32>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        let mut wgrant = prod.grant(4).unwrap();
+        let mut writer = wgrant.writer();
+        assert!(write!(writer, "{}", 123456).is_err());
+        assert_eq!(writer.written(), 0);
+        let written = writer.written();
+        wgrant.commit(written);
+        // An empty frame was committed, no partial text
+        let rgrant = cons.read().unwrap();
+        assert!(rgrant.is_empty());
+        rgrant.release();
+
+        let mut wgrant = prod.grant(16).unwrap();
+        let mut writer = wgrant.writer();
+        let (level, msg) = ("INFO", "hello");
+        write!(writer, "[{level}] {msg}").unwrap();
+        let written = writer.written();
+        wgrant.commit(written);
+
+        let rgrant = cons.read().unwrap();
+        assert_eq!(&*rgrant, b"[INFO] hello");
+        rgrant.release();
+        assert!(cons.read().is_err());
+    }
+
+    #[test]
+    fn tagged_frames() {
+        let bb: BBQueue<StaticStorageProvider<20>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed_tagged().unwrap();
+
+        // Enough rounds to wrap around the ring several times
+        for i in 0..20u8 {
+            let mut wgrant = prod.grant_with_tag(4, i).unwrap();
+            assert_eq!(wgrant.len(), 4);
+            wgrant[..3].copy_from_slice(&[i, i + 1, i + 2]);
+            wgrant.commit(3);
+            prod.grant_with_tag(2, 0xF0 | i).unwrap().commit(0);
+            assert_eq!(cons.frames_available(), 2);
+            assert_eq!(cons.queued_bytes(), 3);
+
+            let rgrant = cons.read().unwrap();
+            assert_eq!(rgrant.tag(), Some(i));
+            assert_eq!(&*rgrant, &[i, i + 1, i + 2]);
+            // The tag is stored as the first byte of the frame
+            assert_eq!(rgrant.raw(), &[0x09, i, i, i + 1, i + 2]);
+            rgrant.release();
+
+            // An empty frame still has its tag
+            let rgrant = cons.read().unwrap();
+            assert_eq!(rgrant.tag(), Some(0xF0 | i));
+            assert!(rgrant.is_empty());
+            rgrant.release();
+        }
+        assert!(cons.read().is_err());
+
+        // The tag is not part of the payload
+        let grant = prod.grant_with_tag(4, 1).unwrap();
+        let (grant, err) = grant.try_commit(5).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InsufficientSize {
+                requested: 5,
+                available: 4
+            }
+        );
+        grant.commit(4);
+        assert_eq!(cons.peek().unwrap().len(), 4);
+
+        type Prod<'a> = TaggedFrameProducer<'a, StaticStorageProvider<20>>;
+        assert_eq!(Prod::frame_len(0), 2);
+        assert_eq!(Prod::frame_len(126), 128);
+        assert_eq!(Prod::frame_len(127), 130);
+    }
+
+    #[test]
+    fn untagged_frames() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        prod.grant(2).unwrap().commit(2);
+        let rgrant = cons.read().unwrap();
+        assert_eq!(rgrant.tag(), None);
+        rgrant.release();
+
+        // A queue is either tagged or not, the modes can't be mixed
+        assert!(matches!(
+            bb.try_split_framed_tagged(),
+            Err(Error::AlreadySplit)
+        ));
+        assert!(bb.try_release_framed(prod, cons).is_ok());
+        let (prod, cons) = bb.try_split_framed_tagged().unwrap();
+        assert!(bb.try_release_framed_tagged(prod, cons).is_ok());
+
+        // Not enough room for a tag
+        let bb: BBQueue<StaticStorageProvider<2>> = BBQueue::new_static();
+        assert!(matches!(
+            bb.try_split_framed_tagged(),
+            Err(Error::CapacityTooSmall)
+        ));
+    }
+
+    #[test]
+    fn frame_debug() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        assert!(format!("{:?}", prod).starts_with("FrameProducer { producer: Producer {"));
+        assert!(format!("{:?}", cons).ends_with("reborrowed: false }, tagged: false }"));
+
+        prod.grant(10).unwrap().commit(10);
+        cons.read().unwrap().release();
+
+        // The header of the frame is at the offset of the grant
+        let wgrant = prod.grant(3).unwrap();
+        let dbg = format!("{:?}", wgrant);
+        assert!(dbg.starts_with("FrameGrantW { grant_w: GrantW {"));
+        assert!(dbg.ends_with("offset: 11, len: 4, to_commit: 0 }, hdr_len: 1, tagged: false }"));
+        wgrant.commit(3);
+
+        let rgrant = cons.read().unwrap();
+        assert!(format!("{:?}", rgrant).contains("offset: 11, len: 4, to_release: 0 }"));
     }
 }
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{BBQueue, BbqHooks, Error as BBQError, StaticStorageProvider};
+    use std::sync::Mutex;
+
+    /// Records the regions passed to each hook
+    struct CountingHooks {
+        commits: Mutex<Vec<Vec<u8>>>,
+        reads: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl CountingHooks {
+        const fn new() -> Self {
+            Self {
+                commits: Mutex::new(Vec::new()),
+                reads: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn take_commits(&self) -> Vec<Vec<u8>> {
+            std::mem::take(&mut *self.commits.lock().unwrap())
+        }
+
+        fn take_reads(&self) -> Vec<Vec<u8>> {
+            std::mem::take(&mut *self.reads.lock().unwrap())
+        }
+    }
+
+    impl BbqHooks for CountingHooks {
+        fn pre_commit(&self, data: &[u8]) {
+            self.commits.lock().unwrap().push(data.to_vec());
+        }
+
+        fn post_read(&self, data: &[u8]) {
+            self.reads.lock().unwrap().push(data.to_vec());
+        }
+    }
+
+    #[test]
+    fn one_call_per_commit_and_read() {
+        static HOOKS: CountingHooks = CountingHooks::new();
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        bb.set_hooks(&HOOKS).unwrap();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let mut wgr = prod.grant_exact(4).unwrap();
+        wgr.copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit(3);
+        assert_eq!(HOOKS.take_commits(), [vec![1, 2, 3]]);
+
+        // Committed on drop
+        let mut wgr = prod.grant_exact(2).unwrap();
+        wgr.copy_from_slice(&[5, 6]);
+        wgr.to_commit(2);
+        drop(wgr);
+        assert_eq!(HOOKS.take_commits(), [vec![5, 6]]);
+
+        // Nothing committed, nothing to maintain
+        prod.grant_exact(1).unwrap().commit(0);
+        assert!(HOOKS.take_commits().is_empty());
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(HOOKS.take_reads(), [vec![1, 2, 3, 5, 6]]);
+        rgr.release(4);
+        assert!(HOOKS.take_commits().is_empty());
+
+        let rgr = cons.read().unwrap();
+        rgr.release(1);
+        assert_eq!(HOOKS.take_reads(), [vec![6]]);
+
+        // Empty queue, no grant is handed out
+        assert!(cons.read().is_err());
+        assert!(HOOKS.take_reads().is_empty());
+    }
+
+    #[test]
+    fn one_call_per_region() {
+        static HOOKS: CountingHooks = CountingHooks::new();
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        bb.set_hooks(&HOOKS).unwrap();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        cons.read().unwrap().release(3);
+        HOOKS.take_commits();
+        HOOKS.take_reads();
+
+        // Spans the end of the ring
+        let mut wgr = prod.grant_split_remaining(4).unwrap();
+        let (buf1, buf2) = wgr.bufs_mut();
+        buf1.copy_from_slice(&[5, 6]);
+        buf2.copy_from_slice(&[7, 8]);
+        wgr.commit(4);
+        assert_eq!(HOOKS.take_commits(), [vec![5, 6], vec![7, 8]]);
+
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(HOOKS.take_reads(), [vec![4, 5, 6], vec![7, 8]]);
+        rgr.release(5);
+    }
+
+    #[test]
+    fn set_hooks_after_split() {
+        static HOOKS: CountingHooks = CountingHooks::new();
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (prod, cons) = bb.try_split().unwrap();
+        assert_eq!(bb.set_hooks(&HOOKS), Err(BBQError::AlreadySplit));
+
+        // Available again once released
+        assert!(bb.try_release(prod, cons).is_ok());
+        assert_eq!(bb.set_hooks(&HOOKS), Ok(()));
+    }
+}
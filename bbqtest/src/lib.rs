@@ -1,16 +1,46 @@
 //! NOTE: this crate is really just a shim for testing
 //! the other no-std crate.
 
+// The loom atomics may only be used from a loom model, and the
+// `static` queues of the other tests can't be built with them
+#[cfg(feature = "loom")]
+mod loom_model;
+
+#[cfg(all(feature = "async", not(feature = "loom")))]
 mod async_framed;
+#[cfg(all(feature = "async", not(feature = "loom")))]
 mod async_usage;
+#[cfg(not(feature = "loom"))]
+mod broadcast;
+#[cfg(not(feature = "loom"))]
+mod embedded_io_usage;
+#[cfg(not(feature = "loom"))]
 mod framed;
+#[cfg(not(feature = "loom"))]
+mod hooks;
+#[cfg(not(feature = "loom"))]
+mod logger;
+#[cfg(not(feature = "loom"))]
+mod multi;
+#[cfg(not(feature = "loom"))]
 mod multi_thread;
+#[cfg(not(any(feature = "async", feature = "loom")))]
+mod no_async;
+#[cfg(not(feature = "loom"))]
 mod ring_around_the_senders;
+#[cfg(not(feature = "loom"))]
 mod single_thread;
+#[cfg(not(feature = "loom"))]
+mod std_io;
+#[cfg(not(feature = "loom"))]
+mod typed;
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "loom")))]
 mod tests {
-    use bbqueue::{BBQueue, Error as BBQError, StaticStorageProvider};
+    use bbqueue::{
+        AlignedStorageProvider, BBQueue, Error as BBQError, Positions, StaticStorageProvider, Stats,
+    };
+    use rand::prelude::*;
 
     #[test]
     fn deref_deref_mut() {
@@ -37,10 +67,10 @@ mod tests {
     #[test]
     fn static_allocator() {
         // Check we can make multiple static items...
-        static mut BBQ1: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
-        static mut BBQ2: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
-        let (mut prod1, mut cons1) = unsafe { BBQ1.try_split().unwrap() };
-        let (mut _prod2, mut cons2) = unsafe { BBQ2.try_split().unwrap() };
+        static BBQ1: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        static BBQ2: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod1, mut cons1) = BBQ1.try_split().unwrap();
+        let (mut _prod2, mut cons2) = BBQ2.try_split().unwrap();
 
         // ... and they aren't the same
         let mut wgr1 = prod1.grant_exact(3).unwrap();
@@ -78,12 +108,77 @@ mod tests {
         assert_eq!(&*rgr1, &[1, 2, 3]);
     }
 
+    #[test]
+    fn split_zeroes_slice() {
+        let mut buf = [0xAA; 16];
+        let bb = BBQueue::new_from_slice(&mut buf);
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Commit the whole buffer without writing to it
+        prod.grant_exact(16).unwrap().commit(16);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[0; 16]);
+    }
+
+    #[test]
+    fn release_zeroes_static() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, cons) = bb.try_split().unwrap();
+
+        let mut wgr = prod.grant_exact(16).unwrap();
+        wgr.fill(0xAA);
+        wgr.commit(16);
+
+        // The static storage isn't zeroed when splitting, but when releasing
+        assert!(bb.force_release_and_clear(prod, cons).is_ok());
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        prod.grant_exact(16).unwrap().commit(16);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[0; 16]);
+    }
+
+    #[test]
+    fn release_not_empty() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, cons) = bb.try_split().unwrap();
+
+        // Committed bytes across the end of the ring
+        prod.grant_exact(4).unwrap().commit(4);
+        let (mut prod, mut cons) = bb.try_release(prod, cons).unwrap_err();
+        cons.read().unwrap().release(4);
+        prod.grant_exact(3).unwrap().commit(3);
+        let (prod, mut cons) = bb.try_release(prod, cons).unwrap_err();
+
+        // Drain the queue
+        while let Ok(rgr) = cons.read() {
+            let len = rgr.len();
+            rgr.release(len);
+        }
+        assert!(bb.try_release(prod, cons).is_ok());
+
+        // Same in framed mode
+        let (mut prod, cons) = bb.try_split_framed().unwrap();
+        prod.grant(2).unwrap().commit(2);
+        let (prod, mut cons) = bb.try_release_framed(prod, cons).unwrap_err();
+        cons.read().unwrap().release();
+        assert!(bb.try_release_framed(prod, cons).is_ok());
+
+        // Unless the unread bytes are explicitly discarded
+        let (mut prod, cons) = bb.try_split().unwrap();
+        prod.grant_exact(2).unwrap().commit(2);
+        assert!(bb.force_release_and_clear(prod, cons).is_ok());
+        let (_prod, mut cons) = bb.try_split().unwrap();
+        assert!(cons.read().is_err());
+    }
+
     #[test]
     fn release() {
         // Check we can make multiple static items...
-        static mut BBQ1: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
-        static mut BBQ2: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
-        unsafe {
+        static BBQ1: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        static BBQ2: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        {
             let (prod1, cons1) = BBQ1.try_split().unwrap();
             let (prod2, cons2) = BBQ2.try_split().unwrap();
 
@@ -123,19 +218,28 @@ mod tests {
         // Initialize
         let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
         let (mut prod, mut cons) = bb.try_split().unwrap();
-        assert_eq!(cons.read(), Err(BBQError::InsufficientSize));
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
 
         // Initial grant, shouldn't roll over
         let mut x = prod.grant_exact(4).unwrap();
 
         // Still no data available yet
-        assert_eq!(cons.read(), Err(BBQError::InsufficientSize));
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
 
         // Add full data from grant
         x.copy_from_slice(&[1, 2, 3, 4]);
 
         // Still no data available yet
-        assert_eq!(cons.read(), Err(BBQError::InsufficientSize));
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
 
         // Commit data
         x.commit(4);
@@ -187,21 +291,1089 @@ mod tests {
         r.release(0);
         x.commit(3);
 
-        let a = cons.read().unwrap();
+        let a = cons.read().unwrap();
+
+        // NOTE: The data we just added isn't available yet,
+        // since it has wrapped around
+        assert_eq!(&*a, &[11, 12]);
+
+        a.release(2);
+
+        // And now we can see it
+        let r = cons.read().unwrap();
+        assert_eq!(&*r, &[21, 22, 23]);
+        r.release(0);
+
+        // Ask for something way too big
+        assert!(prod.grant_exact(10).is_err());
+    }
+
+    #[test]
+    fn occupied_free_len() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Fresh queue
+        assert_eq!(bb.occupied_len(), 0);
+        assert_eq!(bb.free_len(), 6);
+        assert_eq!(cons.len(), 0);
+        assert_eq!(prod.space_remaining(), 6);
+
+        // An open write grant reserves space, but doesn't occupy it yet
+        let wgr = prod.grant_exact(4).unwrap();
+        assert_eq!(bb.occupied_len(), 0);
+        assert_eq!(bb.free_len(), 2);
+        wgr.commit(4);
+        assert_eq!(bb.occupied_len(), 4);
+        assert_eq!(bb.free_len(), 2);
+
+        // An open read grant still occupies the space
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.len(), 4);
+        rgr.release(4);
+        assert_eq!(cons.len(), 0);
+        assert_eq!(prod.space_remaining(), 5);
+
+        // Wrap early => | 1 | 2 | 3 | r | x | x |
+        // The two bytes at the end of the ring are skipped
+        let mut wgr = prod.grant_exact(3).unwrap();
+        assert_eq!(bb.occupied_len(), 0);
+        assert_eq!(bb.free_len(), 0);
+        wgr.copy_from_slice(&[1, 2, 3]);
+        wgr.commit(3);
+        assert_eq!(bb.occupied_len(), 3);
+        assert_eq!(bb.free_len(), 0);
+
+        // Drain across the wrap
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        rgr.release(2);
+        assert_eq!(cons.len(), 1);
+        assert_eq!(prod.space_remaining(), 4);
+
+        let rgr = cons.read().unwrap();
+        rgr.release(1);
+        assert_eq!(cons.len(), 0);
+        assert_eq!(prod.space_remaining(), 5);
+
+        // Fill the queue completely
+        let wgr = prod.grant_exact(3).unwrap();
+        wgr.commit(3);
+        let wgr = prod.grant_exact(2).unwrap();
+        wgr.commit(2);
+        assert_eq!(bb.occupied_len(), 5);
+        assert_eq!(bb.free_len(), 0);
+
+        // Drain everything, including the inverted part
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.combined_len(), 5);
+        rgr.release(5);
+        assert_eq!(bb.occupied_len(), 0);
+        assert_eq!(bb.free_len(), 5);
+    }
+
+    #[test]
+    fn send_slice_across_wrap() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(prod.send_slice(&[1, 2, 3, 4]), Ok(4));
+        cons.read().unwrap().release(3);
+
+        // Only two bytes are left at the end of the ring, so an exact grant
+        // of four bytes can't be made...
+        assert!(matches!(
+            prod.grant_exact(4),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // ...but a slice can be sent across the wrap point
+        assert_eq!(prod.send_slice(&[5, 6, 7, 8]), Ok(4));
+
+        // And read back across the wrap point in one call
+        let mut out = [0u8; 8];
+        assert_eq!(cons.read_into(&mut out), Ok(5));
+        assert_eq!(&out[..5], &[4, 5, 6, 7, 8]);
+
+        assert!(matches!(
+            cons.read_into(&mut out),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    fn send_slice_read_into_partial() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Empty slices never touch the queue, even with a grant in progress
+        let grant = prod.grant_exact(1).unwrap();
+        assert_eq!(prod.send_slice(&[]), Ok(0));
+        assert_eq!(prod.send_slice(&[1]), Err(BBQError::GrantInProgress));
+        drop(grant);
+        assert_eq!(cons.read_into(&mut []), Ok(0));
+
+        // Only as much as fits is written
+        assert_eq!(prod.send_slice(&[1, 2, 3, 4, 5, 6, 7]), Ok(6));
+        assert!(matches!(
+            prod.send_slice(&[8]),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // Only as much as fits is read
+        let mut out = [0u8; 4];
+        assert_eq!(cons.read_into(&mut out), Ok(4));
+        assert_eq!(out, [1, 2, 3, 4]);
+        assert_eq!(cons.read_into(&mut out), Ok(2));
+        assert_eq!(&out[..2], &[5, 6]);
+    }
+
+    #[test]
+    fn push_slice_contiguous() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(prod.push_slice(&[]), Ok(0));
+        assert_eq!(prod.push_slice(&[1, 2, 3, 4]), Ok(4));
+        cons.read().unwrap().release(3);
+
+        // Only the end of the ring is used, even though there's room after wrapping
+        assert_eq!(prod.push_slice(&[5, 6, 7]), Ok(2));
+        assert_eq!(prod.push_slice(&[7, 8]), Ok(2));
+
+        // The strict variant doesn't write anything if the slice doesn't fit
+        assert!(matches!(
+            prod.push_slice_all(&[9]),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[4, 5, 6]);
+        rgr.release(3);
+        assert_eq!(prod.push_slice_all(&[9, 10, 11]), Ok(()));
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn pop_slice_contiguous() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut out = [0u8; 4];
+
+        assert_eq!(cons.pop_slice(&mut []), Ok(0));
+        assert!(matches!(
+            cons.pop_slice(&mut out),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(cons.pop_slice(&mut out[..3]), Ok(3));
+        assert_eq!(&out[..3], &[1, 2, 3]);
+
+        // Wrap around, only the end of the ring is read
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7, 8]).unwrap();
+        assert!(matches!(
+            cons.pop_exact(&mut out),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        assert_eq!(cons.pop_slice(&mut out), Ok(3));
+        assert_eq!(&out[..3], &[4, 5, 6]);
+
+        // Nothing is released when not enough bytes are available
+        assert_eq!(
+            cons.pop_exact(&mut out[..3]),
+            Err(BBQError::InsufficientSize {
+                requested: 3,
+                available: 2
+            })
+        );
+        assert_eq!(cons.pop_exact(&mut out[..2]), Ok(()));
+        assert_eq!(&out[..2], &[7, 8]);
+    }
+
+    #[test]
+    fn discard() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(cons.discard(4), Ok(0));
+
+        prod.push_slice_all(&[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(cons.discard(3), Ok(3));
+
+        // Wrap around, skipping the last byte of the ring
+        prod.push_slice_all(&[6, 7]).unwrap();
+
+        // A read grant in progress
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.discard(1), Err(BBQError::GrantInProgress));
+        drop(rgr);
+
+        // Discard across the wrap point in a single call
+        assert_eq!(cons.discard(3), Ok(3));
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[7]);
+        rgr.release(1);
+
+        // Back to a non inverted queue
+        prod.push_slice_all(&[8, 9, 10]).unwrap();
+        assert_eq!(cons.discard(10), Ok(3));
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    fn drain_all() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(cons.drain_all(), Ok(0));
+
+        // A read grant in progress
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.drain_all(), Err(BBQError::GrantInProgress));
+        rgr.release(2);
+
+        // Wrap around, both parts are discarded
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7]).unwrap();
+        assert_eq!(cons.len(), 5);
+        assert_eq!(cons.drain_all(), Ok(5));
+        assert_eq!(cons.len(), 0);
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // The queue is usable again, and the full capacity is available
+        assert_eq!(prod.space_remaining(), 5);
+        prod.push_slice_all(&[8, 9]).unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[8, 9]);
+        rgr.release(2);
+        assert_eq!(cons.drain_all(), Ok(0));
+    }
+
+    #[test]
+    fn flush() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(cons.flush(), Ok(0));
+
+        // A read grant in progress
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(cons.flush(), Err(BBQError::GrantInProgress));
+        rgr.release(3);
+
+        // Fill the end of the ring, then wrap around
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7, 8]).unwrap();
+        assert!(bb.debug_positions().inverted);
+        assert_eq!(cons.len(), 5);
+
+        assert_eq!(cons.flush(), Ok(5));
+        assert_eq!(cons.len(), 0);
+        assert!(!bb.debug_positions().inverted);
+
+        // The pointers are back at the start, the whole capacity can be granted
+        let wgr = prod.grant_exact(6).unwrap();
+        wgr.commit(6);
+        assert_eq!(cons.flush(), Ok(6));
+
+        // With a write grant in progress, only the read pointer moves
+        prod.push_slice_all(&[1, 2]).unwrap();
+        let wgr = prod.grant_exact(2).unwrap();
+        assert_eq!(cons.flush(), Ok(2));
+        wgr.commit(2);
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr.len(), 2);
+        rgr.release(2);
+        assert_eq!(
+            (bb.debug_positions().read, bb.debug_positions().write),
+            (4, 4)
+        );
+    }
+
+    #[test]
+    fn grant_exact_overwrite() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        fn overwrite<B: bbqueue::StorageProvider>(prod: &mut bbqueue::Producer<B>, data: &[u8]) {
+            let mut wgr = prod.grant_exact_overwrite(data.len()).unwrap();
+            wgr.copy_from_slice(data);
+            wgr.commit(data.len());
+        }
+
+        // Enough room, nothing is discarded
+        overwrite(&mut prod, &[1, 2, 3, 4]);
+        assert_eq!(cons.len(), 4);
+
+        // Not possible even on an empty queue, nothing is discarded
+        assert_eq!(
+            prod.grant_exact_overwrite(7).unwrap_err(),
+            BBQError::RequestTooLarge
+        );
+        assert_eq!(cons.len(), 4);
+
+        // No bytes can be discarded while a read grant is in progress
+        let rgr = cons.read().unwrap();
+        assert_eq!(
+            prod.grant_exact_overwrite(3).unwrap_err(),
+            BBQError::GrantInProgress
+        );
+        drop(rgr);
+
+        // Fill the queue, then wrap around, discarding the four oldest bytes
+        overwrite(&mut prod, &[5, 6]);
+        overwrite(&mut prod, &[7, 8, 9]);
+        assert_eq!(cons.len(), 5);
+
+        // Inverted, the end of the ring is discarded
+        overwrite(&mut prod, &[10, 11, 12]);
+        assert_eq!(cons.len(), 6);
+
+        // Wrap around again, discarding the start of the ring
+        overwrite(&mut prod, &[13, 14]);
+        assert_eq!(cons.len(), 5);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[10, 11, 12]);
+        rgr.release_all();
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[13, 14]);
+        rgr.release_all();
+        assert_eq!(cons.len(), 0);
+    }
+
+    #[test]
+    fn high_water_mark() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        assert_eq!(bb.high_water_mark(), 0);
+
+        prod.grant_exact(3).unwrap().commit(3);
+        assert_eq!(bb.high_water_mark(), 3);
+
+        // Releasing doesn't lower the mark
+        cons.read().unwrap().release(2);
+        prod.grant_exact(2).unwrap().commit(2);
+        assert_eq!(bb.high_water_mark(), 3);
+
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(bb.high_water_mark(), 4);
+        cons.read().unwrap().release(1);
+        assert_eq!(bb.high_water_mark(), 4);
+
+        bb.reset_high_water_mark();
+        assert_eq!(bb.high_water_mark(), 3);
+        drop(prod.grant_exact(1).unwrap());
+        assert_eq!(bb.high_water_mark(), 3);
+
+        // Inverted, the bytes on both sides of the ring are counted
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(bb.high_water_mark(), 4);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn aligned_grants() {
+        const ALIGN: usize = 32;
+
+        let bb: BBQueue<AlignedStorageProvider<1024, ALIGN>> = BBQueue::new_static_aligned();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut trng = thread_rng();
+        let (mut tx_ctr, mut rx_ctr) = (0u8, 0u8);
+
+        for _ in 0..10_000 {
+            // Mix aligned and unaligned grants of random sizes
+            let sz = trng.gen_range(1, 200);
+            let grant = if trng.gen() {
+                prod.grant_exact_aligned(sz, ALIGN).map(|gr| {
+                    assert_eq!(gr.as_ptr() as usize % ALIGN, 0);
+                    gr
+                })
+            } else {
+                prod.grant_exact(sz)
+            };
+
+            if let Ok(mut gr) = grant {
+                assert_eq!(gr.len(), sz);
+                for by in gr.iter_mut() {
+                    *by = tx_ctr;
+                    tx_ctr = tx_ctr.wrapping_add(1);
+                }
+                gr.commit(sz);
+            }
+
+            // The skipped bytes are never read
+            if let Ok(gr) = cons.read() {
+                let len = trng.gen_range(1, gr.len() + 1);
+                for by in &gr[..len] {
+                    assert_eq!(*by, rx_ctr);
+                    rx_ctr = rx_ctr.wrapping_add(1);
+                }
+                gr.release(len);
+            }
+        }
+    }
+
+    #[test]
+    fn aligned_grant_misaligned_storage() {
+        #[repr(align(4))]
+        struct Aligned([u8; 65]);
+
+        let mut memory = Aligned([0; 65]);
+        let bb = BBQueue::new_from_slice(&mut memory.0[1..]);
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        assert_eq!(
+            prod.grant_exact_aligned(4, 4).unwrap_err(),
+            BBQError::Misaligned
+        );
+
+        // Grants don't need to be aligned by default
+        assert!(prod.grant_exact(4).is_ok());
+    }
+
+    #[test]
+    fn grant_exact_too_large() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Can never succeed, even on an empty queue
+        assert_eq!(prod.grant_exact(7).unwrap_err(), BBQError::RequestTooLarge);
+        assert_eq!(
+            prod.grant_exact_spin(7, || panic!("should not retry"))
+                .unwrap_err(),
+            BBQError::RequestTooLarge
+        );
+
+        // Permanent errors are reported before transient ones
+        let wgr = prod.grant_exact(6).unwrap();
+        assert_eq!(prod.grant_exact(7).unwrap_err(), BBQError::RequestTooLarge);
+        assert_eq!(prod.grant_exact(1).unwrap_err(), BBQError::GrantInProgress);
+        wgr.commit(6);
+
+        // A full queue is only a transient error
+        assert!(matches!(
+            prod.grant_exact(6),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        cons.read().unwrap().release(6);
+    }
+
+    #[test]
+    fn grant_exact_spin_rewinds() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Move the write pointer to the middle of the ring
+        prod.grant_exact(3).unwrap().commit(3);
+        assert_eq!(bb.debug_positions().write, 3);
+
+        // Fits neither at the end of the ring nor before the write pointer, until
+        // the consumer drains the queue
+        let mut spins = 0;
+        let wgr = prod
+            .grant_exact_spin(5, || {
+                spins += 1;
+                cons.read().unwrap().release(3);
+            })
+            .unwrap();
+        assert_eq!(spins, 1);
+        assert_eq!(wgr.len(), 5);
+        wgr.commit(5);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr.len(), 5);
+        rgr.release(5);
+    }
+
+    #[test]
+    fn try_variants() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Empty queue
+        assert!(cons.try_read().is_none());
+
+        // Grant in progress
+        let wgr = prod.try_grant_exact(4).unwrap();
+        assert!(prod.try_grant_exact(1).is_none());
+        wgr.commit(4);
+
+        // Full queue
+        assert!(prod.try_grant_exact(3).is_none());
+
+        let rgr = cons.try_read().unwrap();
+        assert_eq!(rgr.len(), 4);
+        assert!(cons.try_read().is_none());
+        rgr.release(4);
+
+        assert!(cons.try_read().is_none());
+        assert!(prod.try_grant_exact(3).is_some());
+    }
+
+    #[test]
+    fn grant_exact_zeroed() {
+        let mut buf = [0xAA; 8];
+        {
+            let bb = BBQueue::new_from_slice(&mut buf);
+            let (mut prod, mut cons) = bb.try_split().unwrap();
+
+            // Splitting zeroes the buffer, leave stale data in it
+            let mut wgr = prod.grant_exact(6).unwrap();
+            wgr.fill(0xAA);
+            wgr.commit(6);
+            cons.read().unwrap().release(6);
+
+            // Wraps around, only the granted bytes are zeroed
+            let mut wgr = prod.grant_exact_zeroed(3).unwrap();
+            assert_eq!(&*wgr, &[0, 0, 0]);
+            wgr[0] = 1;
+            wgr.commit(3);
+
+            // Committing more than was written doesn't expose stale data
+            let rgr = cons.read().unwrap();
+            assert_eq!(&*rgr, &[1, 0, 0]);
+            rgr.release(3);
+
+            // Errors are the same as for `grant_exact`
+            let wgr = prod.grant_exact_zeroed(2).unwrap();
+            assert_eq!(prod.grant_exact_zeroed(1), Err(BBQError::GrantInProgress));
+            wgr.commit(0);
+            assert!(matches!(
+                prod.grant_exact_zeroed(8),
+                Err(BBQError::InsufficientSize { .. })
+            ));
+        }
+
+        assert_eq!(buf, [1, 0, 0, 0, 0, 0xAA, 0, 0]);
+    }
+
+    #[test]
+    fn skipped_bytes() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        assert_eq!(bb.skipped_len(), 0);
+
+        prod.grant_exact(5).unwrap().commit(5);
+        cons.read().unwrap().release(5);
+        assert_eq!(bb.skipped_len(), 0);
+
+        // Doesn't fit before the end of the ring
+        assert!(matches!(
+            prod.grant_exact_no_wrap(4),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        assert!(matches!(
+            prod.grant_exact_no_wrap(2),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // Fits in the last byte
+        let wgr = prod.grant_exact_no_wrap(1).unwrap();
+        assert_eq!(prod.grant_exact_no_wrap(1), Err(BBQError::GrantInProgress));
+        wgr.commit(0);
+
+        // Wrapping grant skips the last byte, until it is committed
+        let wgr = prod.grant_exact(4).unwrap();
+        assert_eq!(bb.skipped_len(), 0);
+        wgr.commit(4);
+        assert_eq!(bb.skipped_len(), 1);
+        assert_eq!(bb.occupied_len(), 4);
+
+        // Inverted, the write pointer can't catch up to the read pointer
+        assert!(matches!(
+            prod.grant_exact_no_wrap(1),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        prod.grant_exact_no_wrap(0).unwrap().commit(0);
+        assert_eq!(bb.skipped_len(), 1);
+
+        // The reader wraps around
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr.len(), 4);
+        assert_eq!(bb.skipped_len(), 0);
+        rgr.release(4);
+        assert_eq!(bb.skipped_len(), 0);
+        assert_eq!(prod.grant_exact_no_wrap(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn clear() {
+        let mut bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        #[cfg(feature = "async")]
+        bb.set_read_watermark(2).unwrap();
+
+        {
+            // Wrap around, and leak grants on both sides
+            let (mut prod, mut cons) = bb.try_split().unwrap();
+            prod.grant_exact(5).unwrap().commit(5);
+            cons.read().unwrap().release(4);
+            prod.grant_exact(3).unwrap().commit(3);
+            core::mem::forget(prod.grant_exact(0).unwrap());
+            core::mem::forget(cons.read().unwrap());
+        }
+        assert!(bb.try_split().is_err());
+
+        bb.clear();
+        assert_eq!(bb.occupied_len(), 0);
+        assert_eq!(bb.free_len(), 6);
+        assert_eq!(bb.stats(), Stats::default());
+        assert!(format!("{:?}", bb).contains(
+            "write: 0, read: 0, last: 0, reserve: 0, inverted: false, occupied: 0, free: 6, \
+             read_in_progress: false, write_in_progress: false, already_split: false"
+        ));
+
+        // The storage is zeroed, and the queue is usable again
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut wgr = prod.grant_exact(6).unwrap();
+        assert_eq!(&*wgr, &[0; 6]);
+        wgr.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        wgr.commit(6);
+        assert_eq!(&*cons.read().unwrap(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn stats() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        assert_eq!(bb.stats(), Stats::default());
+
+        prod.grant_exact(4).unwrap().commit(4);
+        assert!(matches!(
+            prod.grant_exact(3),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        prod.grant_max_remaining(4).unwrap().commit(1);
+        cons.read().unwrap().release(3);
+
+        // Grants in progress and empty commits or releases aren't counted
+        let wgr = prod.grant_exact(1).unwrap();
+        assert_eq!(prod.grant_exact(1).unwrap_err(), BBQError::GrantInProgress);
+        wgr.commit(0);
+        cons.read().unwrap().release(0);
+
+        // The overwriting grant is counted once, and the discarded bytes are released
+        prod.grant_exact_overwrite(4).unwrap().commit(4);
+
+        assert_eq!(
+            bb.stats(),
+            Stats {
+                committed: 9,
+                released: 5,
+                grants_granted: 4,
+                grants_denied: 1,
+                max_occupied: 5,
+            }
+        );
+        assert_eq!(bb.stats().committed, 5 + bb.occupied_len() as u64);
+    }
+
+    #[test]
+    fn commit_release_all_wrap() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let mut wgr = prod.grant_exact(4).unwrap();
+        wgr.copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit_all();
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3, 4]);
+        rgr.release_all();
+
+        // Wrap around, committing the full grant marks the skipped end of the ring
+        let mut wgr = prod.grant_exact(3).unwrap();
+        wgr.copy_from_slice(&[5, 6, 7]);
+        wgr.commit_all();
+        assert!(format!("{:?}", bb).contains("write: 3, read: 4, last: 4"));
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[5, 6, 7]);
+        rgr.release_all();
+
+        // Passing the old end of the ring releases it again
+        let mut wgr = prod.grant_exact(3).unwrap();
+        wgr.copy_from_slice(&[8, 9, 10]);
+        wgr.commit_all();
+        assert!(format!("{:?}", bb).contains("write: 6, read: 3, last: 6"));
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[8, 9, 10]);
+        rgr.release_all();
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    fn debug_state() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Wrap around, skipping the last byte of the ring, with a write grant in progress
+        prod.grant_exact(5).unwrap().commit(5);
+        cons.read().unwrap().release(4);
+        prod.grant_exact(2).unwrap().commit(2);
+        let _grant = prod.grant_exact(1).unwrap();
+
+        let state = format!("{:?}", bb);
+        assert_eq!(
+            state,
+            "BBQueue { capacity: 6, write: 2, read: 4, last: 5, reserve: 3, inverted: true, \
+             occupied: 3, free: 0, read_in_progress: false, write_in_progress: true, \
+             already_split: true, closed: false }"
+        );
+    }
+
+    #[test]
+    fn debug_halves_and_grants() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let queue = format!("queue: {:p}", &bb);
+
+        let prod_dbg = format!("{:?}", prod);
+        assert!(prod_dbg.starts_with("Producer {"));
+        assert!(prod_dbg.contains(&queue));
+        assert!(prod_dbg.contains("capacity: 6, reborrowed: false"));
+        assert!(format!("{:?}", cons.reborrow()).starts_with("Consumer {"));
+        assert!(format!("{:?}", prod.reborrow()).contains("reborrowed: true"));
+
+        let wgr = prod.grant_exact(4).unwrap();
+        assert!(format!("{:?}", wgr).ends_with("offset: 0, len: 4, to_commit: 0 }"));
+        wgr.commit(4);
+        let mut rgr = cons.read().unwrap();
+        rgr.to_release(3);
+        let rgr_dbg = format!("{:?}", rgr);
+        assert!(rgr_dbg.starts_with("GrantR {"));
+        assert!(rgr_dbg.contains(&queue));
+        assert!(rgr_dbg.ends_with("offset: 0, len: 4, to_release: 3 }"));
+        drop(rgr);
+
+        // The offset follows the write position
+        let wgr = prod.grant_max_remaining(4).unwrap();
+        assert!(format!("{:?}", wgr).ends_with("offset: 4, len: 2, to_commit: 0 }"));
+        wgr.commit(2);
+
+        // Wraps around to the start of the ring
+        let wgr = prod.grant_exact(2).unwrap();
+        assert!(format!("{:?}", wgr).ends_with("offset: 0, len: 2, to_commit: 0 }"));
+        wgr.commit(2);
+
+        let rgr = cons.split_read().unwrap();
+        assert!(format!("{:?}", rgr).ends_with("offset: 3, len1: 3, len2: 2, to_release: 0 }"));
+    }
+
+    #[test]
+    fn new_const() {
+        use bbqueue::{ConstStorageProvider, StorageProvider};
+
+        static BB: BBQueue<StaticStorageProvider<6>> =
+            BBQueue::new_const(StaticStorageProvider::new());
+        const fn aligned() -> BBQueue<AlignedStorageProvider<8, 4>, u8> {
+            BBQueue::new_const(AlignedStorageProvider::new())
+        }
+
+        assert_eq!(BB.capacity(), 6);
+        let (mut prod, mut cons) = BB.try_split().unwrap();
+        prod.grant_exact(6).unwrap().commit(6);
+        assert_eq!(cons.read().unwrap().len(), 6);
+
+        let bb = aligned();
+        assert_eq!(bb.capacity(), 8);
+        let (mut prod, _cons) = bb.try_split().unwrap();
+        assert!(prod.grant_exact_aligned(4, 4).is_ok());
+
+        // Same as the runtime capacity
+        assert_eq!(
+            <StaticStorageProvider<6> as ConstStorageProvider>::CAPACITY,
+            StaticStorageProvider::<6>::new().capacity()
+        );
+        assert_eq!(
+            <AlignedStorageProvider<8, 4> as ConstStorageProvider>::CAPACITY,
+            AlignedStorageProvider::<8, 4>::new().capacity()
+        );
+    }
+
+    #[test]
+    fn static_initialized() {
+        static BB: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static_initialized();
+        assert!(format!("{:?}", BB).contains("last: 6"));
+
+        // Runs the same sequence on a queue, recording what was granted and read
+        fn run(bb: &BBQueue<StaticStorageProvider<6>>) -> Vec<usize> {
+            let (mut prod, mut cons) = bb.try_split().unwrap();
+            let mut seen = vec![];
+
+            assert!(cons.read().is_err());
+            assert!(cons.split_read().is_err());
+            seen.push(bb.occupied_len());
+
+            // Fill, drain, and wrap around a few times
+            let steps = [(4, 4, 3), (2, 2, 2), (3, 2, 1), (5, 4, 4), (1, 1, 1)];
+            for (grant, commit, release) in steps {
+                let wgr = prod.grant_max_remaining(grant).unwrap();
+                seen.push(wgr.len());
+                wgr.commit(commit);
+                seen.push(bb.occupied_len());
+
+                let rgr = cons.split_read().unwrap();
+                seen.push(rgr.combined_len());
+                rgr.release(release);
+                seen.push(bb.occupied_len());
+            }
+            seen
+        }
+
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let expected = run(&bb);
+        assert_eq!(run(&BB), expected);
+
+        // Smaller index types start out at the end of the ring too
+        static BB8: BBQueue<StaticStorageProvider<255>, u8> = BBQueue::new_static_initialized();
+        assert!(format!("{:?}", BB8).contains("last: 255"));
+        let (mut prod, mut cons) = BB8.try_split().unwrap();
+        prod.grant_exact(255).unwrap().commit(255);
+        assert_eq!(cons.read().unwrap().len(), 255);
+    }
+
+    #[test]
+    fn debug_positions() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let positions = bb.debug_positions();
+        assert_eq!(
+            (positions.write, positions.read, positions.reserve),
+            (0, 0, 0)
+        );
+        assert!(!positions.inverted);
+
+        // Same state as `debug_state`
+        prod.grant_exact(5).unwrap().commit(5);
+        cons.read().unwrap().release(4);
+        prod.grant_exact(2).unwrap().commit(2);
+        let grant = prod.grant_exact(1).unwrap();
+
+        assert_eq!(
+            bb.debug_positions(),
+            Positions {
+                write: 2,
+                read: 4,
+                last: 5,
+                reserve: 3,
+                inverted: true,
+            }
+        );
+
+        // Reading past `last` brings the queue out of the inverted state
+        drop(grant);
+        cons.read().unwrap().release(1);
+        cons.read().unwrap().release(2);
+        let positions = bb.debug_positions();
+        assert_eq!((positions.write, positions.read), (2, 2));
+        assert!(!positions.inverted);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn provider_names() {
+        use bbqueue::{SliceBufferProvider, SliceStorageProvider, StaticBufferProvider};
+
+        fn roundtrip<B: bbqueue::StorageProvider>(bb: BBQueue<B>) {
+            let (mut prod, mut cons) = bb.try_split().unwrap();
+            prod.grant_exact(1).unwrap().commit(1);
+            cons.read().unwrap().release(1);
+        }
+
+        // Current names
+        roundtrip(BBQueue::<StaticStorageProvider<6>>::new_static());
+        roundtrip(BBQueue::new(StaticStorageProvider::<6>::new()));
+        let mut mem = [0u8; 6];
+        roundtrip::<SliceStorageProvider>(BBQueue::new_from_slice(&mut mem));
+
+        // Deprecated names
+        roundtrip(BBQueue::<StaticBufferProvider<6>>::new_static());
+        roundtrip(BBQueue::new(StaticBufferProvider::<6>::new()));
+        let mut mem = [0u8; 6];
+        roundtrip(BBQueue::new(SliceBufferProvider::new(&mut mem)));
+    }
+
+    #[test]
+    fn read_exact() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert!(matches!(
+            cons.read_exact(1),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        let mut wgr = prod.grant_exact(4).unwrap();
+        wgr.copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit(4);
+
+        assert!(matches!(
+            cons.read_exact(5),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // A failed attempt leaves no grant in progress, and releases nothing
+        let rgr = cons.read_exact(3).unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        assert_eq!(cons.read_exact(1).unwrap_err(), BBQError::GrantInProgress);
+        rgr.release(3);
+
+        // Fill the end of the ring, then wrap around
+        let mut wgr = prod.grant_exact(2).unwrap();
+        wgr.copy_from_slice(&[5, 6]);
+        wgr.commit(2);
+        let mut wgr = prod.grant_exact(2).unwrap();
+        wgr.copy_from_slice(&[7, 8]);
+        wgr.commit(2);
+
+        // Five bytes are committed, but only three are contiguous
+        assert!(matches!(
+            cons.read_exact(4),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+        let rgr = cons.read_exact(3).unwrap();
+        assert_eq!(&*rgr, &[4, 5, 6]);
+        rgr.release(3);
+
+        let rgr = cons.read_exact(2).unwrap();
+        assert_eq!(&*rgr, &[7, 8]);
+    }
+
+    #[test]
+    fn split_arc() {
+        use std::sync::Arc;
+
+        let bb: Arc<BBQueue<StaticStorageProvider<6>>> = Arc::new(BBQueue::new_static());
+        let (mut prod, mut cons) = bb.clone().try_split_arc().unwrap();
+        assert_eq!(
+            bb.clone().try_split_arc().err(),
+            Some(BBQError::AlreadySplit)
+        );
+
+        prod.grant_exact(4).unwrap().commit(4);
+        assert_eq!(cons.len(), 4);
+        assert_eq!(prod.space_remaining(), 2);
+        cons.read().unwrap().release(4);
+
+        // Dropping one half is not enough to split again
+        drop(prod);
+        assert!(bb.try_split().is_err());
+
+        // But dropping both is, and the queue is reset
+        drop(cons);
+        let (_prod, mut cons) = bb.try_split().unwrap();
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    fn close() {
+        let mut bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        assert!(!cons.is_closed());
+
+        prod.push_slice_all(&[1, 2, 3]).unwrap();
+
+        // A reborrowed producer can't close the queue under the original one
+        assert!(prod.reborrow().close().is_err());
+        assert!(!cons.is_closed());
+
+        prod.close().unwrap();
+        assert!(cons.is_closed());
+
+        // The bytes committed before closing are still there
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        rgr.release(3);
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        // Clearing reopens the queue
+        bb.clear();
+        let (_prod, cons) = bb.try_split().unwrap();
+        assert!(!cons.is_closed());
+    }
+
+    #[test]
+    fn close_arc() {
+        use std::sync::Arc;
+
+        let bb: Arc<BBQueue<StaticStorageProvider<6>>> = Arc::new(BBQueue::new_static());
+        let (prod, cons) = bb.clone().try_split_arc().unwrap();
+
+        prod.close();
+        assert!(cons.is_closed());
+
+        // Still released once both halves are gone
+        drop(cons);
+        let (_prod, cons) = bb.try_split().unwrap();
+        assert!(!cons.is_closed());
+    }
 
-        // NOTE: The data we just added isn't available yet,
-        // since it has wrapped around
-        assert_eq!(&*a, &[11, 12]);
+    #[test]
+    fn reborrow() {
+        use bbqueue::{Consumer, Producer};
 
-        a.release(2);
+        fn write(mut prod: Producer<'_, StaticStorageProvider<6>>, data: &[u8]) {
+            let mut wgr = prod.grant_exact(data.len()).unwrap();
+            wgr.copy_from_slice(data);
+            wgr.commit(data.len());
+        }
 
-        // And now we can see it
-        let r = cons.read().unwrap();
-        assert_eq!(&*r, &[21, 22, 23]);
-        r.release(0);
+        fn read(mut cons: Consumer<'_, StaticStorageProvider<6>>) -> Vec<u8> {
+            let rgr = cons.read().unwrap();
+            let data = rgr.to_vec();
+            rgr.release(data.len());
+            data
+        }
 
-        // Ask for something way too big
-        assert!(prod.grant_exact(10).is_err());
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        write(prod.reborrow(), &[1, 2]);
+        write(prod.reborrow(), &[3]);
+        assert_eq!(read(cons.reborrow()), vec![1, 2, 3]);
+
+        // Grants from a reborrow follow the usual rules
+        let mut reborrowed = prod.reborrow();
+        let wgr = reborrowed.grant_exact(2).unwrap();
+        assert!(matches!(
+            reborrowed.grant_exact(1),
+            Err(BBQError::GrantInProgress)
+        ));
+        wgr.commit(2);
+        assert_eq!(read(cons.reborrow()), vec![0, 0]);
+
+        // The originals would still be alive after releasing the reborrows
+        assert!(bb.try_release(prod.reborrow(), cons.reborrow()).is_err());
+        assert!(bb
+            .force_release_and_clear(prod.reborrow(), cons.reborrow())
+            .is_err());
+        assert!(matches!(bb.try_split(), Err(BBQError::AlreadySplit)));
+
+        assert!(bb.try_release(prod, cons).is_ok());
+        assert!(bb.try_split().is_ok());
     }
 
     #[test]
@@ -278,7 +1450,7 @@ mod tests {
         wgrant.commit(10);
         // 1 frame in queue
 
-        // 20 + 2 used (assuming u64 test platform)
+        // 20 + 2 used
         let mut wgrant = prod.grant(10).unwrap();
         assert_eq!(wgrant.len(), 10);
         for (idx, i) in wgrant.iter_mut().enumerate() {
@@ -306,7 +1478,7 @@ mod tests {
         rgrant.release();
         // 0 frames in queue
 
-        // 10 + 1 used (assuming u64 test platform)
+        // 10 + 1 used
         let mut wgrant = prod.grant(10).unwrap();
         assert_eq!(wgrant.len(), 10);
         for (idx, i) in wgrant.iter_mut().enumerate() {
@@ -327,7 +1499,7 @@ mod tests {
         // 0 frames in queue
 
         // No more frames!
-        assert!(cons.read().is_none());
+        assert!(cons.try_read().is_none());
     }
 
     #[test]
@@ -424,6 +1596,469 @@ mod tests {
     }
 
     #[test]
+    fn split_iter() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Empty second buffer
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        let rgr = cons.split_read().unwrap();
+        assert!(rgr.iter().eq(&[1, 2, 3, 4]));
+        assert_eq!(rgr.bytes().collect::<Vec<u8>>(), vec![1, 2, 3, 4]);
+        rgr.release(3);
+
+        // Wrap around, both parts are iterated in order
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7, 8]).unwrap();
+        let mut rgr = cons.split_read().unwrap();
+        for by in rgr.iter_mut() {
+            *by *= 10;
+        }
+        assert_eq!(rgr.bufs(), (&[40, 50, 60][..], &[70, 80][..]));
+        assert_eq!(rgr.bytes().collect::<Vec<u8>>(), vec![40, 50, 60, 70, 80]);
+        rgr.release_all();
+
+        // Nothing left to read
+        assert!(cons.split_read().is_err());
+    }
+
+    #[test]
+    fn split_copy_to_slice() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        cons.read().unwrap().release(3);
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7, 8]).unwrap();
+
+        let rgr = cons.split_read().unwrap();
+        let mut out = [0u8; 6];
+
+        // Shorter than the first part, within the second part, larger than both
+        assert_eq!(rgr.copy_to_slice(&mut out[..2]), 2);
+        assert_eq!(&out[..2], &[4, 5]);
+        assert_eq!(rgr.copy_to_slice(&mut out[..4]), 4);
+        assert_eq!(&out[..4], &[4, 5, 6, 7]);
+        assert_eq!(rgr.copy_to_slice(&mut out), 5);
+        assert_eq!(out, [4, 5, 6, 7, 8, 0]);
+        assert_eq!(rgr.copy_to_slice(&mut []), 0);
+
+        // Nothing was released
+        rgr.release(0);
+        assert_eq!(cons.split_read().unwrap().combined_len(), 5);
+    }
+
+    #[test]
+    fn split_write_boundaries() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Not wrapped, only the end of the ring
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        assert_eq!(wgr.bufs_mut().0.len(), 6);
+        assert_eq!(wgr.bufs_mut().1.len(), 0);
+        wgr.bufs_mut().0[..4].copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit(4);
+        cons.read().unwrap().release(3);
+
+        // A commit within the first region doesn't wrap
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        assert_eq!(wgr.combined_len(), 4);
+        wgr.bufs_mut().0[0] = 5;
+        wgr.commit(1);
+        assert!(format!("{:?}", bb).contains("write: 5, read: 3"));
+
+        // A commit of exactly the first region ends at the end of the ring
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        assert_eq!(wgr.combined_len(), 3);
+        wgr.bufs_mut().0[0] = 6;
+        wgr.commit(1);
+        assert!(format!("{:?}", bb).contains("write: 6, read: 3, last: 6"));
+
+        // Only the start of the ring, stopping before `read`
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        assert_eq!(wgr.bufs_mut().0.len(), 0);
+        assert_eq!(wgr.bufs_mut().1.len(), 2);
+        wgr.bufs_mut().1.copy_from_slice(&[7, 8]);
+        wgr.commit_all();
+
+        // Full, write never reaches read in the inverted state
+        assert!(format!("{:?}", bb).contains("write: 2, read: 3"));
+        assert!(matches!(
+            prod.grant_split_remaining(1),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.bufs(), (&[4, 5, 6][..], &[7, 8][..]));
+        rgr.release(2);
+
+        // Inverted, only the space up to `read`
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        assert_eq!(wgr.bufs_mut().0.len(), 2);
+        assert_eq!(wgr.bufs_mut().1.len(), 0);
+        wgr.bufs_mut().0[0] = 9;
+        wgr.commit(1);
+
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.bufs(), (&[6][..], &[7, 8, 9][..]));
+        rgr.release_all();
+    }
+
+    #[test]
+    fn split_write_spill() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+        cons.read().unwrap().release(4);
+
+        // Spill into the second region on drop
+        let mut wgr = prod.grant_split_remaining(10).unwrap();
+        let (buf1, buf2) = wgr.bufs_mut();
+        assert_eq!((buf1.len(), buf2.len()), (2, 3));
+        buf1.copy_from_slice(&[5, 6]);
+        buf2.copy_from_slice(&[7, 8, 9]);
+        wgr.to_commit(3);
+        drop(wgr);
+        assert!(format!("{:?}", bb).contains("write: 1, read: 4, last: 6"));
+
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.bufs(), (&[5, 6][..], &[7][..]));
+        rgr.release_all();
+
+        // Dropping without committing commits nothing
+        drop(prod.grant_split_remaining(1).unwrap());
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn split_write_model() {
+        use std::collections::VecDeque;
+
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut model = VecDeque::new();
+        let mut trng = thread_rng();
+        let mut ctr = 0u8;
+
+        for _ in 0..10_000 {
+            if let Ok(mut wgr) = prod.grant_split_remaining(trng.gen_range(1, 80)) {
+                let used = trng.gen_range(0, wgr.combined_len() + 1);
+                let (buf1, buf2) = wgr.bufs_mut();
+                for by in buf1.iter_mut().chain(buf2.iter_mut()).take(used) {
+                    *by = ctr;
+                    model.push_back(ctr);
+                    ctr = ctr.wrapping_add(1);
+                }
+                wgr.commit(used);
+            }
+            assert_eq!(bb.occupied_len(), model.len());
+
+            if let Ok(rgr) = cons.split_read() {
+                let used = trng.gen_range(0, rgr.combined_len() + 1);
+                assert!(rgr.iter().eq(model.iter()));
+                model.drain(..used);
+                rgr.release(used);
+            }
+            assert_eq!(bb.occupied_len(), model.len());
+        }
+    }
+
+    /// Runs a random trace of grants, commits, reads and releases, checking the queue
+    /// against a `VecDeque` holding the committed bytes
+    fn model_trace<const N: usize>(seed: u64, steps: usize) {
+        use bbqueue::{GrantR, GrantW, SplitGrantR};
+        use std::collections::VecDeque;
+
+        enum Read<'a, const N: usize> {
+            Whole(GrantR<'a, StaticStorageProvider<N>>),
+            Split(SplitGrantR<'a, StaticStorageProvider<N>>),
+        }
+
+        let bb: BBQueue<StaticStorageProvider<N>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut model = VecDeque::new();
+        let mut wgrant: Option<GrantW<'_, StaticStorageProvider<N>>> = None;
+        let mut rgrant: Option<Read<'_, N>> = None;
+        let mut ctr = 0u8;
+
+        for step in 0..steps {
+            let ctx = format!(
+                "N={}, seed={}, step={}, {:?}",
+                N,
+                seed,
+                step,
+                bb.debug_positions()
+            );
+
+            match rng.gen_range(0, 6) {
+                // Write grants, checked against the free space
+                op @ (0 | 1) => {
+                    let largest = op == 1 && rng.gen();
+                    let sz = if largest { N } else { rng.gen_range(1, N + 1) };
+                    let free = N - model.len();
+                    let res = if op == 0 {
+                        prod.grant_exact(sz)
+                    } else if largest {
+                        prod.grant_largest()
+                    } else {
+                        prod.grant_max_remaining(sz)
+                    };
+
+                    match res {
+                        Ok(grant) => {
+                            assert!(wgrant.is_none(), "{}", ctx);
+                            assert!(!grant.is_empty() && grant.len() <= free, "{}", ctx);
+                            assert!(op == 1 || grant.len() == sz, "{}", ctx);
+                            assert!(grant.len() <= sz, "{}", ctx);
+                            wgrant = Some(grant);
+                        }
+                        Err(BBQError::GrantInProgress) => assert!(wgrant.is_some(), "{}", ctx),
+                        Err(BBQError::InsufficientSize { available, .. }) => {
+                            assert!(wgrant.is_none(), "{}", ctx);
+                            assert!(available < sz, "{}", ctx);
+                            // The reported size can actually be granted
+                            if available != 0 {
+                                drop(prod.grant_exact(available).expect(&ctx));
+                            } else {
+                                assert!(prod.grant_exact(1).is_err(), "{}", ctx);
+                            }
+                        }
+                        Err(e) => panic!("{}: {:?}", ctx, e),
+                    }
+                }
+                // Commits part of the write grant
+                2 => {
+                    if let Some(mut grant) = wgrant.take() {
+                        let used = rng.gen_range(0, grant.len() + 1);
+                        for by in grant[..used].iter_mut() {
+                            *by = ctr;
+                            model.push_back(ctr);
+                            ctr = ctr.wrapping_add(1);
+                        }
+                        grant.commit(used);
+                    }
+                }
+                // Read grants, checked against the committed bytes
+                op @ (3 | 4) => {
+                    let res = if op == 3 {
+                        cons.read().map(Read::Whole)
+                    } else {
+                        cons.split_read().map(Read::Split)
+                    };
+
+                    match res {
+                        Ok(Read::Whole(grant)) => {
+                            assert!(rgrant.is_none(), "{}", ctx);
+                            assert!(!grant.is_empty(), "{}", ctx);
+                            assert!(grant.iter().eq(model.iter().take(grant.len())), "{}", ctx);
+                            rgrant = Some(Read::Whole(grant));
+                        }
+                        Ok(Read::Split(grant)) => {
+                            assert!(rgrant.is_none(), "{}", ctx);
+                            // All the committed bytes are readable at once
+                            assert!(grant.iter().eq(model.iter()), "{}", ctx);
+                            rgrant = Some(Read::Split(grant));
+                        }
+                        Err(BBQError::GrantInProgress) => assert!(rgrant.is_some(), "{}", ctx),
+                        Err(BBQError::InsufficientSize { .. }) => {
+                            assert!(rgrant.is_none(), "{}", ctx);
+                            assert!(model.is_empty(), "{}", ctx);
+                        }
+                        Err(e) => panic!("{}: {:?}", ctx, e),
+                    }
+                }
+                // Releases part of the read grant
+                _ => match rgrant.take() {
+                    Some(Read::Whole(grant)) => {
+                        let used = rng.gen_range(0, grant.len() + 1);
+                        model.drain(..used);
+                        grant.release(used);
+                    }
+                    Some(Read::Split(grant)) => {
+                        let used = rng.gen_range(0, grant.combined_len() + 1);
+                        model.drain(..used);
+                        grant.release(used);
+                    }
+                    None => {}
+                },
+            }
+
+            assert_eq!(bb.occupied_len(), model.len(), "{}", ctx);
+
+            // Once drained, the queue is always writable again
+            if model.is_empty() && wgrant.is_none() && rgrant.is_none() {
+                drop(prod.grant_max_remaining(N).expect(&ctx));
+            }
+        }
+    }
+
+    #[test]
+    fn grant_max_remaining_near_full() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Write at the end of the ring, read at 1: only the byte at 0 is free, but
+        // it can't be used as write would catch up with read
+        prod.grant_exact(6).unwrap().commit(6);
+        cons.read().unwrap().release(1);
+        assert!(matches!(
+            prod.grant_max_remaining(4),
+            Err(BBQError::InsufficientSize { available: 0, .. })
+        ));
+
+        // Read at 2, a single byte at the start of the ring
+        cons.read().unwrap().release(1);
+        let wgr = prod.grant_max_remaining(4).unwrap();
+        assert_eq!(wgr.len(), 1);
+        wgr.commit(1);
+        assert!(prod.grant_max_remaining(4).is_err());
+
+        // Fully drained with write at the start of the ring, the end of the ring
+        // is usable again
+        cons.read().unwrap().release(4);
+        cons.read().unwrap().release(1);
+        assert_eq!(
+            cons.read().unwrap_err(),
+            BBQError::InsufficientSize {
+                requested: 1,
+                available: 0,
+            }
+        );
+        assert_eq!(prod.grant_max_remaining(6).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn grant_largest() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Empty, the whole ring
+        prod.grant_largest().unwrap().commit(6);
+        assert_eq!(cons.read().unwrap().len(), 6);
+
+        // Two bytes at the end, three at the beginning
+        cons.read().unwrap().release(4);
+        let wgr = prod.grant_largest().unwrap();
+        assert_eq!(wgr.len(), 3);
+        assert_eq!(bb.debug_positions().reserve, 3);
+
+        // Wrapping around while the end of the ring is unused: `last` holds the line
+        // at the old write position, even without committing anything
+        wgr.commit(0);
+        assert_eq!(bb.debug_positions().last, 6);
+        let mut wgr = prod.grant_largest().unwrap();
+        assert_eq!(wgr.len(), 3);
+        wgr.copy_from_slice(&[1, 2, 3]);
+        wgr.commit(3);
+
+        // Inverted, nothing left before the read position
+        assert!(prod.grant_largest().is_err());
+
+        // The skipped bytes are never read
+        assert_eq!(cons.read().unwrap().len(), 2);
+        cons.read().unwrap().release(2);
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        rgr.release(1);
+
+        // The end of the ring is the largest region again
+        let wgr = prod.grant_largest().unwrap();
+        assert_eq!(wgr.len(), 5);
+        wgr.commit(5);
+        assert!(matches!(
+            prod.grant_largest(),
+            Err(BBQError::InsufficientSize { available: 0, .. })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn model_check() {
+        for seed in 0..2_000 {
+            model_trace::<4>(seed, 100);
+            model_trace::<5>(seed, 100);
+            model_trace::<6>(seed, 100);
+            model_trace::<7>(seed, 100);
+            model_trace::<8>(seed, 100);
+        }
+    }
+
+    #[test]
+    fn split_release_across_wrap() {
+        use bbqueue::{Consumer, Producer};
+
+        type Storage = StaticStorageProvider<8>;
+
+        // Two bytes readable at the end of the ring, three at the beginning,
+        // with the last two bytes of the ring skipped
+        fn setup(bb: &BBQueue<Storage>) -> (Producer<'_, Storage>, Consumer<'_, Storage>) {
+            let (mut prod, mut cons) = bb.try_split().unwrap();
+            let mut wgr = prod.grant_exact(6).unwrap();
+            wgr.copy_from_slice(&[0, 1, 2, 3, 4, 5]);
+            wgr.commit(6);
+            cons.read().unwrap().release(4);
+            let mut wgr = prod.grant_exact(3).unwrap();
+            wgr.copy_from_slice(&[6, 7, 8]);
+            wgr.commit(3);
+            assert_eq!(
+                bb.debug_positions(),
+                Positions {
+                    write: 3,
+                    read: 4,
+                    last: 6,
+                    reserve: 3,
+                    inverted: true
+                }
+            );
+            (prod, cons)
+        }
+
+        // Exactly the first buffer, one more byte, and both buffers
+        for used in [2, 3, 5] {
+            let split: BBQueue<Storage> = BBQueue::new_static();
+            let (mut split_prod, mut split_cons) = setup(&split);
+            let seq: BBQueue<Storage> = BBQueue::new_static();
+            let (_seq_prod, mut seq_cons) = setup(&seq);
+
+            // Released at once
+            let rgr = split_cons.split_read().unwrap();
+            assert_eq!(rgr.bufs(), (&[4, 5][..], &[6, 7, 8][..]));
+            rgr.release(used);
+
+            // Released in two steps
+            let rgr = seq_cons.read().unwrap();
+            let first = rgr.len().min(used);
+            rgr.release(first);
+            if used > first {
+                seq_cons.read().unwrap().release(used - first);
+            }
+
+            assert_eq!(split.debug_positions(), seq.debug_positions());
+            assert_eq!(split.occupied_len(), 5 - used);
+
+            // The writer sees all the freed space, up to the end of the ring
+            let wgr = split_prod.grant_max_remaining(8).unwrap();
+            assert_eq!(wgr.len(), 5);
+            drop(wgr);
+
+            let rest: Vec<u8> = match split_cons.split_read() {
+                Ok(rgr) => rgr.iter().copied().collect(),
+                Err(_) => Vec::new(),
+            };
+            assert_eq!(rest, [4, 5, 6, 7, 8][used..]);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn split_read_sanity_check() {
         let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
         let (mut prod, mut cons) = bb.try_split().unwrap();
@@ -472,7 +2107,7 @@ mod tests {
             } else if second.len() == 1 {
                 assert_eq!(second[0], j);
             } else {
-                assert!(false, "wrong len");
+                panic!("wrong len");
             }
 
             #[cfg(feature = "extra-verbose")]
@@ -484,4 +2119,579 @@ mod tests {
             println!("FINSH: {:?}", bb);
         }
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn split_read_small_index() {
+        let bb: BBQueue<StaticStorageProvider<200>, u8> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Smaller pointers take less space
+        assert!(
+            core::mem::size_of::<BBQueue<StaticStorageProvider<200>, u8>>()
+                < core::mem::size_of::<BBQueue<StaticStorageProvider<200>>>()
+        );
+
+        const ITERS: usize = 10000;
+        let mut rng = rand::thread_rng();
+        let mut next_wr = 0u8;
+        let mut next_rd = 0u8;
+
+        for _ in 0..ITERS {
+            // Write a random amount of data, wrapping around the ring
+            if let Ok(mut wgr) = prod.grant_max_remaining(rng.gen_range(1, 100)) {
+                for b in wgr.iter_mut() {
+                    *b = next_wr;
+                    next_wr = next_wr.wrapping_add(1);
+                }
+                wgr.commit_all();
+            }
+
+            // Read back a random amount, across the end of the ring
+            let rgr = cons.split_read().unwrap();
+            let used = rng.gen_range(0, rgr.combined_len() + 1);
+            for b in rgr.bytes().take(used) {
+                assert_eq!(b, next_rd);
+                next_rd = next_rd.wrapping_add(1);
+            }
+            rgr.release(used);
+            assert!(bb.occupied_len() <= 200);
+        }
+
+        // Framed and typed modes work too
+        while let Ok(rgr) = cons.split_read() {
+            let len = rgr.combined_len();
+            rgr.release(len);
+        }
+        assert!(bb.try_release(prod, cons).is_ok());
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+        prod.grant(150).unwrap().commit(150);
+        assert_eq!(cons.read().unwrap().len(), 150);
+    }
+
+    #[test]
+    fn u16_index() {
+        let mut buf = [0; 1000];
+        let bb = BBQueue::<_, u16>::new_with_index(bbqueue::SliceStorageProvider::new(&mut buf));
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.grant_exact(999).unwrap().commit(999);
+        cons.read().unwrap().release(998);
+        prod.grant_exact(997).unwrap().commit(997);
+        assert_eq!(bb.occupied_len(), 998);
+        assert_eq!(cons.split_read().unwrap().combined_len(), 998);
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity of the queue is too large for its index type")]
+    fn index_too_small() {
+        let _bb: BBQueue<StaticStorageProvider<256>, u8> = BBQueue::new_static();
+    }
+
+    #[test]
+    fn static_mut_buf() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // The lent buffer may be used until the grant is committed
+        let mut wgr = prod.grant_exact(4).unwrap();
+        let buf = unsafe { wgr.as_static_mut_buf() };
+        buf.copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit(4);
+
+        assert_eq!(&*cons.read().unwrap(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the buffer of the grant was lent out by `as_static_mut_buf`")]
+    fn static_mut_buf_aliased() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        let mut wgr = prod.grant_exact(4).unwrap();
+        let _buf = unsafe { wgr.as_static_mut_buf() };
+        wgr[0] = 1;
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the buffer of the grant was lent out by `as_static_mut_buf`")]
+    fn static_mut_buf_lent_twice() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        let mut wgr = prod.grant_exact(4).unwrap();
+        let _buf = unsafe { wgr.as_static_mut_buf() };
+        let _buf = unsafe { wgr.as_static_mut_buf() };
+    }
+
+    #[test]
+    fn grant_writer() {
+        use core::fmt::Write;
+
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Text that does not fit is not truncated
+        let mut wgr = prod.grant_exact(8).unwrap();
+        let mut writer = wgr.writer();
+        let text = "too long for the grant";
+        assert!(write!(writer, "{text}").is_err());
+        assert_eq!(writer.written(), 0);
+        assert_eq!(writer.remaining(), 8);
+        let written = writer.written();
+        wgr.commit(written);
+        assert!(cons.read().is_err());
+
+        // The last argument does not fit, what was written before is kept
+        let mut wgr = prod.grant_exact(8).unwrap();
+        let mut writer = wgr.writer();
+        let (num, text) = (1234, "overflow");
+        assert!(write!(writer, "{num} {text}").is_err());
+        assert_eq!(writer.written(), 5);
+        wgr.abort();
+        assert!(cons.read().is_err());
+
+        let mut wgr = prod.grant_exact(8).unwrap();
+        let mut writer = wgr.writer();
+        let (text, num) = ("ab", 42);
+        write!(writer, "{text}:{num}").unwrap();
+        assert_eq!(writer.written(), 5);
+        let written = writer.written();
+        wgr.commit(written);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, b"ab:42");
+        rgr.release(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity of the queue must not be zero")]
+    fn zero_capacity_static() {
+        let _bb: BBQueue<StaticStorageProvider<0>> = BBQueue::new_static();
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity of the queue must not be zero")]
+    fn zero_capacity_slice() {
+        let _bb = BBQueue::new_from_slice(&mut []);
+    }
+
+    #[test]
+    fn read_until() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert!(matches!(
+            cons.read_until(b'\n'),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        prod.push_slice(b"ab\ncd").unwrap();
+        let rgr = cons.read_until(b'\n').unwrap();
+        assert_eq!(&*rgr, b"ab\n");
+        rgr.release_all();
+
+        // Not found, nothing is released
+        assert!(matches!(
+            cons.read_until(b'\n'),
+            Err(BBQError::InsufficientSize {
+                requested: 3,
+                available: 2
+            })
+        ));
+        assert_eq!(cons.len(), 2);
+
+        // The delimiter may be the first byte
+        prod.push_slice(b"\n").unwrap();
+        let rgr = cons.read_until(b'\n').unwrap();
+        assert_eq!(&*rgr, b"cd\n");
+        rgr.release_all();
+        prod.push_slice(b"\n").unwrap();
+        assert_eq!(&*cons.read_until(b'\n').unwrap(), b"\n");
+    }
+
+    #[test]
+    fn raw_parts_other_thread() {
+        use core::ptr::NonNull;
+        use std::thread;
+
+        // Raw pointers are not `Send`, unlike an address given to a DMA driver
+        struct Dma(NonNull<u8>, usize);
+        unsafe impl Send for Dma {}
+
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let (ptr, len, token) = prod.grant_exact(4).unwrap().into_raw_parts();
+        let dma = Dma(ptr, len);
+        thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..dma.1 {
+                    unsafe { dma.0.as_ptr().add(i).write(i as u8 + 1) };
+                }
+                unsafe { token.commit(3) };
+            });
+        });
+        assert_eq!(cons.len(), 3);
+
+        let (ptr, len, token) = cons.read().unwrap().into_raw_parts();
+        let dma = Dma(ptr, len);
+        let data = thread::scope(|s| {
+            s.spawn(move || {
+                let data = unsafe { std::slice::from_raw_parts(dma.0.as_ptr(), dma.1) }.to_vec();
+                unsafe { token.release(2) };
+                data
+            })
+            .join()
+            .unwrap()
+        });
+        assert_eq!(data, [1, 2, 3]);
+        assert_eq!(&*cons.read().unwrap(), &[3]);
+    }
+
+    #[test]
+    fn raw_parts_dropped_token() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Like dropping the grant, nothing is committed, and the next grant is allowed
+        let (_, _, token) = prod.grant_exact(4).unwrap().into_raw_parts();
+        drop(token);
+        assert!(cons.read().is_err());
+        prod.grant_exact(2).unwrap().commit(2);
+
+        let (_, _, token) = cons.read().unwrap().into_raw_parts();
+        drop(token);
+        assert_eq!(cons.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn try_commit_release() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Saturated by `commit`, rejected by `try_commit`
+        prod.grant_exact(2).unwrap().commit(3);
+        assert_eq!(cons.len(), 2);
+
+        let wgr = prod.grant_exact(2).unwrap();
+        let (wgr, err) = wgr.try_commit(3).unwrap_err();
+        assert_eq!(
+            err,
+            BBQError::InsufficientSize {
+                requested: 3,
+                available: 2
+            }
+        );
+        assert_eq!(cons.len(), 2);
+        wgr.try_commit(1).unwrap();
+        assert_eq!(cons.len(), 3);
+
+        // Same for the read side
+        let rgr = cons.read().unwrap();
+        let (rgr, err) = rgr.try_release(4).unwrap_err();
+        assert_eq!(
+            err,
+            BBQError::InsufficientSize {
+                requested: 4,
+                available: 3
+            }
+        );
+        assert_eq!(cons.len(), 3);
+        rgr.try_release(2).unwrap();
+        assert_eq!(cons.len(), 1);
+
+        cons.read().unwrap().release(2);
+        assert!(cons.is_empty());
+    }
+
+    #[test]
+    fn grant_content_eq() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(rgr, &[1, 2, 3, 4][..]);
+        assert!(rgr == [1, 2, 3, 4][..]);
+        let shorter: &[u8] = &[1, 2, 3];
+        assert!(rgr != shorter);
+        assert!(!rgr.content_eq(&[1, 2, 3, 5]));
+        rgr.release(3);
+
+        // Wrap around, the split grant is compared as one sequence
+        prod.push_slice_all(&[5, 6]).unwrap();
+        prod.push_slice_all(&[7, 8]).unwrap();
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.bufs(), (&[4, 5, 6][..], &[7, 8][..]));
+        assert!(rgr.content_eq(&[4, 5, 6, 7, 8]));
+        let expected: &[u8] = &[4, 5, 6, 7, 8];
+        assert!(rgr == expected);
+
+        // Differences on either side of the wrap, or in the length
+        assert!(!rgr.content_eq(&[4, 5, 0, 7, 8]));
+        assert!(!rgr.content_eq(&[4, 5, 6, 0, 8]));
+        assert!(!rgr.content_eq(&[4, 5, 6, 7]));
+        assert!(!rgr.content_eq(&[4, 5, 6, 7, 8, 9]));
+        assert!(!rgr.content_eq(&[4, 5]));
+        rgr.release(3);
+
+        // Nothing left at the end of the ring, the second part is empty
+        let rgr = cons.split_read().unwrap();
+        assert_eq!(rgr.bufs(), (&[7, 8][..], &[][..]));
+        assert!(rgr == [7, 8][..]);
+        assert!(!rgr.content_eq(&[]));
+    }
+
+    #[test]
+    fn release_remaining() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice(b"ab\ncd").unwrap();
+
+        // Nothing retained releases the full grant
+        let rgr = cons.read().unwrap();
+        rgr.release_remaining();
+        assert!(cons.is_empty());
+
+        prod.push_slice(b"ef\ngh").unwrap();
+        let mut rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, b"ef\ngh");
+        rgr.retain_tail(2);
+        rgr.release_remaining();
+
+        // The retained tail is the start of the next grant, in place
+        assert_eq!(cons.len(), 2);
+        prod.push_slice(b"\n").unwrap();
+        let mut rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, b"gh\n");
+
+        // Retaining more than the grant keeps everything
+        rgr.retain_tail(10);
+        rgr.release_remaining();
+        assert_eq!(&*cons.read().unwrap(), b"gh\n");
+    }
+
+    #[test]
+    fn read_until_wrapped() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        prod.push_slice(&[0; 5]).unwrap();
+        cons.read().unwrap().release(5);
+
+        // The delimiter is committed after the writer wrapped around
+        assert_eq!(prod.send_slice(b"abc\nd"), Ok(5));
+        assert!(cons.read_until(b'\n').is_err());
+
+        // Found once the reader wrapped around too
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, b"abc");
+        rgr.release_all();
+        let rgr = cons.read_until(b'\n').unwrap();
+        assert_eq!(&*rgr, b"\n");
+        rgr.release_all();
+        assert_eq!(&*cons.read().unwrap(), b"d");
+    }
+
+    #[test]
+    fn empty_full() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let state = || (bb.is_empty(), bb.is_full());
+
+        // Fresh queue
+        assert_eq!(state(), (true, false));
+        assert!(prod.is_empty() && cons.is_empty());
+
+        // Exactly full
+        prod.grant_exact(6).unwrap().commit(6);
+        assert_eq!(state(), (false, true));
+        assert!(prod.is_full() && cons.is_full());
+
+        // Only the start of the ring is free
+        cons.read().unwrap().release(3);
+        assert_eq!(state(), (false, false));
+        assert!(!prod.is_full() && !cons.is_full());
+
+        // Inverted, two then one byte left before the read pointer
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(state(), (false, false));
+        prod.grant_exact(1).unwrap().commit(1);
+        assert_eq!(state(), (false, true));
+
+        cons.read().unwrap().release(3);
+        cons.read().unwrap().release(2);
+        assert_eq!(state(), (true, false));
+
+        // A wrapping grant sets `last`, skipping the byte at the end of the ring
+        prod.grant_exact(3).unwrap().commit(3);
+        cons.read().unwrap().release(3);
+        assert_eq!(state(), (true, false));
+        prod.grant_exact(4).unwrap().commit(4);
+        assert_eq!(bb.skipped_len(), 1);
+        assert_eq!(state(), (false, true));
+
+        // Reserved bytes are not free
+        cons.read().unwrap().release(4);
+        prod.grant_exact(2).unwrap().commit(2);
+        cons.read().unwrap().release(2);
+        let wgr = prod.grant_exact(5).unwrap();
+        assert_eq!(state(), (true, true));
+        wgr.abort();
+        assert_eq!(state(), (true, false));
+    }
+
+    #[test]
+    fn grant_split_at() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Parts are committed in any order, and nothing is readable until both are
+        let (mut head, mut tail) = prod.grant_exact(6).unwrap().split_at(2);
+        assert_eq!((head.len(), tail.len()), (2, 4));
+        assert_eq!(prod.grant_exact(1).unwrap_err(), BBQError::GrantInProgress);
+        tail[..3].copy_from_slice(&[3, 4, 5]);
+        tail.commit(3);
+        assert!(cons.read().is_err());
+        head.copy_from_slice(&[1, 2]);
+        head.commit_all();
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3, 4, 5]);
+        rgr.release(5);
+
+        // The tail is dropped if the head isn't fully committed
+        let (mut head, mut tail) = prod.grant_exact(2).unwrap().split_at(1);
+        head[0] = 6;
+        tail[0] = 7;
+        head.commit(0);
+        tail.commit(1);
+        assert!(cons.read().is_err());
+
+        // The size configured to be committed on drop is divided between the parts
+        let mut wgr = prod.grant_max_remaining(8).unwrap();
+        wgr.copy_from_slice(&[8, 9, 10]);
+        wgr.to_commit(2);
+        let (head, tail) = wgr.split_at(1);
+        drop(tail);
+        drop(head);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[8, 9]);
+        rgr.release(2);
+
+        // Empty parts, committed from another thread
+        let (head, tail) = prod.grant_exact(4).unwrap().split_at(4);
+        assert!(tail.is_empty());
+        std::thread::scope(|s| {
+            s.spawn(move || head.commit_all());
+        });
+        tail.commit(1);
+
+        assert_eq!(cons.read().unwrap().len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "the split point is out of the grant")]
+    fn grant_split_at_out_of_bounds() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        let _parts = prod.grant_exact(4).unwrap().split_at(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "a part of a split grant can't be split again")]
+    fn grant_split_at_twice() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        let (head, _tail) = prod.grant_exact(4).unwrap().split_at(2);
+        let _parts = head.split_at(1);
+    }
+
+    #[test]
+    fn insufficient_size_available() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let insufficient = |requested, available| BBQError::InsufficientSize {
+            requested,
+            available,
+        };
+
+        // Nothing to read
+        assert_eq!(cons.read().unwrap_err(), insufficient(1, 0));
+        assert!(matches!(cons.split_read(), Err(e) if e == insufficient(1, 0)));
+        assert_eq!(cons.read_exact(2).unwrap_err(), insufficient(2, 0));
+
+        prod.grant_exact(4).unwrap().commit(4);
+        assert_eq!(prod.grant_exact(3).unwrap_err(), insufficient(3, 2));
+        assert_eq!(cons.read_exact(5).unwrap_err(), insufficient(5, 4));
+
+        // Both the end and the start of the ring are available, but not contiguously
+        cons.read().unwrap().release(3);
+        assert_eq!(prod.grant_exact(3).unwrap_err(), insufficient(3, 2));
+        assert_eq!(prod.grant_exact_no_wrap(3).unwrap_err(), insufficient(3, 2));
+        prod.grant_max_remaining(4).unwrap().commit(2);
+        prod.grant_max_remaining(4).unwrap().commit(2);
+
+        // Full, in the inverted state
+        assert_eq!(prod.grant_exact(1).unwrap_err(), insufficient(1, 0));
+        assert!(matches!(
+            prod.grant_max_remaining(4),
+            Err(e) if e == insufficient(4, 0)
+        ));
+        assert!(matches!(
+            cons.split_read_exact(6),
+            Err(e) if e == insufficient(6, 5)
+        ));
+
+        assert_eq!(
+            insufficient(3, 2).to_string(),
+            "insufficient size: 3 bytes requested, 2 available"
+        );
+        assert_eq!(BBQError::GrantInProgress.to_string(), "grant in progress");
+    }
+
+    #[test]
+    fn storage_provider_capacity() {
+        use bbqueue::{SliceStorageProvider, StorageProvider};
+        use core::ptr::NonNull;
+
+        assert_eq!(StaticStorageProvider::<6>::new().capacity(), 6);
+        assert_eq!(AlignedStorageProvider::<6, 4>::new().capacity(), 6);
+        let mut storage = [0; 6];
+        assert_eq!(SliceStorageProvider::new(&mut storage).capacity(), 6);
+
+        // Only uses the first half of its storage
+        #[derive(Debug, PartialEq)]
+        struct HalfStorageProvider<const N: usize>(StaticStorageProvider<N>);
+
+        impl<const N: usize> StorageProvider for HalfStorageProvider<N> {
+            fn storage(&self) -> NonNull<[u8]> {
+                self.0.storage()
+            }
+
+            fn capacity(&self) -> usize {
+                N / 2
+            }
+        }
+
+        let bb = BBQueue::new(HalfStorageProvider(StaticStorageProvider::<8>::new()));
+        assert_eq!(bb.capacity(), 4);
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        assert_eq!(prod.grant_exact(5).unwrap_err(), BBQError::RequestTooLarge);
+
+        let mut wgr = prod.grant_max_remaining(8).unwrap();
+        assert_eq!(wgr.len(), 4);
+        wgr.copy_from_slice(&[1, 2, 3, 4]);
+        wgr.commit(4);
+        assert_eq!(&*cons.read().unwrap(), &[1, 2, 3, 4]);
+    }
 }
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{
+        logger::{drain, BBQueueLogger},
+        BBQueue, StaticStorageProvider,
+    };
+    use log::{Level, LevelFilter, Log, Record};
+    use std::thread::spawn;
+
+    const THREADS: usize = 4;
+    const RECORDS: usize = 1000;
+
+    #[test]
+    fn install_multi_thread() {
+        static BB: BBQueue<StaticStorageProvider<512>> = BBQueue::new_static();
+        let (prod, mut cons) = BB.try_split_framed().unwrap();
+
+        // This is the only test installing a logger
+        let logger: &'static BBQueueLogger<_> = Box::leak(Box::new(BBQueueLogger::new(prod, 32)));
+        log::set_logger(logger).unwrap();
+        log::set_max_level(LevelFilter::Info);
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                spawn(move || {
+                    for i in 0..RECORDS {
+                        log::info!(target: "bbq", "{} {}", t, i);
+                        log::debug!(target: "bbq", "filtered out");
+                    }
+                })
+            })
+            .collect();
+
+        let mut last = [None; THREADS];
+        let mut received = 0;
+        let mut check = |record: &[u8]| {
+            let record = core::str::from_utf8(record).unwrap();
+            let mut fields = record.strip_prefix("INFO bbq: ").unwrap().split(' ');
+            let t: usize = fields.next().unwrap().parse().unwrap();
+            let i: usize = fields.next().unwrap().parse().unwrap();
+            assert!(fields.next().is_none());
+
+            // Records of a thread are received in order, some may be dropped
+            assert!(last[t].map_or(true, |l| l < i));
+            last[t] = Some(i);
+            received += 1;
+        };
+
+        while handles.iter().any(|h| !h.is_finished()) {
+            drain(&mut cons, &mut check);
+        }
+        for h in handles {
+            h.join().unwrap();
+        }
+        drain(&mut cons, &mut check);
+
+        assert!(received > 0);
+        assert_eq!(received + logger.dropped(), THREADS * RECORDS);
+    }
+
+    #[test]
+    fn dropped_when_full() {
+        let bb: BBQueue<StaticStorageProvider<32>> = BBQueue::new_static();
+        let (prod, mut cons) = bb.try_split_framed().unwrap();
+
+        // Each record uses 9 bytes, so only 3 of them fit
+        let logger = BBQueueLogger::new(prod, 8);
+        for i in 0..5 {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", i))
+                    .level(Level::Warn)
+                    .target("t")
+                    .build(),
+            );
+        }
+        assert_eq!(logger.dropped(), 2);
+
+        // Records are truncated to the maximum record length
+        let mut records = Vec::new();
+        assert_eq!(drain(&mut cons, |r| records.push(r.to_vec())), 3);
+        assert_eq!(records, [b"WARN t: ", b"WARN t: ", b"WARN t: "]);
+        assert_eq!(drain(&mut cons, |_| unreachable!()), 0);
+
+        // There is room again
+        logger.log(
+            &Record::builder()
+                .args(format_args!("ok"))
+                .level(Level::Error)
+                .target("")
+                .build(),
+        );
+        assert_eq!(logger.dropped(), 2);
+        assert_eq!(drain(&mut cons, |r| assert_eq!(r, b"ERROR : ")), 1);
+    }
+}
@@ -0,0 +1,183 @@
+//! Model checks of the producer/consumer handoff, run with:
+//!
+//! ```text
+//! cargo test --release --features loom --manifest-path bbqtest/Cargo.toml
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use bbqueue::{
+        broadcast::BroadcastBBQueue, multi::MultiBBQueue, BBQueue, StaticStorageProvider,
+    };
+    use loom::thread::{spawn, yield_now};
+    use std::sync::Arc;
+
+    /// Send `chunks` through a queue of `N` bytes, one grant per chunk, while the
+    /// consumer releases at most `release_max` bytes per read grant. Each grant is
+    /// `overgrant` bytes larger than its chunk, and only the chunk is committed
+    fn handoff<const N: usize>(
+        chunks: &'static [&'static [u8]],
+        release_max: usize,
+        overgrant: usize,
+    ) {
+        loom::model(move || {
+            let bb: Arc<BBQueue<StaticStorageProvider<N>>> = Arc::new(BBQueue::new_static());
+            let (mut prod, mut cons) = bb.try_split_arc().unwrap();
+
+            let producer = spawn(move || {
+                for chunk in chunks {
+                    loop {
+                        match prod.grant_exact(chunk.len() + overgrant) {
+                            Ok(mut wgr) => {
+                                wgr[..chunk.len()].copy_from_slice(chunk);
+                                wgr.commit(chunk.len());
+                                break;
+                            }
+                            Err(_) => yield_now(),
+                        }
+                    }
+                }
+            });
+
+            let expected: Vec<u8> = chunks.concat();
+            let mut received = Vec::new();
+            while received.len() < expected.len() {
+                match cons.read() {
+                    Ok(rgr) => {
+                        let len = rgr.len().min(release_max);
+                        received.extend_from_slice(&rgr[..len]);
+                        rgr.release(len);
+                    }
+                    Err(_) => yield_now(),
+                }
+            }
+
+            producer.join().unwrap();
+            assert_eq!(received, expected);
+            assert!(cons.read().is_err());
+        });
+    }
+
+    #[test]
+    fn handoff_contiguous() {
+        handoff::<4>(&[&[1, 2], &[3, 4]], usize::MAX, 0);
+    }
+
+    #[test]
+    fn handoff_wrap_around() {
+        // The second grant doesn't fit at the end of the ring, and wraps around
+        // once the consumer released the first one, inverting the queue
+        handoff::<4>(&[&[1, 2, 3], &[4, 5]], usize::MAX, 0);
+    }
+
+    #[test]
+    fn handoff_wrap_around_partial_release() {
+        // The consumer reaches the end of the ring while the queue is inverted,
+        // and moves the read pointer back to the start
+        handoff::<4>(&[&[1, 2, 3], &[4, 5]], 2, 0);
+    }
+
+    #[test]
+    fn handoff_partial_commit() {
+        // The first grant takes the whole ring but leaves its last byte uncommitted,
+        // and the second one wraps around and commits less than granted, which must
+        // not expose the uncommitted bytes or move `last` past the committed ones
+        handoff::<4>(&[&[1, 2, 3], &[4]], usize::MAX, 1);
+    }
+
+    #[test]
+    fn handoff_partial_commit_partial_release() {
+        handoff::<4>(&[&[1, 2, 3], &[4]], 2, 1);
+    }
+
+    #[test]
+    fn multi_producer_handoff() {
+        // Two producers race for the reservation, and commit in any order.
+        // Three threads are too many for an exhaustive check, bound the preemptions
+        let mut model = loom::model::Builder::new();
+        model.preemption_bound = Some(3);
+        model.check(|| {
+            // The shared producers borrow the queue, which must outlive the threads
+            let bb: &'static MultiBBQueue<StaticStorageProvider<4>, 2> =
+                Box::leak(Box::new(MultiBBQueue::new_static()));
+            let (prod, mut cons) = bb.try_split_multi().unwrap();
+
+            // Both grants always fit, so the producers never have to retry
+            let producers: Vec<_> = [[1, 1], [2, 2]]
+                .iter()
+                .map(|&chunk| {
+                    let mut prod = prod.clone();
+                    spawn(move || {
+                        let mut wgr = prod.grant_exact(2).unwrap();
+                        wgr.copy_from_slice(&chunk);
+                        wgr.commit(2);
+                    })
+                })
+                .collect();
+
+            let mut received = Vec::new();
+            while received.len() < 4 {
+                match cons.read() {
+                    Ok(rgr) => {
+                        received.extend_from_slice(&rgr);
+                        let len = rgr.len();
+                        rgr.release(len);
+                    }
+                    Err(_) => yield_now(),
+                }
+            }
+
+            for producer in producers {
+                producer.join().unwrap();
+            }
+            assert!(received == [1, 1, 2, 2] || received == [2, 2, 1, 1]);
+            assert!(cons.read().is_err());
+        });
+    }
+
+    #[test]
+    fn broadcast_reclaim() {
+        // Both consumers release concurrently, while the producer tries to wrap
+        // around. Once they are done, the shared read pointer must have been moved
+        // past the bytes read by both, whichever reclaimed last
+        let mut model = loom::model::Builder::new();
+        model.preemption_bound = Some(3);
+        model.check(|| {
+            // The consumers borrow the queue, which must outlive the threads
+            let bb: &'static BroadcastBBQueue<StaticStorageProvider<4>, 2> =
+                Box::leak(Box::new(BroadcastBBQueue::new_static()));
+            let (mut prod, [c0, c1]) = bb.try_split_broadcast().unwrap();
+
+            let mut wgr = prod.grant_exact(3).unwrap();
+            wgr.copy_from_slice(&[1, 2, 3]);
+            wgr.commit(3);
+
+            // The bytes are already committed, so the consumers never have to retry
+            let consumers: Vec<_> = vec![c0, c1]
+                .into_iter()
+                .map(|mut cons| {
+                    spawn(move || {
+                        let rgr = cons.read().unwrap();
+                        assert_eq!(&*rgr, &[1, 2, 3]);
+                        rgr.release(3);
+                        cons
+                    })
+                })
+                .collect();
+
+            // Only succeeds if both consumers already reclaimed the first chunk
+            let wrapped = prod.grant_exact(2).ok();
+            let mut consumers: Vec<_> = consumers.into_iter().map(|c| c.join().unwrap()).collect();
+
+            let mut wgr = wrapped.unwrap_or_else(|| prod.grant_exact(2).unwrap());
+            wgr.copy_from_slice(&[4, 5]);
+            wgr.commit(2);
+
+            for cons in consumers.iter_mut() {
+                let rgr = cons.read().unwrap();
+                assert_eq!(&*rgr, &[4, 5]);
+                rgr.release(2);
+            }
+        });
+    }
+}
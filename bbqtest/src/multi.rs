@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{multi::MultiBBQueue, Error as BBQError, StaticStorageProvider};
+    use std::thread::{scope, yield_now};
+
+    #[test]
+    fn out_of_order_commit() {
+        let bb: MultiBBQueue<StaticStorageProvider<8>, 3> = MultiBBQueue::new_static();
+        let (mut prod_a, mut cons) = bb.try_split_multi().unwrap();
+        let mut prod_b = prod_a.clone();
+        let mut prod_c = prod_a.clone();
+
+        let mut wgr_a = prod_a.grant_exact(2).unwrap();
+        let mut wgr_b = prod_b.grant_exact(2).unwrap();
+        let mut wgr_c = prod_c.grant_exact(2).unwrap();
+        wgr_a.copy_from_slice(&[1, 2]);
+        wgr_b.copy_from_slice(&[3, 4]);
+        wgr_c.copy_from_slice(&[5, 6]);
+
+        // Only one grant may be active per slot, but a request which can never
+        // succeed is reported first
+        assert_eq!(prod_a.grant_exact(9), Err(BBQError::RequestTooLarge));
+        assert_eq!(prod_a.grant_exact(1), Err(BBQError::GrantInProgress));
+
+        wgr_c.commit(2);
+        assert!(cons.read().is_err());
+        wgr_a.commit(2);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2]);
+        rgr.release(2);
+        assert!(cons.read().is_err());
+
+        // Committing the middle grant publishes the last one too
+        wgr_b.commit(2);
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[3, 4, 5, 6]);
+        rgr.release(4);
+    }
+
+    #[test]
+    fn undercommit() {
+        let bb: MultiBBQueue<StaticStorageProvider<16>, 2> = MultiBBQueue::new_static();
+        let (mut prod_a, mut cons) = bb.try_split_multi().unwrap();
+        let mut prod_b = prod_a.clone();
+
+        // The last reserved grant gives its unused bytes back
+        let mut wgr_a = prod_a.grant_exact(4).unwrap();
+        wgr_a.copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(wgr_a.commit(2), 0);
+
+        // A grant followed by another one can't, and commits them zeroed
+        let mut wgr_a = prod_a.grant_exact(4).unwrap();
+        let mut wgr_b = prod_b.grant_exact(2).unwrap();
+        wgr_a.copy_from_slice(&[5, 6, 7, 8]);
+        wgr_b.copy_from_slice(&[9, 10]);
+        assert_eq!(wgr_a.commit(1), 3);
+        assert_eq!(wgr_b.commit(2), 0);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 5, 0, 0, 0, 9, 10]);
+        rgr.release(8);
+
+        // Dropping a grant without committing it is the same as committing 0 bytes
+        let wgr_a = prod_a.grant_exact(4).unwrap();
+        let mut wgr_b = prod_b.grant_exact(2).unwrap();
+        wgr_b.copy_from_slice(&[11, 12]);
+        wgr_b.to_commit_all();
+        drop(wgr_b);
+        assert!(cons.read().is_err());
+        drop(wgr_a);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[0, 0, 0, 0, 11, 12]);
+        rgr.release(6);
+        assert!(cons.read().is_err());
+    }
+
+    #[test]
+    fn wrap_around() {
+        let bb: MultiBBQueue<StaticStorageProvider<8>, 2> = MultiBBQueue::new_static();
+        let (mut prod_a, mut cons) = bb.try_split_multi().unwrap();
+        let mut prod_b = prod_a.clone();
+
+        let mut wgr = prod_a.grant_exact(6).unwrap();
+        wgr.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        wgr.commit(6);
+
+        let rgr = cons.read().unwrap();
+        rgr.release(4);
+
+        // The second grant doesn't fit at the end of the ring, and wraps around
+        let mut wgr_a = prod_a.grant_exact(2).unwrap();
+        let mut wgr_b = prod_b.grant_exact(2).unwrap();
+        wgr_a.copy_from_slice(&[7, 8]);
+        wgr_b.copy_from_slice(&[9, 10]);
+        assert_eq!(prod_a.grant_exact(1), Err(BBQError::GrantInProgress));
+        wgr_b.commit(2);
+        wgr_a.commit(2);
+
+        // The inverted queue has no room left
+        assert!(matches!(
+            prod_a.grant_exact(2),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[5, 6, 7, 8]);
+        rgr.release(4);
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[9, 10]);
+        rgr.release(2);
+        assert!(cons.read().is_err());
+    }
+
+    #[test]
+    fn flush_with_live_grant() {
+        let bb: MultiBBQueue<StaticStorageProvider<16>, 2> = MultiBBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_multi().unwrap();
+
+        let mut wgr_a = prod.grant_exact(4).unwrap();
+        wgr_a.copy_from_slice(&[1, 2, 3, 4]);
+        let start = wgr_a.as_ptr();
+
+        // The consumer must not rewind the pointers under the reserved grant
+        assert_eq!(cons.flush(), Ok(0));
+        let mut wgr_b = prod.grant_exact(4).unwrap();
+        assert_eq!(wgr_b.as_ptr(), start.wrapping_add(4));
+        wgr_b.copy_from_slice(&[5, 6, 7, 8]);
+
+        assert_eq!(wgr_a.commit(4), 0);
+        assert_eq!(wgr_b.commit(4), 0);
+
+        // Flushing discards the committed bytes, still without rewinding
+        assert_eq!(cons.flush(), Ok(8));
+        assert!(cons.read().is_err());
+        let mut wgr = prod.grant_exact(4).unwrap();
+        assert_eq!(wgr.as_ptr(), start.wrapping_add(8));
+        wgr.copy_from_slice(&[9, 10, 11, 12]);
+        assert_eq!(wgr.commit(4), 0);
+        assert_eq!(&*cons.read().unwrap(), &[9, 10, 11, 12]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn stress_multi_producer() {
+        #[cfg(feature = "short-potato")]
+        const RECORDS: u16 = 1_000;
+        #[cfg(not(feature = "short-potato"))]
+        const RECORDS: u16 = 5_000;
+        const PRODUCERS: u8 = 3;
+
+        let bb: MultiBBQueue<StaticStorageProvider<64>, 2> = MultiBBQueue::new_static();
+        let (prod, mut cons) = bb.try_split_multi().unwrap();
+
+        scope(|s| {
+            for id in 0..PRODUCERS {
+                let mut prod = prod.clone();
+                s.spawn(move || {
+                    // Each record is [id, seq (2 bytes), len, payload...]
+                    for seq in 0..RECORDS {
+                        let len = (seq % 7) as usize;
+                        let mut wgr = loop {
+                            match prod.grant_exact(4 + len) {
+                                Ok(wgr) => break wgr,
+                                Err(_) => yield_now(),
+                            }
+                        };
+                        wgr[0] = id;
+                        wgr[1..3].copy_from_slice(&seq.to_le_bytes());
+                        wgr[3] = len as u8;
+                        wgr[4..].fill(id ^ seq as u8);
+                        wgr.commit(4 + len);
+                    }
+                });
+            }
+
+            let mut next = [0u16; PRODUCERS as usize];
+            let mut pending = Vec::new();
+            while next.iter().any(|&seq| seq < RECORDS) {
+                if let Ok(rgr) = cons.read() {
+                    pending.extend_from_slice(&rgr);
+                    let len = rgr.len();
+                    rgr.release(len);
+                }
+
+                // Records don't straddle the end of the ring, but may be split
+                // across two reads
+                while pending.len() >= 4 && pending.len() >= 4 + pending[3] as usize {
+                    let id = pending[0];
+                    let seq = u16::from_le_bytes([pending[1], pending[2]]);
+                    let len = pending[3] as usize;
+                    assert_eq!(seq, next[id as usize]);
+                    assert_eq!(len, (seq % 7) as usize);
+                    assert!(pending[4..4 + len].iter().all(|&b| b == id ^ seq as u8));
+                    next[id as usize] += 1;
+                    pending.drain(..4 + len);
+                }
+            }
+            assert!(pending.is_empty());
+        });
+
+        assert!(cons.read().is_err());
+    }
+}
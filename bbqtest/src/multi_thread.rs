@@ -3,7 +3,11 @@
 mod tests {
     use bbqueue::{BBQueue, Error, StaticStorageProvider};
     use rand::prelude::*;
-    use std::thread::spawn;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Barrier,
+    };
+    use std::thread::{spawn, yield_now};
     use std::time::{Duration, Instant};
 
     #[cfg(feature = "travisci")]
@@ -18,6 +22,7 @@ mod tests {
     const TIMEOUT_NODATA: Duration = Duration::from_millis(10_000);
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn randomize_tx() {
         #[cfg(feature = "travisci")]
         #[cfg(feature = "verbose")]
@@ -48,12 +53,12 @@ mod tests {
         #[cfg(feature = "verbose")]
         println!("RTX: Running test...");
 
-        static mut BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
-        let (mut tx, mut rx) = unsafe { BB.try_split().unwrap() };
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
 
         let mut last_tx = Instant::now();
-        let mut last_rx = last_tx.clone();
-        let start_time = last_tx.clone();
+        let mut last_rx = last_tx;
+        let start_time = last_tx;
 
         let tx_thr = spawn(move || {
             let mut txd_ct = 0;
@@ -96,14 +101,14 @@ mod tests {
             let mut rxd_ct = 0;
             let mut rxd_ivl = 0;
 
-            for (_idx, i) in data_rx.drain(..).enumerate() {
+            for i in data_rx.drain(..) {
                 'inner: loop {
                     if last_rx.elapsed() > TIMEOUT_NODATA {
                         panic!("rx timeout, iter {}", i);
                     }
                     let gr = match rx.read() {
                         Ok(gr) => gr,
-                        Err(Error::InsufficientSize) => continue 'inner,
+                        Err(Error::InsufficientSize { .. }) => continue 'inner,
                         Err(_) => panic!(),
                     };
 
@@ -139,13 +144,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn sanity_check() {
-        static mut BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
-        let (mut tx, mut rx) = unsafe { BB.try_split().unwrap() };
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
 
         let mut last_tx = Instant::now();
-        let mut last_rx = last_tx.clone();
-        let start_time = last_tx.clone();
+        let mut last_rx = last_tx;
+        let start_time = last_tx;
 
         let tx_thr = spawn(move || {
             let mut txd_ct = 0;
@@ -156,23 +162,20 @@ mod tests {
                     if last_tx.elapsed() > TIMEOUT_NODATA {
                         panic!("tx timeout, iter {}", i);
                     }
-                    match tx.grant_exact(1) {
-                        Ok(mut gr) => {
-                            gr[0] = (i & 0xFF) as u8;
-                            gr.commit(1);
-
-                            // Update tracking
-                            last_tx = Instant::now();
-                            txd_ct += 1;
-                            if (txd_ct / RPT_IVAL) > txd_ivl {
-                                txd_ivl = txd_ct / RPT_IVAL;
-                                #[cfg(feature = "verbose")]
-                                println!("{:?} - sctx: {}", start_time.elapsed(), txd_ct);
-                            }
-
-                            break 'inner;
+                    if let Ok(mut gr) = tx.grant_exact(1) {
+                        gr[0] = (i & 0xFF) as u8;
+                        gr.commit(1);
+
+                        // Update tracking
+                        last_tx = Instant::now();
+                        txd_ct += 1;
+                        if (txd_ct / RPT_IVAL) > txd_ivl {
+                            txd_ivl = txd_ct / RPT_IVAL;
+                            #[cfg(feature = "verbose")]
+                            println!("{:?} - sctx: {}", start_time.elapsed(), txd_ct);
                         }
-                        Err(_) => {}
+
+                        break 'inner;
                     }
                 }
             }
@@ -191,7 +194,7 @@ mod tests {
 
                 let gr = match rx.read() {
                     Ok(gr) => gr,
-                    Err(Error::InsufficientSize) => continue,
+                    Err(Error::InsufficientSize { .. }) => continue,
                     Err(_) => panic!(),
                 };
 
@@ -233,9 +236,10 @@ mod tests {
     }
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn sanity_check_grant_max() {
-        static mut BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
-        let (mut tx, mut rx) = unsafe { BB.try_split().unwrap() };
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
 
         #[cfg(feature = "verbose")]
         println!("SCGM: Generating Test Data...");
@@ -250,8 +254,8 @@ mod tests {
         println!("SCGM: Starting Test...");
 
         let mut last_tx = Instant::now();
-        let mut last_rx = last_tx.clone();
-        let start_time = last_tx.clone();
+        let mut last_rx = last_tx;
+        let start_time = last_tx;
 
         let tx_thr = spawn(move || {
             let mut txd_ct = 0;
@@ -264,29 +268,26 @@ mod tests {
                     if last_tx.elapsed() > TIMEOUT_NODATA {
                         panic!("tx timeout");
                     }
-                    match tx
-                        .grant_max_remaining(trng.gen_range(QUEUE_SIZE / 3, (2 * QUEUE_SIZE) / 3))
+                    if let Ok(mut gr) =
+                        tx.grant_max_remaining(trng.gen_range(QUEUE_SIZE / 3, (2 * QUEUE_SIZE) / 3))
                     {
-                        Ok(mut gr) => {
-                            let sz = ::std::cmp::min(data_tx.len(), gr.len());
-                            for i in 0..sz {
-                                gr[i] = data_tx.pop().unwrap();
-                            }
-
-                            // Update tracking
-                            last_tx = Instant::now();
-                            txd_ct += sz;
-                            if (txd_ct / RPT_IVAL) > txd_ivl {
-                                txd_ivl = txd_ct / RPT_IVAL;
-                                #[cfg(feature = "verbose")]
-                                println!("{:?} - scgmtx: {}", start_time.elapsed(), txd_ct);
-                            }
+                        let sz = ::std::cmp::min(data_tx.len(), gr.len());
+                        for i in 0..sz {
+                            gr[i] = data_tx.pop().unwrap();
+                        }
 
-                            let len = gr.len();
-                            gr.commit(len);
-                            break 'inner;
+                        // Update tracking
+                        last_tx = Instant::now();
+                        txd_ct += sz;
+                        if (txd_ct / RPT_IVAL) > txd_ivl {
+                            txd_ivl = txd_ct / RPT_IVAL;
+                            #[cfg(feature = "verbose")]
+                            println!("{:?} - scgmtx: {}", start_time.elapsed(), txd_ct);
                         }
-                        Err(_) => {}
+
+                        let len = gr.len();
+                        gr.commit(len);
+                        break 'inner;
                     }
                 }
             }
@@ -303,7 +304,7 @@ mod tests {
                     }
                     let gr = match rx.read() {
                         Ok(gr) => gr,
-                        Err(Error::InsufficientSize) => continue 'inner,
+                        Err(Error::InsufficientSize { .. }) => continue 'inner,
                         Err(_) => panic!(),
                     };
 
@@ -339,4 +340,318 @@ mod tests {
         tx_thr.join().unwrap();
         rx_thr.join().unwrap();
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn sanity_check_arc() {
+        // No static required, the owned halves keep the queue alive
+        let bb: Arc<BBQueue<StaticStorageProvider<QUEUE_SIZE>>> = Arc::new(BBQueue::new_static());
+        let (mut tx, mut rx) = bb.clone().try_split_arc().unwrap();
+
+        let mut last_tx = Instant::now();
+        let mut last_rx = last_tx;
+
+        let tx_thr = spawn(move || {
+            for i in 0..ITERS {
+                loop {
+                    if last_tx.elapsed() > TIMEOUT_NODATA {
+                        panic!("tx timeout, iter {}", i);
+                    }
+                    if let Ok(mut gr) = tx.grant_exact(1) {
+                        gr[0] = (i & 0xFF) as u8;
+                        gr.commit(1);
+                        last_tx = Instant::now();
+                        break;
+                    }
+                }
+            }
+        });
+
+        let rx_thr = spawn(move || {
+            let mut i = 0;
+
+            while i < ITERS {
+                if last_rx.elapsed() > TIMEOUT_NODATA {
+                    panic!("rx timeout, iter {}", i);
+                }
+
+                let gr = match rx.read() {
+                    Ok(gr) => gr,
+                    Err(Error::InsufficientSize { .. }) => continue,
+                    Err(_) => panic!(),
+                };
+
+                for data in &*gr {
+                    assert_eq!(*data, (i & 0xFF) as u8, "RX Iter: {}", i);
+                    i += 1;
+                }
+
+                let len = gr.len();
+                gr.release(len);
+                last_rx = Instant::now();
+            }
+        });
+
+        tx_thr.join().unwrap();
+        rx_thr.join().unwrap();
+
+        // Both halves were dropped with their threads, so we can split again
+        assert!(bb.try_split().is_ok());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn split_write_consistency() {
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
+
+        let tx_thr = spawn(move || {
+            let mut trng = thread_rng();
+            let mut ctr = 0u8;
+            let mut sent = 0;
+
+            while sent < ITERS {
+                let mut gr = match tx.grant_split_remaining(trng.gen_range(1, QUEUE_SIZE)) {
+                    Ok(gr) => gr,
+                    Err(_) => {
+                        yield_now();
+                        continue;
+                    }
+                };
+
+                // Commit a random amount, which may stop anywhere in either region
+                let used = trng.gen_range(0, gr.combined_len() + 1).min(ITERS - sent);
+                let (buf1, buf2) = gr.bufs_mut();
+                for by in buf1.iter_mut().chain(buf2.iter_mut()).take(used) {
+                    *by = ctr;
+                    ctr = ctr.wrapping_add(1);
+                }
+                gr.commit(used);
+                sent += used;
+            }
+        });
+
+        let rx_thr = spawn(move || {
+            let mut ctr = 0u8;
+            let mut received = 0;
+
+            while received < ITERS {
+                let gr = match rx.read() {
+                    Ok(gr) => gr,
+                    Err(_) => {
+                        yield_now();
+                        continue;
+                    }
+                };
+
+                for by in gr.iter() {
+                    assert_eq!(*by, ctr);
+                    ctr = ctr.wrapping_add(1);
+                }
+                received += gr.len();
+                gr.release_all();
+            }
+        });
+
+        tx_thr.join().unwrap();
+        rx_thr.join().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn spin_helpers() {
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
+
+        let tx_thr = spawn(move || {
+            let mut trng = thread_rng();
+            let mut ctr = 0u8;
+            let mut sent = 0;
+
+            while sent < ITERS {
+                // Sizes up to half the queue can always be granted eventually
+                let sz = trng.gen_range(1, QUEUE_SIZE / 2).min(ITERS - sent);
+                let mut gr = tx.grant_exact_spin(sz, yield_now).unwrap();
+                for by in gr.iter_mut() {
+                    *by = ctr;
+                    ctr = ctr.wrapping_add(1);
+                }
+                gr.commit(sz);
+                sent += sz;
+            }
+        });
+
+        let rx_thr = spawn(move || {
+            let mut ctr = 0u8;
+            let mut received = 0;
+
+            while received < ITERS {
+                let gr = rx.read_spin(yield_now).unwrap();
+                for by in gr.iter() {
+                    assert_eq!(*by, ctr);
+                    ctr = ctr.wrapping_add(1);
+                }
+                received += gr.len();
+                gr.release_all();
+            }
+        });
+
+        tx_thr.join().unwrap();
+        rx_thr.join().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn overwrite_consistency() {
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        static DONE: AtomicBool = AtomicBool::new(false);
+        let (mut tx, mut rx) = BB.try_split().unwrap();
+
+        let tx_thr = spawn(move || {
+            let mut trng = thread_rng();
+            let mut ctr = 0u8;
+
+            for _ in 0..ITERS {
+                // The producer never has to wait, it discards old data instead
+                let sz = trng.gen_range(1, 64);
+                let mut gr = tx.grant_exact_overwrite(sz).unwrap_or_else(|e| {
+                    // Only fails while the consumer holds a read grant
+                    assert_eq!(e, Error::GrantInProgress);
+                    loop {
+                        if let Ok(gr) = tx.grant_exact_overwrite(sz) {
+                            break gr;
+                        }
+                    }
+                });
+                for by in gr.iter_mut() {
+                    *by = ctr;
+                    ctr = ctr.wrapping_add(1);
+                }
+                gr.commit(sz);
+            }
+
+            DONE.store(true, Ordering::Release);
+        });
+
+        let rx_thr = spawn(move || {
+            let mut total = 0;
+
+            loop {
+                let done = DONE.load(Ordering::Acquire);
+                let gr = match rx.read() {
+                    Ok(gr) => gr,
+                    Err(_) if done => break,
+                    Err(_) => continue,
+                };
+
+                // Some bytes may have been discarded between reads, but
+                // a single grant always contains consecutive bytes
+                for w in gr.windows(2) {
+                    assert_eq!(w[1], w[0].wrapping_add(1));
+                }
+
+                total += gr.len();
+                gr.release_all();
+
+                // Give the producer a chance to discard data while
+                // no read grant is in progress
+                yield_now();
+            }
+
+            total
+        });
+
+        tx_thr.join().unwrap();
+        let total = rx_thr.join().unwrap();
+        #[cfg(feature = "verbose")]
+        println!("Overwrite: received {} bytes", total);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
+    fn stats_consistency() {
+        #[cfg(feature = "travisci")]
+        const ROUNDS: usize = 100;
+        #[cfg(not(feature = "travisci"))]
+        const ROUNDS: usize = 10_000;
+
+        const ROUND_BYTES: usize = 4 * QUEUE_SIZE;
+
+        static BB: BBQueue<StaticStorageProvider<QUEUE_SIZE>> = BBQueue::new_static();
+        let (mut tx, mut rx) = BB.try_split().unwrap();
+
+        // Bytes left in the queue by the consumer at the end of each round
+        let mut trng = thread_rng();
+        let keep: Arc<Vec<usize>> = Arc::new(
+            (0..ROUNDS)
+                .map(|_| trng.gen_range(0, QUEUE_SIZE / 2))
+                .collect(),
+        );
+        let barrier = Arc::new(Barrier::new(3));
+
+        let tx_barrier = barrier.clone();
+        let tx_thr = spawn(move || {
+            let mut trng = thread_rng();
+
+            for _ in 0..ROUNDS {
+                let mut left = ROUND_BYTES;
+                while left != 0 {
+                    let sz = trng.gen_range(1, 64).min(left);
+                    match tx.grant_max_remaining(sz) {
+                        Ok(gr) => {
+                            let len = gr.len();
+                            left -= len;
+                            gr.commit(len);
+                        }
+                        Err(_) => yield_now(),
+                    }
+                }
+
+                // Wait for the checks, then for the next round
+                tx_barrier.wait();
+                tx_barrier.wait();
+            }
+        });
+
+        let rx_barrier = barrier.clone();
+        let rx_keep = keep.clone();
+        let rx_thr = spawn(move || {
+            let mut total = 0;
+
+            for round in 0..ROUNDS {
+                let target = ROUND_BYTES * (round + 1) - rx_keep[round];
+                while total != target {
+                    match rx.read() {
+                        Ok(gr) => {
+                            let len = gr.len().min(target - total);
+                            total += len;
+                            gr.release(len);
+                        }
+                        Err(_) => yield_now(),
+                    }
+                }
+
+                rx_barrier.wait();
+                rx_barrier.wait();
+            }
+        });
+
+        for round in 0..ROUNDS {
+            // Both threads are idle between the two barriers
+            barrier.wait();
+
+            let stats = BB.stats();
+            let occupied = BB.occupied_len() as u64;
+            assert_eq!(stats.committed, (ROUND_BYTES * (round + 1)) as u64);
+            assert_eq!(occupied, keep[round] as u64);
+            assert_eq!(stats.committed, stats.released + occupied);
+            assert!(stats.max_occupied <= QUEUE_SIZE as u64);
+            assert!(stats.max_occupied >= occupied);
+
+            barrier.wait();
+        }
+
+        tx_thr.join().unwrap();
+        rx_thr.join().unwrap();
+    }
 }
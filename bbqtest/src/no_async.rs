@@ -0,0 +1,42 @@
+//! Only built with `--no-default-features`, to check that the synchronous API works
+//! without the `async` feature of the queue.
+
+#[cfg(test)]
+mod tests {
+    use bbqueue::{BBQueue, StaticStorageProvider};
+
+    #[test]
+    fn sync_round_trip() {
+        let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Enough rounds to wrap around the ring several times
+        for i in 0..20u8 {
+            let mut wgr = prod.grant_exact(3).unwrap();
+            wgr.copy_from_slice(&[i, i + 1, i + 2]);
+            wgr.commit(3);
+
+            let rgr = cons.read_exact(3).unwrap();
+            assert_eq!(&*rgr, &[i, i + 1, i + 2]);
+            rgr.release(3);
+        }
+
+        assert!(cons.is_empty());
+    }
+
+    #[test]
+    fn framed_round_trip() {
+        let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+
+        let mut wgr = prod.grant(3).unwrap();
+        wgr.copy_from_slice(&[1, 2, 3]);
+        wgr.commit(3);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3]);
+        rgr.release();
+
+        assert!(cons.read().is_err());
+    }
+}
@@ -84,14 +84,15 @@ mod tests {
         }
     }
 
-    static mut BB: BBQueue<StaticStorageProvider<BUFFER_SIZE>> = BBQueue::new_static();
+    static BB: BBQueue<StaticStorageProvider<BUFFER_SIZE>> = BBQueue::new_static();
 
     use std::sync::mpsc::{channel, Receiver, Sender};
     use std::thread::spawn;
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn hello() {
-        let (prod, cons) = unsafe { BB.try_split().unwrap() };
+        let (prod, cons) = BB.try_split().unwrap();
 
         // create the channels
         let (tx_1_2, rx_1_2): (
@@ -142,17 +143,9 @@ mod tests {
                 }
                 let (new_me, send) = me.work();
 
-                let we_done = if let Potato::Done = &send {
-                    true
-                } else {
-                    false
-                };
+                let we_done = matches!(send, Potato::Done);
 
-                let nop = if let Potato::Idle = &send {
-                    true
-                } else {
-                    false
-                };
+                let nop = matches!(send, Potato::Idle);
 
                 if !nop {
                     tx_1_2.send(send).unwrap();
@@ -186,17 +179,9 @@ mod tests {
                     }
                     let (new_me, send) = me.work();
 
-                    let we_done = if let Potato::Done = &send {
-                        true
-                    } else {
-                        false
-                    };
+                    let we_done = matches!(send, Potato::Done);
 
-                    let nop = if let Potato::Idle = &send {
-                        true
-                    } else {
-                        false
-                    };
+                    let nop = matches!(send, Potato::Idle);
 
                     if !nop {
                         tx.send(send).ok();
@@ -3,6 +3,7 @@ mod tests {
     use bbqueue::{BBQueue, StaticStorageProvider};
 
     #[test]
+    #[cfg_attr(miri, ignore = "too slow under miri")]
     fn sanity_check() {
         let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
         let (mut prod, mut cons) = bb.try_split().unwrap();
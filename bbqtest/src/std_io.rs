@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{BBQueue, StaticStorageProvider};
+    use std::io::{copy, ErrorKind, Read, Write};
+
+    #[test]
+    fn write_until_full() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        assert_eq!(prod.write(&[1, 2, 3, 4]).unwrap(), 4);
+        assert_eq!(prod.write(&[5, 6, 7, 8]).unwrap(), 2);
+
+        // The queue is full
+        assert_eq!(prod.write(&[9]).unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(
+            prod.write_all(&[9]).unwrap_err().kind(),
+            ErrorKind::WouldBlock
+        );
+        prod.flush().unwrap();
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[1, 2, 3, 4, 5, 6]);
+        rgr.release(6);
+
+        // Wraps around to the start of the ring
+        write!(prod, "hi").unwrap();
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, b"hi");
+    }
+
+    #[test]
+    fn write_grant_in_progress() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, _cons) = bb.try_split().unwrap();
+
+        let grant = prod.grant_exact(1).unwrap();
+        assert_eq!(prod.write(&[1]).unwrap_err().kind(), ErrorKind::Other);
+        grant.commit(1);
+
+        assert_eq!(prod.write(&[1]).unwrap(), 1);
+    }
+
+    #[test]
+    fn read_wraparound() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+        let mut buf = [0u8; 8];
+
+        // `Consumer::read` shadows the trait method, so call it explicitly.
+        // Empty queue
+        assert_eq!(
+            Read::read(&mut cons, &mut buf).unwrap_err().kind(),
+            ErrorKind::WouldBlock
+        );
+        assert_eq!(Read::read(&mut cons, &mut []).unwrap(), 0);
+
+        prod.write_all(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(Read::read(&mut cons, &mut buf[..3]).unwrap(), 3);
+        assert_eq!(&buf[..3], &[1, 2, 3]);
+
+        // Wrap around, the first call only returns the end of the ring
+        prod.write_all(&[5, 6]).unwrap();
+        prod.write_all(&[7, 8]).unwrap();
+        assert_eq!(Read::read(&mut cons, &mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], &[4, 5, 6]);
+        assert_eq!(Read::read(&mut cons, &mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], &[7, 8]);
+        assert_eq!(
+            Read::read(&mut cons, &mut buf).unwrap_err().kind(),
+            ErrorKind::WouldBlock
+        );
+    }
+
+    #[test]
+    fn io_copy() {
+        let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        // Much more data than the capacity, so the queue wraps around many times
+        let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        let mut to_write = &data[..];
+        let mut out = Vec::new();
+
+        while out.len() < data.len() {
+            // Fill the queue as much as possible
+            loop {
+                match prod.write(to_write) {
+                    Ok(0) => break,
+                    Ok(len) => to_write = &to_write[len..],
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(e) => panic!("{:?}", e),
+                }
+            }
+
+            // Drain it, until the consumer would block
+            match copy(&mut cons, &mut out) {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                other => panic!("{:?}", other),
+            }
+        }
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn read_to_end_after_close() {
+        let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        let (mut prod, mut cons) = bb.try_split().unwrap();
+
+        let mut out = Vec::new();
+        prod.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(
+            cons.read_to_end(&mut out).unwrap_err().kind(),
+            ErrorKind::WouldBlock
+        );
+
+        // The end of the stream, once the committed bytes are read
+        prod.write_all(&[4]).unwrap();
+        prod.close().unwrap();
+        out.clear();
+        assert_eq!(cons.read_to_end(&mut out).unwrap(), 1);
+        assert_eq!(out, [4]);
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use bbqueue::{typed::Element, BBQueue, Error as BBQError};
+
+    #[repr(align(8))]
+    struct Aligned<const N: usize>([u8; N]);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Sample {
+        timestamp: u32,
+        value: i16,
+        channel: u8,
+        flags: u8,
+    }
+
+    unsafe impl Element for Sample {}
+
+    #[test]
+    fn u16_roundtrip() {
+        let mut mem = Aligned([0u8; 16]);
+        let bb = BBQueue::new_from_slice(&mut mem.0);
+        let (mut prod, mut cons) = bb.try_split_typed::<u16>().unwrap();
+
+        assert_eq!(prod.capacity(), 8);
+        assert_eq!(cons.capacity(), 8);
+
+        let mut wgr = prod.grant_exact(3).unwrap();
+        assert_eq!(wgr.len(), 3);
+        wgr.copy_from_slice(&[0x0102, 0x0304, 0xFFFF]);
+        wgr.commit(3);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[0x0102, 0x0304, 0xFFFF]);
+        rgr.release(2);
+
+        let rgr = cons.read().unwrap();
+        assert_eq!(&*rgr, &[0xFFFF]);
+        rgr.release(1);
+
+        assert!(matches!(
+            cons.read(),
+            Err(BBQError::InsufficientSize { .. })
+        ));
+    }
+
+    #[test]
+    fn struct_wraparound() {
+        let mut mem = Aligned([0u8; 32]);
+        let bb = BBQueue::new_from_slice(&mut mem.0);
+        let (mut prod, mut cons) = bb.try_split_typed::<Sample>().unwrap();
+        assert_eq!(prod.capacity(), 4);
+
+        let sample = |i: u32| Sample {
+            timestamp: i,
+            value: -(i as i16),
+            channel: i as u8,
+            flags: 0xA5,
+        };
+
+        for i in 0..100 {
+            let mut wgr = prod.grant_max_remaining(3).unwrap();
+            let len = wgr.len();
+            assert!(len > 0 && len <= 3);
+            for (j, s) in wgr.iter_mut().enumerate() {
+                *s = sample(i + j as u32);
+            }
+            wgr.commit(len);
+
+            let rgr = cons.read().unwrap();
+            assert_eq!(rgr.len(), len);
+            for (j, s) in rgr.iter().enumerate() {
+                assert_eq!(*s, sample(i + j as u32));
+            }
+            rgr.release(len);
+        }
+    }
+
+    #[test]
+    fn odd_capacity() {
+        // 11 bytes only fit 5 `u16`s, the trailing byte is never used
+        let mut mem = Aligned([0u8; 16]);
+        let bb = BBQueue::new_from_slice(&mut mem.0[..11]);
+        let (mut prod, mut cons) = bb.try_split_typed::<u16>().unwrap();
+        assert_eq!(prod.capacity(), 5);
+
+        for i in 0..50u16 {
+            let mut wgr = prod.grant_max_remaining(2).unwrap();
+            let len = wgr.len();
+            for (j, v) in wgr.iter_mut().enumerate() {
+                *v = i.wrapping_mul(7).wrapping_add(j as u16);
+            }
+            wgr.commit(len);
+
+            let rgr = cons.read().unwrap();
+            assert_eq!(rgr.len(), len);
+            for (j, v) in rgr.iter().enumerate() {
+                assert_eq!(*v, i.wrapping_mul(7).wrapping_add(j as u16));
+            }
+            rgr.release(len);
+        }
+
+        // Exact grants never hand out the trailing byte either
+        assert!(prod.grant_exact(6).is_err());
+    }
+
+    #[test]
+    fn struct_odd_capacity() {
+        // 30 bytes only fit 3 `Sample`s, the trailing 6 bytes are never used
+        let mut mem = Aligned([0u8; 32]);
+        let bb = BBQueue::new_from_slice(&mut mem.0[..30]);
+        let (mut prod, mut cons) = bb.try_split_typed::<Sample>().unwrap();
+        assert_eq!(prod.capacity(), 3);
+
+        let sample = |i: u32| Sample {
+            timestamp: i,
+            value: i as i16,
+            channel: 3,
+            flags: 0,
+        };
+
+        // Exact grants wrap around before the trailing bytes
+        for i in 0..10 {
+            let mut wgr = prod.grant_exact(1).unwrap();
+            wgr[0] = sample(i);
+            wgr.commit(1);
+            assert!(bb.debug_positions().write <= 24);
+
+            let rgr = cons.read().unwrap();
+            assert_eq!(&*rgr, &[sample(i)]);
+            rgr.release(1);
+        }
+        assert!(prod.grant_exact(4).is_err());
+    }
+
+    #[test]
+    fn misaligned() {
+        let mut mem = Aligned([0u8; 17]);
+        let bb = BBQueue::new_from_slice(&mut mem.0[1..]);
+
+        assert!(matches!(
+            bb.try_split_typed::<u32>(),
+            Err(BBQError::Misaligned)
+        ));
+
+        // The failed split leaves the queue available
+        let (prod, cons) = bb.try_split_typed::<u8>().unwrap();
+        assert!(bb.try_release_typed(prod, cons).is_ok());
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut mem = Aligned([0u8; 16]);
+        let bb = BBQueue::new_from_slice(&mut mem.0);
+
+        assert!(matches!(
+            bb.try_split_typed::<[u8; 0]>(),
+            Err(BBQError::ZeroSizedElement)
+        ));
+        assert!(matches!(
+            bb.try_split_typed::<[u32; 0]>(),
+            Err(BBQError::ZeroSizedElement)
+        ));
+
+        // The failed split leaves the queue available
+        let (prod, cons) = bb.try_split_typed::<[u8; 2]>().unwrap();
+        assert_eq!(prod.capacity(), 8);
+        assert!(bb.try_release_typed(prod, cons).is_ok());
+    }
+}
@@ -1,45 +1,62 @@
-use atomic_waker::AtomicWaker;
+#[cfg(feature = "async-api")]
+use crate::waker::{WakerCell, WakerStorage};
 
+#[cfg(any(feature = "async-api", feature = "metrics", feature = "split-grants"))]
+use crate::sync::AtomicUsize;
+#[cfg(feature = "hooks")]
+use crate::BbqHooks;
 use crate::{
-    framed::{FrameConsumer, FrameProducer},
-    Error, Result, SliceStorageProvider, StaticStorageProvider, StorageProvider,
+    framed::{FrameConsumer, FrameProducer, TaggedFrameProducer},
+    index::{
+        from_const,
+        private::{AtomicIndex, ConstIndex},
+        zero,
+    },
+    sync::{const_fn, AtomicBool},
+    typed::{Element, TypedConsumer, TypedProducer},
+    Align, AlignedStorageProvider, Alignment, BbqIndex, ConstStorageProvider, Error, GrantWriter,
+    Result, SliceStorageProvider, StaticStorageProvider, StorageProvider,
 };
 use core::{
     cell::UnsafeCell,
     cmp::min,
-    future::Future,
+    fmt,
     marker::PhantomData,
-    mem::{forget, transmute},
+    mem::{align_of, forget, size_of, transmute},
     ops::{Deref, DerefMut},
-    pin::Pin,
     ptr::NonNull,
     result::Result as CoreResult,
     slice::{from_raw_parts, from_raw_parts_mut},
-    sync::atomic::{
-        AtomicBool, AtomicUsize,
-        Ordering::{AcqRel, Acquire, Release},
-    },
+    sync::atomic::Ordering::{AcqRel, Acquire, Release},
+};
+#[cfg(feature = "async-api")]
+use core::{
+    future::{poll_fn, Future},
+    pin::{pin, Pin},
     task::{Context, Poll},
 };
 
-#[derive(Debug)]
 /// A backing structure for a BBQueue. Can be used to create either
 /// a BBQueue or a split Producer/Consumer pair
-pub struct BBQueue<B>
+///
+/// The pointers into the buffer are stored as `I`, which limits the capacity
+/// of the queue, see [BbqIndex].
+pub struct BBQueue<B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     // The buffer provider
-    buf: UnsafeCell<B>,
+    pub(crate) buf: UnsafeCell<B>,
 
     // Max capacity of the buffer
     capacity: usize,
 
     // Where the next byte will be written
-    write: AtomicUsize,
+    pub(crate) write: I::Atomic,
 
     // Where the next byte will be read from
-    read: AtomicUsize,
+    pub(crate) read: I::Atomic,
 
     // Used in the inverted case to mark the end of the
     // readable streak. Otherwise will == sizeof::<self.buf>().
@@ -47,45 +64,225 @@ where
     // place when entering an inverted condition, and Reader
     // is responsible for moving it back to sizeof::<self.buf>()
     // when exiting the inverted condition
-    last: AtomicUsize,
+    pub(crate) last: I::Atomic,
 
     // Used by the Writer to remember what bytes are currently
     // allowed to be written to, but are not yet ready to be
     // read from
-    reserve: AtomicUsize,
+    pub(crate) reserve: I::Atomic,
 
     // Is there an active read grant?
-    read_in_progress: AtomicBool,
+    pub(crate) read_in_progress: AtomicBool,
 
     // Is there an active write grant?
-    write_in_progress: AtomicBool,
+    pub(crate) write_in_progress: AtomicBool,
 
     // Have we already split?
     already_split: AtomicBool,
 
+    // Has the producer closed the queue?
+    pub(crate) closed: AtomicBool,
+
+    // Minimum number of committed bytes before the read
+    // waker is woken up on commit
+    #[cfg(feature = "async-api")]
+    read_watermark: AtomicUsize,
+
+    // Parts of a write grant split by `GrantW::split_at` which are not committed
+    // yet, and the number of bytes committed by the head and the tail part
+    #[cfg(feature = "split-grants")]
+    split_parts: AtomicUsize,
+    #[cfg(feature = "split-grants")]
+    split_used: [AtomicUsize; 2],
+
+    // Maximum number of occupied bytes seen after a commit
+    #[cfg(feature = "metrics")]
+    high_water_mark: AtomicUsize,
+
+    // Activity counters, see `Stats`
+    #[cfg(feature = "metrics")]
+    pub(crate) counters: Counters,
+
+    // Hooks called around commits and reads, see `set_hooks`
+    #[cfg(feature = "hooks")]
+    hooks: UnsafeCell<Option<&'static dyn BbqHooks>>,
+
     // Read waker for async support
-    // Woken up when a commit is done
-    read_waker: AtomicWaker,
+    // Woken up when a commit is done, and at least
+    // `read_watermark` bytes are available
+    #[cfg(feature = "async-api")]
+    pub(crate) read_waker: WakerStorage,
 
     // Write waker for async support
     // Woken up when a release is done
-    write_waker: AtomicWaker,
+    #[cfg(feature = "async-api")]
+    pub(crate) write_waker: WakerStorage,
+}
+
+unsafe impl<B, I> Sync for BBQueue<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Counters of the activity of a queue, see [BBQueue::stats].
+///
+/// The counters are stored as `usize`, and wrap around on overflow.
+///
+/// Only available with the `metrics` feature.
+#[cfg(feature = "metrics")]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct Stats {
+    /// Total number of bytes committed by the producer
+    pub committed: u64,
+
+    /// Total number of bytes released by the consumer, including the bytes
+    /// discarded by [Producer::grant_exact_overwrite]
+    pub released: u64,
+
+    /// Maximum number of occupied bytes observed after a commit, see
+    /// [BBQueue::high_water_mark]
+    pub max_occupied: u64,
+
+    /// Number of write grants given to the producer
+    pub grants_granted: u64,
+
+    /// Number of write grants denied to the producer, because not
+    /// enough space was available
+    pub grants_denied: u64,
+}
+
+#[cfg(feature = "metrics")]
+pub(crate) struct Counters {
+    committed: AtomicUsize,
+    released: AtomicUsize,
+    grants_granted: AtomicUsize,
+    grants_denied: AtomicUsize,
+}
+
+#[cfg(feature = "metrics")]
+impl Counters {
+    const_fn! {
+        const fn new() -> Self {
+            Self {
+                committed: AtomicUsize::new(0),
+                released: AtomicUsize::new(0),
+                grants_granted: AtomicUsize::new(0),
+                grants_denied: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    pub(crate) fn record_grant<T>(&self, res: &Result<T>) {
+        match res {
+            Ok(_) => {
+                atomic::fetch_add(&self.grants_granted, 1, AcqRel);
+            }
+            Err(Error::InsufficientSize { .. } | Error::RequestTooLarge) => {
+                atomic::fetch_add(&self.grants_denied, 1, AcqRel);
+            }
+            Err(_) => {}
+        }
+    }
 }
 
-unsafe impl<B> Sync for BBQueue<B> where B: StorageProvider {}
+/// Positions of the pointers of a queue, see [BBQueue::debug_positions].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Positions {
+    /// Where the next byte will be written
+    pub write: usize,
 
-impl<'a, B> BBQueue<B>
+    /// Where the next byte will be read from
+    pub read: usize,
+
+    /// End of the readable bytes at the end of the ring, when the queue is inverted
+    pub last: usize,
+
+    /// End of the active write grant, or equal to `write` if there is none
+    pub reserve: usize,
+
+    /// Whether the writer has wrapped around to the start of the ring, and the
+    /// reader has not yet
+    pub inverted: bool,
+}
+
+/// Shows a snapshot of the current state of the queue, rather than the raw atomics
+/// and the contents of the buffer: the pointers, whether the queue is inverted, the
+/// number of occupied and free bytes, and the flags.
+///
+/// As the queue may be used concurrently, the values are loaded one after the other,
+/// and may not be consistent with each other.
+impl<B, I> fmt::Debug for BBQueue<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write = self.write.load(Acquire);
+        let read = self.read.load(Acquire);
+        f.debug_struct("BBQueue")
+            .field("capacity", &self.capacity)
+            .field("write", &write)
+            .field("read", &read)
+            .field("last", &self.last.load(Acquire))
+            .field("reserve", &self.reserve.load(Acquire))
+            .field("inverted", &(write < read))
+            .field("occupied", &self.occupied_len())
+            .field("free", &self.free_len())
+            .field("read_in_progress", &self.read_in_progress.load(Acquire))
+            .field("write_in_progress", &self.write_in_progress.load(Acquire))
+            .field("already_split", &self.already_split.load(Acquire))
+            .field("closed", &self.closed.load(Acquire))
+            .finish()
+    }
+}
+
+/// Compact snapshot of the current state of the queue, see the [fmt::Debug] implementation.
+#[cfg(feature = "defmt")]
+impl<B, I> defmt::Format for BBQueue<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let write = self.write.load(Acquire);
+        let read = self.read.load(Acquire);
+        defmt::write!(
+            f,
+            "BBQueue {{ capacity: {=usize}, write: {=usize}, read: {=usize}, last: {=usize}, reserve: {=usize}, inverted: {=bool}, occupied: {=usize}, free: {=usize}, read_in_progress: {=bool}, write_in_progress: {=bool}, already_split: {=bool}, closed: {=bool} }}",
+            self.capacity,
+            write,
+            read,
+            self.last.load(Acquire),
+            self.reserve.load(Acquire),
+            write < read,
+            self.occupied_len(),
+            self.free_len(),
+            self.read_in_progress.load(Acquire),
+            self.write_in_progress.load(Acquire),
+            self.already_split.load(Acquire),
+            self.closed.load(Acquire),
+        );
+    }
+}
+
+impl<'a, B, I> BBQueue<B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Attempt to split the `BBQueue` into `Consumer` and `Producer` halves to gain access to the
     /// buffer. If buffer has already been split, an error will be returned.
     ///
     /// NOTE: When splitting, the underlying buffer will be explicitly initialized
     /// to zero. This may take a measurable amount of time, depending on the size
-    /// of the buffer. This is necessary to prevent undefined behavior. If the buffer
-    /// is placed at `static` scope within the `.bss` region, the explicit initialization
-    /// will be elided (as it is already performed as part of memory initialization)
+    /// of the buffer. This is necessary to prevent undefined behavior. If the storage
+    /// provider is known to be zeroed, see [StorageProvider::is_zeroed], the explicit
+    /// initialization will be elided. This is the case of a [StaticStorageProvider](crate::StaticStorageProvider),
+    /// which is zeroed as part of memory initialization when placed in the `.bss` region.
     ///
     /// NOTE:  If the `thumbv6` feature is selected, this function takes a short critical section
     /// while splitting.
@@ -93,10 +290,10 @@ where
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (prod, cons) = buffer.try_split().unwrap();
     ///
     /// // Not possible to split twice
@@ -109,7 +306,7 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub fn try_split(&'a self) -> Result<(Producer<'a, B>, Consumer<'a, B>)> {
+    pub fn try_split(&'a self) -> Result<(Producer<'a, B, I>, Consumer<'a, B, I>)> {
         if atomic::swap(&self.already_split, true, AcqRel) {
             return Err(Error::AlreadySplit);
         }
@@ -117,9 +314,13 @@ where
         unsafe {
             // Explicitly zero the data to avoid undefined behavior.
             // This is required, because we hand out references to the buffers,
-            // which mean that creating them as references is technically UB for now
-            let mu_ptr = (&mut *self.buf.get()).storage().as_mut();
-            (*mu_ptr).as_mut_ptr().write_bytes(0u8, 1);
+            // and the storage may be uninitialized. Only write through a raw
+            // pointer here, as creating a reference to it would already be UB
+            let buf = &*self.buf.get();
+            if !buf.is_zeroed() {
+                let storage = buf.storage();
+                (storage.as_ptr() as *mut u8).write_bytes(0u8, storage.len());
+            }
 
             let nn1 = NonNull::new_unchecked(self as *const _ as *mut _);
             let nn2 = NonNull::new_unchecked(self as *const _ as *mut _);
@@ -127,10 +328,12 @@ where
                 Producer {
                     bbq: nn1,
                     pd: PhantomData,
+                    reborrowed: false,
                 },
                 Consumer {
                     bbq: nn2,
                     pd: PhantomData,
+                    reborrowed: false,
                 },
             ))
         }
@@ -144,13 +347,56 @@ where
     /// to zero. This may take a measurable amount of time, depending on the size
     /// of the buffer. This is necessary to prevent undefined behavior. If the buffer
     /// is placed at `static` scope within the `.bss` region, the explicit initialization
-    /// will be elided (as it is already performed as part of memory initialization)
+    /// will be elided (as it is already performed as part of memory initialization).
+    /// See [Self::try_split] for details.
+    ///
+    /// If the capacity is too small to hold a frame of one byte with its header, see
+    /// [FrameProducer::frame_len], [Error::CapacityTooSmall] is returned and the buffer
+    /// remains unsplit.
     ///
     /// NOTE:  If the `thumbv6` feature is selected, this function takes a short critical
     /// section while splitting.
-    pub fn try_split_framed(&'a self) -> Result<(FrameProducer<'a, B>, FrameConsumer<'a, B>)> {
+    pub fn try_split_framed(
+        &'a self,
+    ) -> Result<(FrameProducer<'a, B, I>, FrameConsumer<'a, B, I>)> {
+        if self.capacity < FrameProducer::<B, I>::frame_len(1) {
+            return Err(Error::CapacityTooSmall);
+        }
+
+        let (producer, consumer) = self.try_split()?;
+        Ok((
+            FrameProducer { producer },
+            FrameConsumer {
+                consumer,
+                tagged: false,
+            },
+        ))
+    }
+
+    /// Attempt to split the `BBQueue` into `FrameConsumer` and `TaggedFrameProducer` halves,
+    /// where each frame carries a tag byte. If buffer has already been split, an error will
+    /// be returned.
+    ///
+    /// If the capacity is too small to hold a frame of one byte with its header and its tag,
+    /// see [TaggedFrameProducer::frame_len], [Error::CapacityTooSmall] is returned and the
+    /// buffer remains unsplit.
+    ///
+    /// See [Self::try_split_framed] for details.
+    pub fn try_split_framed_tagged(
+        &'a self,
+    ) -> Result<(TaggedFrameProducer<'a, B, I>, FrameConsumer<'a, B, I>)> {
+        if self.capacity < TaggedFrameProducer::<B, I>::frame_len(1) {
+            return Err(Error::CapacityTooSmall);
+        }
+
         let (producer, consumer) = self.try_split()?;
-        Ok((FrameProducer { producer }, FrameConsumer { consumer }))
+        Ok((
+            TaggedFrameProducer { producer },
+            FrameConsumer {
+                consumer,
+                tagged: true,
+            },
+        ))
     }
 
     /// Attempt to release the Producer and Consumer
@@ -158,21 +404,33 @@ where
     /// This re-initializes the buffer so it may be split in a different mode at a later
     /// time. There must be no read or write grants active, or an error will be returned.
     ///
+    /// All committed bytes must have been released by the consumer, or an error will be
+    /// returned, as they would be lost. See [Self::force_release_and_clear] to discard them.
+    ///
     /// The `Producer` and `Consumer` must be from THIS `BBQueue`, or an error will
     /// be returned.
     ///
+    /// If the storage provider is known to be zeroed, see [StorageProvider::is_zeroed], the
+    /// buffer is zeroed again, as it won't be initialized by the next split.
+    ///
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (prod, cons) = buffer.try_split().unwrap();
     ///
     /// // Not possible to split twice
     /// assert!(buffer.try_split().is_err());
     ///
+    /// // Not possible to release with unread bytes
+    /// let (mut prod, mut cons) = (prod, cons);
+    /// prod.grant_exact(2).unwrap().commit(2);
+    /// let (prod, mut cons) = buffer.try_release(prod, cons).unwrap_err();
+    /// cons.read().unwrap().release(2);
+    ///
     /// // Release the producer and consumer
     /// assert!(buffer.try_release(prod, cons).is_ok());
     ///
@@ -186,11 +444,60 @@ where
     /// # bbqtest();
     /// # }
     /// ```
+    #[allow(clippy::type_complexity)]
     pub fn try_release(
         &'a self,
-        prod: Producer<'a, B>,
-        cons: Consumer<'a, B>,
-    ) -> CoreResult<(), (Producer<'a, B>, Consumer<'a, B>)> {
+        prod: Producer<'a, B, I>,
+        cons: Consumer<'a, B, I>,
+    ) -> CoreResult<(), (Producer<'a, B, I>, Consumer<'a, B, I>)> {
+        self.release_inner(prod, cons, false)
+    }
+
+    /// Release the Producer and Consumer, discarding the committed bytes which were not
+    /// released by the consumer yet.
+    ///
+    /// Same as [Self::try_release], but the queue doesn't need to be empty. There must
+    /// still be no read or write grants active, and the `Producer` and `Consumer` must be
+    /// from THIS `BBQueue`, or an error will be returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    /// prod.grant_exact(2).unwrap().commit(2);
+    ///
+    /// // The unread bytes are dropped
+    /// assert!(buffer.force_release_and_clear(prod, cons).is_ok());
+    /// let (_prod, mut cons) = buffer.try_split().unwrap();
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn force_release_and_clear(
+        &'a self,
+        prod: Producer<'a, B, I>,
+        cons: Consumer<'a, B, I>,
+    ) -> CoreResult<(), (Producer<'a, B, I>, Consumer<'a, B, I>)> {
+        self.release_inner(prod, cons, true)
+    }
+
+    /// Release the Producer and Consumer, if `force` is false the queue must be empty
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn release_inner(
+        &'a self,
+        prod: Producer<'a, B, I>,
+        cons: Consumer<'a, B, I>,
+        force: bool,
+    ) -> CoreResult<(), (Producer<'a, B, I>, Consumer<'a, B, I>)> {
         // Note: Re-entrancy is not possible because we require ownership
         // of the producer and consumer, which are not cloneable. We also
         // can assume the buffer has been split, because
@@ -204,6 +511,11 @@ where
             return Err((prod, cons));
         }
 
+        if prod.reborrowed || cons.reborrowed {
+            // Can't release, the original halves are still alive
+            return Err((prod, cons));
+        }
+
         let wr_in_progress = self.write_in_progress.load(Acquire);
         let rd_in_progress = self.read_in_progress.load(Acquire);
 
@@ -212,15 +524,29 @@ where
             return Err((prod, cons));
         }
 
+        if !force && self.occupied_len() != 0 {
+            // Can't release, the unread bytes would be lost
+            return Err((prod, cons));
+        }
+
         // Drop the producer and consumer halves
-        drop(prod);
-        drop(cons);
+        let _ = (prod, cons);
 
         // Re-initialize the buffer (not totally needed, but nice to do)
         self.write.store(0, Release);
         self.read.store(0, Release);
         self.reserve.store(0, Release);
         self.last.store(0, Release);
+        self.closed.store(false, Release);
+
+        // Storage known to be zeroed isn't initialized when splitting, zero it again
+        unsafe {
+            let buf = &*self.buf.get();
+            if buf.is_zeroed() {
+                let storage = buf.storage();
+                (storage.as_ptr() as *mut u8).write_bytes(0u8, storage.len());
+            }
+        }
 
         // Mark the buffer as ready to be split
         self.already_split.store(false, Release);
@@ -231,148 +557,474 @@ where
     /// Attempt to release the Producer and Consumer in Framed mode
     ///
     /// This re-initializes the buffer so it may be split in a different mode at a later
-    /// time. There must be no read or write grants active, or an error will be returned.
+    /// time. There must be no read or write grants active, and no unread bytes, or an
+    /// error will be returned, see [Self::try_release].
     ///
     /// The `FrameProducer` and `FrameConsumer` must be from THIS `BBQueue`, or an error
     /// will be returned.
+    #[allow(clippy::type_complexity)]
     pub fn try_release_framed(
         &'a self,
-        prod: FrameProducer<'a, B>,
-        cons: FrameConsumer<'a, B>,
-    ) -> CoreResult<(), (FrameProducer<'a, B>, FrameConsumer<'a, B>)> {
+        prod: FrameProducer<'a, B, I>,
+        cons: FrameConsumer<'a, B, I>,
+    ) -> CoreResult<(), (FrameProducer<'a, B, I>, FrameConsumer<'a, B, I>)> {
+        let tagged = cons.tagged;
+        self.try_release(prod.producer, cons.consumer)
+            .map_err(|(producer, consumer)| {
+                // Restore the wrapper types
+                (
+                    FrameProducer { producer },
+                    FrameConsumer { consumer, tagged },
+                )
+            })
+    }
+
+    /// Attempt to release the Producer and Consumer in tagged Framed mode
+    ///
+    /// See [Self::try_release_framed] for details.
+    #[allow(clippy::type_complexity)]
+    pub fn try_release_framed_tagged(
+        &'a self,
+        prod: TaggedFrameProducer<'a, B, I>,
+        cons: FrameConsumer<'a, B, I>,
+    ) -> CoreResult<(), (TaggedFrameProducer<'a, B, I>, FrameConsumer<'a, B, I>)> {
+        let tagged = cons.tagged;
+        self.try_release(prod.producer, cons.consumer)
+            .map_err(|(producer, consumer)| {
+                // Restore the wrapper types
+                (
+                    TaggedFrameProducer { producer },
+                    FrameConsumer { consumer, tagged },
+                )
+            })
+    }
+
+    /// Attempt to split the `BBQueue` into `TypedConsumer` and `TypedProducer` halves,
+    /// passing elements of type `T`. If buffer has already been split, an error
+    /// will be returned.
+    ///
+    /// The underlying storage must be aligned to `align_of::<T>()`, otherwise
+    /// [Error::Misaligned] will be returned and the buffer will remain unsplit.
+    /// Zero sized element types, such as `[u8; 0]`, are rejected with
+    /// [Error::ZeroSizedElement].
+    ///
+    /// See [Self::try_split] for details on initialization of the buffer.
+    #[allow(clippy::type_complexity)]
+    pub fn try_split_typed<T: Element>(
+        &'a self,
+    ) -> Result<(TypedProducer<'a, B, T, I>, TypedConsumer<'a, B, T, I>)> {
+        if size_of::<T>() == 0 {
+            return Err(Error::ZeroSizedElement);
+        }
+
+        let base = unsafe { (*self.buf.get()).storage().as_ptr() as *mut u8 };
+        if (base as usize) & (align_of::<T>() - 1) != 0 {
+            return Err(Error::Misaligned);
+        }
+
+        let (producer, consumer) = self.try_split()?;
+        Ok((
+            TypedProducer {
+                producer,
+                pd: PhantomData,
+            },
+            TypedConsumer {
+                consumer,
+                pd: PhantomData,
+            },
+        ))
+    }
+
+    /// Attempt to release the Producer and Consumer in Typed mode
+    ///
+    /// This re-initializes the buffer so it may be split in a different mode at a later
+    /// time. There must be no read or write grants active, and no unread bytes, or an
+    /// error will be returned, see [Self::try_release].
+    ///
+    /// The `TypedProducer` and `TypedConsumer` must be from THIS `BBQueue`, or an error
+    /// will be returned.
+    #[allow(clippy::type_complexity)]
+    pub fn try_release_typed<T: Element>(
+        &'a self,
+        prod: TypedProducer<'a, B, T, I>,
+        cons: TypedConsumer<'a, B, T, I>,
+    ) -> CoreResult<(), (TypedProducer<'a, B, T, I>, TypedConsumer<'a, B, T, I>)> {
         self.try_release(prod.producer, cons.consumer)
             .map_err(|(producer, consumer)| {
                 // Restore the wrapper types
-                (FrameProducer { producer }, FrameConsumer { consumer })
+                (
+                    TypedProducer {
+                        producer,
+                        pd: PhantomData,
+                    },
+                    TypedConsumer {
+                        consumer,
+                        pd: PhantomData,
+                    },
+                )
             })
     }
+
+    /// Reset the queue to its initial, empty state, and zero its storage.
+    ///
+    /// As this takes `&mut self`, it can only be called while no producer, consumer or
+    /// grant borrows the queue, for example after [Self::try_release], or after the halves
+    /// were dropped. Unlike [Self::try_release], this also resets the queue if the halves
+    /// were dropped without being released, or if a grant was leaked, and the queue can be
+    /// split again afterwards. With the `metrics` feature, the activity counters and the high
+    /// water mark are reset as well. The read watermark is kept.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// drop((prod, cons));
+    ///
+    /// buffer.clear();
+    /// assert_eq!(buffer.occupied_len(), 0);
+    ///
+    /// // The queue can be split again, and the old data is gone
+    /// let (mut prod, _cons) = buffer.try_split().unwrap();
+    /// assert_eq!(&*prod.grant_exact(6).unwrap(), &[0; 6]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        self.write = zero::<I>();
+        self.read = zero::<I>();
+        self.last = zero::<I>();
+        self.reserve = zero::<I>();
+        self.read_in_progress = AtomicBool::new(false);
+        self.write_in_progress = AtomicBool::new(false);
+        self.already_split = AtomicBool::new(false);
+        self.closed = AtomicBool::new(false);
+
+        #[cfg(feature = "metrics")]
+        {
+            self.high_water_mark = AtomicUsize::new(0);
+            self.counters = Counters::new();
+        }
+
+        // We have exclusive access, nothing else may be using the storage.
+        // Same as when splitting, don't create a reference to it
+        let storage = self.buf.get_mut().storage();
+        unsafe { (storage.as_ptr() as *mut u8).write_bytes(0u8, storage.len()) };
+    }
 }
 
-impl<B> BBQueue<B>
+impl<B, I> BBQueue<B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    /// Create a new BBQueue with abstraction over the memory provider
+    /// Same as [BBQueue::new], for a queue using `I` to store its pointers, see
+    /// [BbqIndex].
     ///
-    /// ```rust,no_run
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// # Panics
     ///
+    /// Panics if the capacity of the storage, see [StorageProvider::capacity], is zero
+    /// or larger than [BbqIndex::MAX].
+    ///
+    /// ```rust,no_run
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// fn main() {
-    ///    let provider = StaticBufferProvider::<6>::new();
-    ///    let mut buf = BBQueue::new(provider);
+    ///    let provider = StaticStorageProvider::<200>::new();
+    ///    let mut buf = BBQueue::<_, u8>::new_with_index(provider);
     ///    let (prod, cons) = buf.try_split().unwrap();
     /// }
     /// ```
-    pub fn new(buf: B) -> Self {
-        Self {
-            capacity: unsafe { buf.storage().as_ref().len() },
-
-            // This will not be initialized until we split the buffer
-            buf: UnsafeCell::new(buf),
-
-            // Owned by the writer
-            write: AtomicUsize::new(0),
-
-            // Owned by the reader
-            read: AtomicUsize::new(0),
-
-            // Cooperatively owned
-            //
-            // NOTE: This should generally be initialized as size_of::<self.buf>(), however
-            // this would prevent the structure from being entirely zero-initialized,
-            // and can cause the .data section to be much larger than necessary. By
-            // forcing the `last` pointer to be zero initially, we place the structure
-            // in an "inverted" condition, which will be resolved on the first commited
-            // bytes that are written to the structure.
-            //
-            // When read == last == write, no bytes will be allowed to be read (good), but
-            // write grants can be given out (also good).
-            last: AtomicUsize::new(0),
-
-            // Owned by the Writer, "private"
-            reserve: AtomicUsize::new(0),
-
-            // Owned by the Reader, "private"
-            read_in_progress: AtomicBool::new(false),
-
-            // Owned by the Writer, "private"
-            write_in_progress: AtomicBool::new(false),
-
-            // We haven't split at the start
-            already_split: AtomicBool::new(false),
+    pub fn new_with_index(buf: B) -> Self {
+        let capacity = buf.capacity();
+        Self::new_with_capacity(buf, capacity, zero::<I>())
+    }
 
-            // Shared between reader and writer.
-            read_waker: AtomicWaker::new(),
+    const_fn! {
+        /// Same as [BBQueue::new_with_index], but usable in a `const` context, for a
+        /// provider whose capacity is known at compile time.
+        ///
+        /// # Panics
+        ///
+        /// Panics if [ConstStorageProvider::CAPACITY] is zero or larger than
+        /// [BbqIndex::MAX], which is checked at compile time when creating a `static` queue.
+        ///
+        /// ```rust
+        /// # // bbqueue test shim!
+        /// # fn bbqtest() {
+        /// use bbqueue::{BBQueue, ConstStorageProvider, StorageProvider};
+        /// use core::{cell::UnsafeCell, ptr::NonNull};
+        ///
+        /// // A buffer which could be placed in a dedicated memory region
+        /// struct Region {
+        ///     buf: UnsafeCell<[u8; 64]>,
+        /// }
+        ///
+        /// impl Region {
+        ///     const fn new() -> Self {
+        ///         Self { buf: UnsafeCell::new([0; 64]) }
+        ///     }
+        /// }
+        ///
+        /// impl PartialEq for Region {
+        ///     fn eq(&self, other: &Self) -> bool {
+        ///         core::ptr::eq(self, other)
+        ///     }
+        /// }
+        ///
+        /// impl StorageProvider for Region {
+        ///     fn storage(&self) -> NonNull<[u8]> {
+        ///         NonNull::new(self.buf.get()).unwrap()
+        ///     }
+        /// }
+        ///
+        /// impl ConstStorageProvider for Region {
+        ///     const CAPACITY: usize = 64;
+        /// }
+        ///
+        /// static BUF: BBQueue<Region> = BBQueue::new_const(Region::new());
+        ///
+        /// let (mut prod, cons) = BUF.try_split().unwrap();
+        /// prod.grant_exact(64).unwrap().commit(64);
+        /// assert_eq!(cons.len(), 64);
+        /// # // bbqueue test shim!
+        /// # }
+        /// #
+        /// # fn main() {
+        /// # #[cfg(not(feature = "thumbv6"))]
+        /// # bbqtest();
+        /// # }
+        /// ```
+        pub const fn new_const(buf: B) -> Self
+        where
+            B: ConstStorageProvider,
+        {
+            Self::new_with_capacity(buf, B::CAPACITY, zero::<I>())
+        }
+    }
 
-            // Shared between reader and writer
-            write_waker: AtomicWaker::new(),
+    const_fn! {
+        /// Create a new BBQueue, where `capacity` is the size of the storage of `buf`
+        const fn new_with_capacity(buf: B, capacity: usize, last: I::Atomic) -> Self {
+            assert!(capacity != 0, "the capacity of the queue must not be zero");
+            assert!(
+                capacity <= I::MAX,
+                "the capacity of the queue is too large for its index type"
+            );
+
+            Self {
+                capacity,
+
+                // This will not be initialized until we split the buffer
+                buf: UnsafeCell::new(buf),
+
+                // Owned by the writer
+                write: zero::<I>(),
+
+                // Owned by the reader
+                read: zero::<I>(),
+
+                // Cooperatively owned
+                //
+                // NOTE: This should generally be initialized as size_of::<self.buf>(), however
+                // this would prevent the structure from being entirely zero-initialized,
+                // and can cause the .data section to be much larger than necessary. By
+                // forcing the `last` pointer to be zero initially, we place the structure
+                // in an "inverted" condition, which will be resolved on the first commited
+                // bytes that are written to the structure.
+                //
+                // When read == last == write, no bytes will be allowed to be read (good), but
+                // write grants can be given out (also good).
+                //
+                // `new_static_initialized` opts out of this, and starts with last == capacity.
+                last,
+
+                // Owned by the Writer, "private"
+                reserve: zero::<I>(),
+
+                // Owned by the Reader, "private"
+                read_in_progress: AtomicBool::new(false),
+
+                // Owned by the Writer, "private"
+                write_in_progress: AtomicBool::new(false),
+
+                // We haven't split at the start
+                already_split: AtomicBool::new(false),
+
+                // Set by the Writer, once
+                closed: AtomicBool::new(false),
+
+                // Wake the reader on every commit by default
+                #[cfg(feature = "async-api")]
+                read_watermark: AtomicUsize::new(1),
+
+                // Owned by the Writer, "private"
+                #[cfg(feature = "split-grants")]
+                split_parts: AtomicUsize::new(0),
+                #[cfg(feature = "split-grants")]
+                split_used: [AtomicUsize::new(0), AtomicUsize::new(0)],
+
+                // Nothing was committed yet
+                #[cfg(feature = "metrics")]
+                high_water_mark: AtomicUsize::new(0),
+
+                #[cfg(feature = "metrics")]
+                counters: Counters::new(),
+
+                // No hooks until `set_hooks` is called
+                #[cfg(feature = "hooks")]
+                hooks: UnsafeCell::new(None),
+
+                // Shared between reader and writer.
+                #[cfg(feature = "async-api")]
+                read_waker: WakerStorage::new(),
+
+                // Shared between reader and writer
+                #[cfg(feature = "async-api")]
+                write_waker: WakerStorage::new(),
+            }
         }
     }
 }
 
-impl<const N: usize> BBQueue<StaticStorageProvider<N>> {
-    /// Create a new constant static BBQ, using staic memory allocation
+impl<B> BBQueue<B>
+where
+    B: StorageProvider,
+{
+    /// Create a new BBQueue with abstraction over the memory provider
+    ///
+    /// See [Self::new_with_index] to use a smaller index type than `usize`, and
+    /// [Self::new_const] to create a queue in a `const` context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity of the storage is zero.
+    ///
     /// ```rust,no_run
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
-    /// static BUF: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
     ///
     /// fn main() {
-    ///    let (prod, cons) = BUF.try_split().unwrap();
+    ///    let provider = StaticStorageProvider::<6>::new();
+    ///    let mut buf = BBQueue::new(provider);
+    ///    let (prod, cons) = buf.try_split().unwrap();
     /// }
     /// ```
-    pub const fn new_static() -> Self {
-        Self {
-            capacity: N,
-
-            // This will not be initialized until we split the buffer
-            buf: UnsafeCell::new(StaticStorageProvider::new()),
-
-            // Owned by the writer
-            write: AtomicUsize::new(0),
-
-            // Owned by the reader
-            read: AtomicUsize::new(0),
-
-            // Cooperatively owned
-            //
-            // NOTE: This should generally be initialized as size_of::<self.buf>(), however
-            // this would prevent the structure from being entirely zero-initialized,
-            // and can cause the .data section to be much larger than necessary. By
-            // forcing the `last` pointer to be zero initially, we place the structure
-            // in an "inverted" condition, which will be resolved on the first commited
-            // bytes that are written to the structure.
-            //
-            // When read == last == write, no bytes will be allowed to be read (good), but
-            // write grants can be given out (also good).
-            last: AtomicUsize::new(0),
-
-            // Owned by the Writer, "private"
-            reserve: AtomicUsize::new(0),
-
-            // Owned by the Reader, "private"
-            read_in_progress: AtomicBool::new(false),
-
-            // Owned by the Writer, "private"
-            write_in_progress: AtomicBool::new(false),
+    pub fn new(buf: B) -> Self {
+        Self::new_with_index(buf)
+    }
+}
 
-            // We haven't split at the start
-            already_split: AtomicBool::new(false),
+impl<const N: usize, I> BBQueue<StaticStorageProvider<N>, I>
+where
+    I: BbqIndex,
+{
+    const_fn! {
+        /// Create a new constant static BBQ, using staic memory allocation
+        ///
+        /// The capacity `N` must not be zero, nor larger than [BbqIndex::MAX], which is
+        /// checked at compile time when creating a `static` queue.
+        /// ```rust,no_run
+        /// use bbqueue::{BBQueue, StaticStorageProvider};
+        ///
+        /// static BUF: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+        ///
+        /// fn main() {
+        ///    let (prod, cons) = BUF.try_split().unwrap();
+        /// }
+        /// ```
+        ///
+        /// ```rust,compile_fail
+        /// use bbqueue::{BBQueue, StaticStorageProvider};
+        ///
+        /// // A queue without storage can't hold anything
+        /// static BUF: BBQueue<StaticStorageProvider<0>> = BBQueue::new_static();
+        /// ```
+        pub const fn new_static() -> Self {
+            Self::new_with_capacity(StaticStorageProvider::new(), N, zero::<I>())
+        }
+    }
+}
 
-            // Shared between reader and writer.
-            read_waker: AtomicWaker::new(),
+impl<const N: usize, I> BBQueue<StaticStorageProvider<N>, I>
+where
+    I: BbqIndex + ConstIndex<N>,
+{
+    const_fn! {
+        /// Same as [Self::new_static], but the queue starts out with its `last` pointer at
+        /// the end of the ring, rather than at zero.
+        ///
+        /// A queue created by [Self::new_static] is entirely zero-initialized, so it can be
+        /// placed in `.bss`, and starts in an "inverted" state which is resolved by the first
+        /// commit. A queue created by this function is placed in `.data` instead, which takes
+        /// up `N` extra bytes of flash, but starts out in the same state as any empty queue.
+        /// Both behave the same otherwise.
+        ///
+        /// Like [Self::new_static], the capacity `N` must not be zero, nor larger than
+        /// [BbqIndex::MAX], which is checked at compile time when creating a `static` queue.
+        ///
+        /// ```rust,no_run
+        /// use bbqueue::{BBQueue, StaticStorageProvider};
+        ///
+        /// static BUF: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static_initialized();
+        ///
+        /// fn main() {
+        ///    let (prod, cons) = BUF.try_split().unwrap();
+        /// }
+        /// ```
+        ///
+        /// ```rust,compile_fail
+        /// use bbqueue::{BBQueue, StaticStorageProvider};
+        ///
+        /// // 256 bytes can't be indexed with a u8
+        /// static BUF: BBQueue<StaticStorageProvider<256>, u8> = BBQueue::new_static_initialized();
+        /// ```
+        pub const fn new_static_initialized() -> Self {
+            Self::new_with_capacity(StaticStorageProvider::new(), N, from_const::<I, N>())
+        }
+    }
+}
 
-            // Shared between reader and writer
-            write_waker: AtomicWaker::new(),
+impl<const N: usize, const ALIGN: usize, I> BBQueue<AlignedStorageProvider<N, ALIGN>, I>
+where
+    Align<ALIGN>: Alignment,
+    I: BbqIndex,
+{
+    const_fn! {
+        /// Create a new constant static BBQ, using static memory allocation aligned
+        /// to `ALIGN` bytes. See [Producer::grant_exact_aligned] to obtain aligned grants.
+        /// ```rust,no_run
+        /// use bbqueue::{AlignedStorageProvider, BBQueue};
+        ///
+        /// static BUF: BBQueue<AlignedStorageProvider<64, 32>> = BBQueue::new_static_aligned();
+        ///
+        /// fn main() {
+        ///    let (prod, cons) = BUF.try_split().unwrap();
+        /// }
+        /// ```
+        pub const fn new_static_aligned() -> Self {
+            Self::new_with_capacity(AlignedStorageProvider::new(), N, zero::<I>())
         }
     }
 }
 
 impl<'a> BBQueue<SliceStorageProvider<'a>> {
     /// Create a new BBQueue using userspace provided memory in the form of a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty.
+    ///
     /// ```rust,no_run
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// fn main() {
     ///    let mut bb_memory = [0; 6];
@@ -396,32 +1048,73 @@ impl<'a> BBQueue<SliceStorageProvider<'a>> {
 /// * `grant_exact(N)`
 ///   * User will receive a grant `sz == N` (or receive an error)
 ///   * This may cause a wraparound if a grant of size N is not available
-///       at the end of the ring.
+///     at the end of the ring.
 ///   * If this grant caused a wraparound, the bytes that were "skipped" at the
-///       end of the ring will not be available until the reader reaches them,
-///       regardless of whether the grant commited any data or not.
+///     end of the ring will not be available until the reader reaches them,
+///     regardless of whether the grant commited any data or not.
 ///   * Maximum possible waste due to skipping: `N - 1` bytes
 /// * `grant_max_remaining(N)`
 ///   * User will receive a grant `0 < sz <= N` (or receive an error)
 ///   * This will only cause a wrap to the beginning of the ring if exactly
-///       zero bytes are available at the end of the ring.
+///     zero bytes are available at the end of the ring.
 ///   * Maximum possible waste due to skipping: 0 bytes
+/// * `grant_largest()`
+///   * User will receive the largest contiguous grant available (or receive an error)
+///   * This will cause a wraparound if more bytes are available at the beginning
+///     of the ring than at the end of the ring.
+///   * Maximum possible waste due to skipping: the end of the ring, which is
+///     smaller than the grant
+/// * `grant_exact_aligned(N, A)`
+///   * User will receive a grant `sz == N` starting at an address aligned
+///     to `A` bytes (or receive an error)
+///   * Like `grant_exact(N)`, but this will also cause a wraparound if the
+///     write position is not aligned to `A` bytes.
+///   * Maximum possible waste due to skipping: the whole end of the ring,
+///     unless all grants are a multiple of `A` bytes
 ///
 /// See [this github issue](https://github.com/jamesmunns/bbqueue/issues/38) for a
 /// discussion of grant methods that could be added in the future.
-pub struct Producer<'a, B>
+///
+/// The `Producer` is not `Clone`, as the queue only supports a single producer at a
+/// time. [Producer::reborrow] gives a temporary `Producer` instead, and a
+/// [SharedProducer](crate::multi::SharedProducer) may be used by several tasks.
+pub struct Producer<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) bbq: NonNull<BBQueue<B, I>>,
+    pub(crate) pd: PhantomData<&'a ()>,
+    // Obtained with `reborrow`, which can't release the queue
+    pub(crate) reborrowed: bool,
+}
+
+unsafe impl<'a, B, I> Send for Producer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    bbq: NonNull<BBQueue<B>>,
-    pd: PhantomData<&'a ()>,
 }
 
-unsafe impl<'a, B> Send for Producer<'a, B> where B: StorageProvider {}
+/// Shows the address and the capacity of the queue
+impl<'a, B, I> fmt::Debug for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("queue", &self.bbq)
+            .field("capacity", &unsafe { self.bbq.as_ref() }.capacity())
+            .field("reborrowed", &self.reborrowed)
+            .finish()
+    }
+}
 
-impl<'a, B> Producer<'a, B>
+impl<'a, B, I> Producer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Request a writable, contiguous section of memory of exactly
     /// `sz` bytes. If the buffer size requested is not available,
@@ -431,13 +1124,26 @@ where
     /// requested space is not available at the end of the buffer, but
     /// is available at the beginning
     ///
+    /// The possible errors are:
+    ///
+    /// * [Error::InsufficientSize] if the space is not available right now. The request
+    ///   may succeed later, once the [Consumer] has released some bytes, unless it can't
+    ///   be given from the current write position (see [Self::grant_exact_async], which
+    ///   waits for an empty queue in that case).
+    /// * [Error::RequestTooLarge] if `sz` is larger than the capacity of the queue. The
+    ///   request can never succeed, and shouldn't be retried.
+    /// * [Error::GrantInProgress] if a write grant obtained earlier is still alive, as
+    ///   grants do not borrow the producer. This usually indicates a bug in the caller,
+    ///   such as a grant kept around or leaked with [core::mem::forget], and retrying
+    ///   won't help until that grant is committed or dropped.
+    ///
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, Error, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, cons) = buffer.try_split().unwrap();
     ///
     /// // Successfully obtain and commit a grant of four bytes
@@ -445,8 +1151,15 @@ where
     /// assert_eq!(grant.buf().len(), 4);
     /// grant.commit(4);
     ///
-    /// // Try to obtain a grant of three bytes
-    /// assert!(prod.grant_exact(3).is_err());
+    /// // Try to obtain a grant of three bytes, only two are left at the end of the ring
+    /// assert_eq!(
+    ///     prod.grant_exact(3).unwrap_err(),
+    ///     Error::InsufficientSize { requested: 3, available: 2 }
+    /// );
+    ///
+    /// // Only one write grant may be alive at a time
+    /// let grant = prod.grant_exact(1).unwrap();
+    /// assert_eq!(prod.grant_exact(1).unwrap_err(), Error::GrantInProgress);
     /// # // bbqueue test shim!
     /// # }
     /// #
@@ -455,9 +1168,36 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub fn grant_exact(&mut self, sz: usize) -> Result<GrantW<'a, B>> {
+    pub fn grant_exact(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        let res = self.raw_grant_exact(sz, 1, true);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    /// Same as [Self::grant_exact], but the grant will always start at an address
+    /// aligned to `align` bytes. `align == 1` behaves like [Self::grant_exact].
+    fn raw_grant_exact(&mut self, sz: usize, align: usize, wrap: bool) -> Result<GrantW<'a, B, I>> {
         let inner = unsafe { &self.bbq.as_ref() };
 
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
+        let is_aligned = |offset: usize| (start_of_buf_ptr as usize + offset) % align == 0;
+
+        // Wrapping around is the only way to skip bytes, so the start
+        // of the ring must be aligned
+        if !is_aligned(0) {
+            return Err(Error::Misaligned);
+        }
+
+        let max = unsafe { self.bbq.as_ref().capacity() };
+        if sz > max {
+            return Err(Error::RequestTooLarge);
+        }
+
         if atomic::swap(&inner.write_in_progress, true, AcqRel) {
             return Err(Error::GrantInProgress);
         }
@@ -466,20 +1206,35 @@ where
         // be careful writing to `load`
         let write = inner.write.load(Acquire);
         let read = inner.read.load(Acquire);
-        let max = unsafe { self.bbq.as_ref().capacity() };
         let already_inverted = write < read;
 
+        // The largest grant which could be given instead, for the error
+        let insufficient = || {
+            let end = if already_inverted { read - 1 } else { max };
+            let here = if is_aligned(write) { end - write } else { 0 };
+            let wrapped = if !already_inverted && wrap {
+                read.saturating_sub(1)
+            } else {
+                0
+            };
+
+            Error::InsufficientSize {
+                requested: sz,
+                available: here.max(wrapped),
+            }
+        };
+
         let start = if already_inverted {
-            if (write + sz) < read {
+            if (write + sz) < read && is_aligned(write) {
                 // Inverted, room is still available
                 write
             } else {
                 // Inverted, no room is available
                 inner.write_in_progress.store(false, Release);
-                return Err(Error::InsufficientSize);
+                return Err(insufficient());
             }
         } else {
-            if write + sz <= max {
+            if write + sz <= max && is_aligned(write) {
                 // Non inverted condition
                 write
             } else {
@@ -488,13 +1243,13 @@ where
                 // NOTE: We check sz < read, NOT <=, because
                 // write must never == read in an inverted condition, since
                 // we will then not be able to tell if we are inverted or not
-                if sz < read {
+                if wrap && sz < read {
                     // Invertible situation
                     0
                 } else {
                     // Not invertible, no space
                     inner.write_in_progress.store(false, Release);
-                    return Err(Error::InsufficientSize);
+                    return Err(insufficient());
                 }
             }
         };
@@ -502,34 +1257,462 @@ where
         // Safe write, only viewed by this task
         inner.reserve.store(start + sz, Release);
 
-        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
-        // are all `#[repr(Transparent)]
-        let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
-        let grant_slice =
-            unsafe { from_raw_parts_mut(start_of_buf_ptr.offset(start as isize), sz) };
+        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(start), sz) };
 
         Ok(GrantW {
             buf: grant_slice.into(),
             bbq: self.bbq,
             to_commit: 0,
+            #[cfg(feature = "split-grants")]
+            part: GrantPart::Whole,
+            #[cfg(debug_assertions)]
+            lent: false,
             phatom: PhantomData,
         })
     }
 
-    /// Request a writable, contiguous section of memory of up to
-    /// `sz` bytes. If a buffer of size `sz` is not available without
-    /// wrapping, but some space (0 < available < sz) is available without
-    /// wrapping, then a grant will be given for the remaining size at the
+    /// Blocking version of [Self::grant_exact], for firmware without an async executor.
+    ///
+    /// This retries [Self::grant_exact] until it succeeds, calling `spin` between
+    /// attempts. `spin` can be used to wait in a low power mode until an interrupt
+    /// occurs, or to yield to other tasks.
+    ///
+    /// Like [Self::grant_exact_async], this waits while a grant is in progress, and
+    /// the write position doesn't limit the size of the grant: once all committed bytes
+    /// are released, the read and write pointers are moved back to the start of the ring.
+    /// Only a request larger than the capacity returns an error right away, with
+    /// [Error::RequestTooLarge].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(4).unwrap().commit(4);
+    ///
+    /// // Wait for the consumer to make some room
+    /// let grant = prod
+    ///     .grant_exact_spin(3, || cons.read().unwrap().release(4))
+    ///     .unwrap();
+    /// assert_eq!(grant.len(), 3);
+    /// grant.commit(3);
+    ///
+    /// // This would wrap over the read pointer, until the queue is drained
+    /// let grant = prod
+    ///     .grant_exact_spin(6, || cons.read().unwrap().release(3))
+    ///     .unwrap();
+    /// assert_eq!(grant.len(), 6);
+    ///
+    /// // This can never succeed
+    /// assert!(prod.grant_exact_spin(7, || {}).is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_exact_spin(
+        &mut self,
+        sz: usize,
+        mut spin: impl FnMut(),
+    ) -> Result<GrantW<'a, B, I>> {
+        loop {
+            match self.grant_exact(sz) {
+                Err(Error::InsufficientSize { .. }) => {
+                    // Same as `GrantExactFuture`, start over from the beginning of an
+                    // empty ring, as the request may not fit around the write position
+                    if !self.rewind_if_empty() {
+                        spin();
+                    }
+                }
+                Err(Error::GrantInProgress) => spin(),
+                res => return res,
+            }
+        }
+    }
+
+    /// Moves the read and write pointers back to the start of the ring if the queue is
+    /// empty, so a grant as large as the whole queue can be given again. Returns `true`
+    /// if the pointers were moved.
+    ///
+    /// Nothing is done while a read or write grant is in progress.
+    fn rewind_if_empty(&mut self) -> bool {
+        let inner = unsafe { self.bbq.as_ref() };
+
+        // Take the role of the writer and of the reader, like `grant_exact_overwrite`,
+        // so neither a grant nor a read can observe the pointers while they move
+        if atomic::swap(&inner.write_in_progress, true, AcqRel) {
+            return false;
+        }
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            inner.write_in_progress.store(false, Release);
+            return false;
+        }
+
+        // Write never equals read while inverted, so this is an empty queue
+        let empty = inner.write.load(Acquire) == inner.read.load(Acquire);
+        if empty {
+            // Same state as a freshly split queue
+            inner.last.store(0, Release);
+            inner.reserve.store(0, Release);
+            inner.write.store(0, Release);
+            inner.read.store(0, Release);
+        }
+
+        inner.read_in_progress.store(false, Release);
+        inner.write_in_progress.store(false, Release);
+        empty
+    }
+
+    /// Returns an error if a grant of exactly `sz` bytes can never be given from the
+    /// current write position, even once all committed bytes are released.
+    ///
+    /// [Error::RequestTooLarge] is returned if `sz` is larger than the whole queue.
+    /// Otherwise, given a buffer
+    /// [0|1|2|3|4|5|6|7|8]
+    ///              ^
+    ///              Write pointer
+    /// A grant of 7 bytes is impossible, since it would loop over the read pointer
+    /// even if the buffer is empty, and [Error::InsufficientSize] is returned.
+    fn check_grant_possible(&self, sz: usize) -> Result<()> {
+        let inner = unsafe { self.bbq.as_ref() };
+        let max = inner.capacity();
+        let write = inner.write.load(Acquire);
+
+        if sz > max {
+            Err(Error::RequestTooLarge)
+        } else if sz > max - write && sz >= write {
+            // Even on an empty queue, only the end of the ring, or the start of the
+            // ring up to the write pointer can be granted
+            Err(Error::InsufficientSize {
+                requested: sz,
+                available: (max - write).max(write.saturating_sub(1)),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Request a writable, contiguous section of memory of exactly `sz` bytes,
+    /// starting at an address aligned to `align` bytes. This is useful for DMA
+    /// transfers, which often require aligned buffers, for instance to perform
+    /// cache maintenance.
+    ///
+    /// The start of the storage must be aligned to `align`, see
+    /// [AlignedStorageProvider]. Otherwise, [Error::Misaligned] is returned.
+    ///
+    /// If the write position is not aligned, the grant wraps around to the beginning
+    /// of the ring early, like [Self::grant_exact] does when the requested space is not
+    /// available at the end of the ring. The skipped bytes are not readable, and will
+    /// only be available again once the reader reaches them. When the ring is already
+    /// wrapped around, an unaligned write position results in [Error::InsufficientSize]
+    /// until the reader catches up. Keeping the size of every grant a multiple of
+    /// `align` avoids skipping bytes altogether.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{AlignedStorageProvider, BBQueue};
+    ///
+    /// // Create and split a new buffer of 64 elements, aligned to 32 bytes
+    /// let buffer: BBQueue<AlignedStorageProvider<64, 32>> = BBQueue::new_static_aligned();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// let grant = prod.grant_exact_aligned(20, 32).unwrap();
+    /// assert_eq!(grant.as_ptr() as usize % 32, 0);
+    /// grant.commit(20);
+    ///
+    /// // The next aligned position is before the end of the ring, so
+    /// // the grant wraps around once the reader made some room
+    /// assert!(prod.grant_exact_aligned(20, 32).is_err());
+    /// cons.read().unwrap().release(20);
+    /// let grant = prod.grant_exact_aligned(16, 32).unwrap();
+    /// assert_eq!(grant.as_ptr() as usize % 32, 0);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_exact_aligned(&mut self, sz: usize, align: usize) -> Result<GrantW<'a, B, I>> {
+        assert!(align != 0, "the alignment must not be zero");
+        let res = self.raw_grant_exact(sz, align, true);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    /// Same as [Self::grant_exact], but the grant is never wrapped around to the start
+    /// of the ring.
+    ///
+    /// If `sz` bytes don't fit between the write pointer and the end of the ring (or the
+    /// read pointer, if the writer already wrapped around), [Error::InsufficientSize] is
+    /// returned, rather than skipping the bytes at the end of the ring. The caller can then
+    /// fall back to [Self::grant_max_remaining] to use these bytes. See
+    /// [BBQueue::skipped_len] for the number of bytes skipped by a wrapping grant.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, Error, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(5).unwrap().commit(5);
+    /// cons.read().unwrap().release(5);
+    ///
+    /// // Only one byte is left before the end of the ring
+    /// assert_eq!(
+    ///     prod.grant_exact_no_wrap(4).unwrap_err(),
+    ///     Error::InsufficientSize { requested: 4, available: 1 }
+    /// );
+    /// assert_eq!(prod.grant_max_remaining(4).unwrap().len(), 1);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_exact_no_wrap(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        let res = self.raw_grant_exact(sz, 1, false);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    /// Non-blocking version of [Self::grant_exact], returning `None` instead of an error
+    /// when the grant can't be given right now.
+    ///
+    /// Both [Error::InsufficientSize] and [Error::GrantInProgress] result in `None`. This
+    /// is convenient in interrupt handlers, where a full queue is an expected condition.
+    /// A request larger than the capacity also results in `None`, use [Self::grant_exact]
+    /// to tell it apart with [Error::RequestTooLarge].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    ///
+    /// if let Some(mut grant) = prod.try_grant_exact(4) {
+    ///     grant.copy_from_slice(&[1, 2, 3, 4]);
+    ///     grant.commit(4);
+    /// }
+    ///
+    /// // Not enough space left
+    /// assert!(prod.try_grant_exact(3).is_none());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+    pub fn try_grant_exact(&mut self, sz: usize) -> Option<GrantW<'a, B, I>> {
+        self.grant_exact(sz).ok()
+    }
+
+    /// Same as [Self::grant_exact], but the granted bytes are set to zero before the
+    /// grant is returned.
+    ///
+    /// The contents of a regular grant are whatever was last written to that part of the
+    /// ring. Use this instead when stale data must not be exposed, for example if fewer bytes
+    /// than the grant size may be written before committing the whole grant. Only the
+    /// granted bytes are zeroed, which costs a `memset` of `sz` bytes.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// let mut grant = prod.grant_exact(6).unwrap();
+    /// grant.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+    /// grant.commit(6);
+    /// cons.read().unwrap().release(6);
+    ///
+    /// // The grant wraps around, but the bytes written by the previous grant are not visible
+    /// let grant = prod.grant_exact_zeroed(4).unwrap();
+    /// assert_eq!(&*grant, &[0, 0, 0, 0]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_exact_zeroed(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        let mut grant = self.grant_exact(sz)?;
+        grant.buf().fill(0);
+        Ok(grant)
+    }
+
+    /// Request a writable, contiguous section of memory of exactly `sz` bytes,
+    /// discarding the oldest committed bytes if not enough space is available.
+    ///
+    /// This is a lossy alternative to [Self::grant_exact], for example for telemetry
+    /// where recent data is more valuable than old data. Only as many bytes as needed
+    /// for the grant are discarded. The consumer will observe the discarded bytes
+    /// "disappearing" between two reads, so this should not be used when the data has
+    /// some structure, such as in framed mode.
+    ///
+    /// To discard bytes, the producer temporarily takes the role of the consumer. If a
+    /// read grant is in progress, no bytes can be discarded, and an error is returned. While
+    /// bytes are being discarded, [Consumer::read] may return [Error::GrantInProgress].
+    ///
+    /// An error is also returned, without discarding any bytes, if `sz` bytes can't be
+    /// granted even once all bytes are discarded, as grants must be contiguous.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+    ///
+    /// // The queue is full, the three oldest bytes are discarded
+    /// let mut grant = prod.grant_exact_overwrite(2).unwrap();
+    /// grant.copy_from_slice(&[7, 8]);
+    /// grant.commit(2);
+    ///
+    /// let rgr = cons.read().unwrap();
+    /// assert_eq!(&*rgr, &[4, 5, 6]);
+    /// rgr.release(3);
+    ///
+    /// let rgr = cons.read().unwrap();
+    /// assert_eq!(&*rgr, &[7, 8]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_exact_overwrite(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        let res = self.raw_grant_exact_overwrite(sz);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    fn raw_grant_exact_overwrite(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        match self.raw_grant_exact(sz, 1, true) {
+            Err(Error::InsufficientSize { .. }) => {}
+            res => return res,
+        }
+
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        // Don't discard anything if the grant can't succeed even on an empty queue,
+        // like in `GrantExactFuture`
+        self.check_grant_possible(sz)?;
+
+        // Take the role of the reader, so the consumer can't read the
+        // bytes we are about to discard
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        let res = loop {
+            let write = inner.write.load(Acquire);
+            let last = inner.last.load(Acquire);
+            let mut read = inner.read.load(Acquire);
+
+            // Resolve the inverted case or end of read, like `Consumer::read`
+            if (read == last) && (write < read) {
+                read = 0;
+                inner.read.store(0, Release);
+            }
+
+            let err = match self.raw_grant_exact(sz, 1, true) {
+                Err(err @ Error::InsufficientSize { .. }) => err,
+                res => break res,
+            };
+
+            let (end, needed) = if write < read {
+                // Inverted, the grant must end strictly before `read`
+                (last, write + sz + 1)
+            } else {
+                // Not inverted, and no room at the end of the ring. The grant
+                // wraps around, and must end strictly before `read`
+                (write, sz + 1)
+            };
+
+            if read == end {
+                // Nothing left to discard
+                break Err(err);
+            }
+
+            // Discard the oldest bytes, at most up to the end of the readable region.
+            // As the grant failed, `needed` is always larger than `read`.
+            let new_read = min(needed, end);
+
+            #[cfg(feature = "metrics")]
+            atomic::fetch_add(&inner.counters.released, new_read - read, AcqRel);
+
+            inner.read.store(new_read, Release);
+        };
+
+        inner.read_in_progress.store(false, Release);
+
+        // A read polled in the meantime failed with `GrantInProgress`, and waits for a
+        // wakeup that the commit may never send, if nothing ends up committed
+        #[cfg(feature = "async-api")]
+        inner.read_waker.wake();
+
+        res
+    }
+
+    /// Request a writable, contiguous section of memory of up to
+    /// `sz` bytes. If a buffer of size `sz` is not available without
+    /// wrapping, but some space (0 < available < sz) is available without
+    /// wrapping, then a grant will be given for the remaining size at the
     /// end of the buffer. If no space is available for writing, an error
     /// will be returned.
     ///
     /// ```
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, mut cons) = buffer.try_split().unwrap();
     ///
     /// // Successfully obtain and commit a grant of four bytes
@@ -554,7 +1737,27 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub fn grant_max_remaining(&mut self, mut sz: usize) -> Result<GrantW<'a, B>> {
+    pub fn grant_max_remaining(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        self.grant_max_remaining_inner(sz, 1)
+    }
+
+    /// Same as [Self::grant_max_remaining], but the size of the grant will always be a
+    /// multiple of `unit` bytes. If less than `unit` bytes remain at the end of the
+    /// ring, the grant will wrap around to the beginning of the ring.
+    pub(crate) fn grant_max_remaining_inner(
+        &mut self,
+        sz: usize,
+        unit: usize,
+    ) -> Result<GrantW<'a, B, I>> {
+        let res = self.raw_grant_max_remaining(sz, unit);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    fn raw_grant_max_remaining(&mut self, mut sz: usize, unit: usize) -> Result<GrantW<'a, B, I>> {
         let inner = unsafe { &self.bbq.as_ref() };
 
         if atomic::swap(&inner.write_in_progress, true, AcqRel) {
@@ -571,7 +1774,7 @@ where
 
         let start = if already_inverted {
             // In inverted case, read is always > write
-            let remain = read - write - 1;
+            let remain = round_down(read - write - 1, unit);
 
             if remain != 0 {
                 sz = min(remain, sz);
@@ -579,109 +1782,92 @@ where
             } else {
                 // Inverted, no room is available
                 inner.write_in_progress.store(false, Release);
-                return Err(Error::InsufficientSize);
+                return Err(Error::InsufficientSize {
+                    requested: sz,
+                    available: 0,
+                });
             }
         } else {
-            if write != max {
+            let remain = round_down(max - write, unit);
+
+            if remain != 0 {
                 // Some (or all) room remaining in un-inverted case
-                sz = min(max - write, sz);
+                sz = min(remain, sz);
                 write
             } else {
                 // Not inverted, but need to go inverted
 
-                // NOTE: We check read > 1, NOT read >= 1, because
+                // NOTE: We check read > unit, NOT read >= unit, because
                 // write must never == read in an inverted condition, since
                 // we will then not be able to tell if we are inverted or not
-                if read > 1 {
-                    sz = min(read - 1, sz);
+                if read > unit {
+                    sz = min(round_down(read - 1, unit), sz);
                     0
                 } else {
                     // Not invertible, no space
                     inner.write_in_progress.store(false, Release);
-                    return Err(Error::InsufficientSize);
+                    return Err(Error::InsufficientSize {
+                        requested: sz,
+                        available: 0,
+                    });
                 }
             }
         };
 
+        // Never hand out a partial unit
+        let sz = round_down(sz, unit);
+
         // Safe write, only viewed by this task
         inner.reserve.store(start + sz, Release);
 
         // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
         // are all `#[repr(Transparent)]
         let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
-        let grant_slice =
-            unsafe { from_raw_parts_mut(start_of_buf_ptr.offset(start as isize), sz) };
+        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(start), sz) };
 
         Ok(GrantW {
             buf: grant_slice.into(),
             bbq: self.bbq,
             to_commit: 0,
+            #[cfg(feature = "split-grants")]
+            part: GrantPart::Whole,
+            #[cfg(debug_assertions)]
+            lent: false,
             phatom: PhantomData,
         })
     }
 
-    /// Async version of [Self::grant_exact].
-    /// If the buffer can enventually provide a buffer of the requested size, the future
-    /// will wait for the buffer to be read so the exact buffer can be requested.
+    /// Request the largest contiguous section of writable memory available. Unlike
+    /// [Self::grant_max_remaining], which only wraps around once no bytes remain at the end
+    /// of the ring, this compares the free space at the end of the ring with the free space
+    /// at the beginning of the ring, and wraps around if the beginning is larger. If no space
+    /// is available for writing, an error will be returned.
     ///
-    /// If it's not possible to request it, an error is returned.
-    /// For example, given a buffer
-    /// [0|1|2|3|4|5|6|7|8]
-    ///              ^
-    ///              Write pointer
-    /// We cannot request a size of size 7, since we would loop over the read pointer
-    /// even if the buffer is empty. In this case, an error is returned
-    pub fn grant_exact_async(&'_ mut self, sz: usize) -> GrantExactFuture<'a, '_, B> {
-        GrantExactFuture { prod: self, sz }
-    }
-
-    /// Async version of [Self::grant_max_remaining].
-    /// Will wait for the buffer to at least 1 byte available, as soon as it does, return the grant.
-    pub fn grant_max_remaining_async(
-        &'_ mut self,
-        sz: usize,
-    ) -> GrantMaxRemainingFuture<'a, '_, B> {
-        GrantMaxRemainingFuture { prod: self, sz }
-    }
-}
-
-/// `Consumer` is the primary interface for reading data from a `BBQueue`.
-pub struct Consumer<'a, B>
-where
-    B: StorageProvider,
-{
-    bbq: NonNull<BBQueue<B>>,
-    pd: PhantomData<&'a ()>,
-}
-
-unsafe impl<'a, B> Send for Consumer<'a, B> where B: StorageProvider {}
-
-impl<'a, B> Consumer<'a, B>
-where
-    B: StorageProvider,
-{
-    /// Obtains a contiguous slice of committed bytes. This slice may not
-    /// contain ALL available bytes, if the writer has wrapped around. The
-    /// remaining bytes will be available after all readable bytes are
-    /// released
+    /// When wrapping around, the bytes remaining at the end of the ring are skipped, like
+    /// with [Self::grant_exact], even if the grant commits nothing.
     ///
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, mut cons) = buffer.try_split().unwrap();
     ///
-    /// // Successfully obtain and commit a grant of four bytes
-    /// let mut grant = prod.grant_max_remaining(4).unwrap();
-    /// assert_eq!(grant.buf().len(), 4);
+    /// // Leave one byte at the end of the ring, and five at the beginning
+    /// prod.grant_exact(5).unwrap().commit(5);
+    /// cons.read().unwrap().release(5);
+    ///
+    /// // Only four bytes can be granted at the beginning, as the write
+    /// // position must not catch up with the read position
+    /// assert_eq!(prod.grant_max_remaining(6).unwrap().len(), 1);
+    /// let grant = prod.grant_largest().unwrap();
+    /// assert_eq!(grant.len(), 4);
     /// grant.commit(4);
     ///
-    /// // Obtain a read grant
-    /// let mut grant = cons.read().unwrap();
-    /// assert_eq!(grant.buf().len(), 4);
+    /// let grant = cons.read().unwrap();
+    /// assert_eq!(grant.len(), 4);
     /// # // bbqueue test shim!
     /// # }
     /// #
@@ -690,142 +1876,1914 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub fn read(&mut self) -> Result<GrantR<'a, B>> {
+    pub fn grant_largest(&mut self) -> Result<GrantW<'a, B, I>> {
+        let res = self.raw_grant_largest();
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    fn raw_grant_largest(&mut self) -> Result<GrantW<'a, B, I>> {
         let inner = unsafe { &self.bbq.as_ref() };
 
-        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+        if atomic::swap(&inner.write_in_progress, true, AcqRel) {
             return Err(Error::GrantInProgress);
         }
 
+        // Writer component. Must never write to `read`,
+        // be careful writing to `load`
         let write = inner.write.load(Acquire);
-        let last = inner.last.load(Acquire);
-        let mut read = inner.read.load(Acquire);
+        let read = inner.read.load(Acquire);
+        let max = unsafe { self.bbq.as_ref().capacity() };
 
-        // Resolve the inverted case or end of read
-        if (read == last) && (write < read) {
-            read = 0;
-            // This has some room for error, the other thread reads this
-            // Impact to Grant:
-            //   Grant checks if read < write to see if inverted. If not inverted, but
-            //     no space left, Grant will initiate an inversion, but will not trigger it
-            // Impact to Commit:
-            //   Commit does not check read, but if Grant has started an inversion,
-            //   grant could move Last to the prior write position
-            // MOVING READ BACKWARDS!
-            inner.read.store(0, Release);
-        }
-
-        let sz = if write < read {
-            // Inverted, only believe last
-            last
+        let (start, sz) = if write < read {
+            // Inverted, the only room is up to read
+            (write, read - write - 1)
         } else {
-            // Not inverted, only believe write
-            write
-        } - read;
+            // NOTE: We use read - 1, because write must never == read in an
+            // inverted condition, since we will then not be able to tell if we
+            // are inverted or not
+            let end = max - write;
+            let wrapped = read.saturating_sub(1);
+
+            // Prefer not wrapping around on a tie, no bytes are skipped then
+            if end >= wrapped {
+                (write, end)
+            } else {
+                (0, wrapped)
+            }
+        };
 
         if sz == 0 {
-            inner.read_in_progress.store(false, Release);
-            return Err(Error::InsufficientSize);
+            // No room is available
+            inner.write_in_progress.store(false, Release);
+            return Err(Error::InsufficientSize {
+                requested: 1,
+                available: 0,
+            });
         }
 
+        // Safe write, only viewed by this task. If wrapping around, `last` is
+        // set by the commit, as the new write position is before the old one
+        inner.reserve.store(start + sz, Release);
+
         // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
         // are all `#[repr(Transparent)]
         let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
-        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.offset(read as isize), sz) };
+        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(start), sz) };
 
-        Ok(GrantR {
+        Ok(GrantW {
             buf: grant_slice.into(),
             bbq: self.bbq,
-            to_release: 0,
+            to_commit: 0,
+            #[cfg(feature = "split-grants")]
+            part: GrantPart::Whole,
+            #[cfg(debug_assertions)]
+            lent: false,
             phatom: PhantomData,
         })
     }
 
-    /// Obtains two disjoint slices, which are each contiguous of committed bytes.
-    /// Combined these contain all previously commited data.
-    pub fn split_read(&mut self) -> Result<SplitGrantR<'a, B>> {
+    /// Request up to `sz` bytes of writable memory, as two contiguous regions: the
+    /// remaining space at the end of the ring, and the space at the beginning of the
+    /// ring. This is the write equivalent of [Consumer::split_read], and allows to use
+    /// all the free space of the queue in a single grant.
+    ///
+    /// The second region is only used once the first one reaches the end of the ring.
+    /// The first region is empty if the write position is at the end of the ring. If no
+    /// space is available for writing, an error will be returned.
+    ///
+    /// See [SplitGrantW::commit] for how the bytes are committed.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+    /// cons.read().unwrap().release(3);
+    ///
+    /// // Two bytes at the end of the ring, two at the start, as
+    /// // the write position can't reach the read position
+    /// let mut grant = prod.grant_split_remaining(5).unwrap();
+    /// let (buf1, buf2) = grant.bufs_mut();
+    /// assert_eq!((buf1.len(), buf2.len()), (2, 2));
+    /// buf1.copy_from_slice(&[5, 6]);
+    /// buf2.copy_from_slice(&[7, 8]);
+    /// grant.commit(4);
+    ///
+    /// let grant = cons.split_read().unwrap();
+    /// assert_eq!(grant.bufs(), (&[4, 5, 6][..], &[7, 8][..]));
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_split_remaining(&mut self, sz: usize) -> Result<SplitGrantW<'a, B, I>> {
+        let res = self.raw_grant_split_remaining(sz);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.counters.record_grant(&res);
+
+        res
+    }
+
+    fn raw_grant_split_remaining(&mut self, sz: usize) -> Result<SplitGrantW<'a, B, I>> {
         let inner = unsafe { &self.bbq.as_ref() };
 
-        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+        if atomic::swap(&inner.write_in_progress, true, AcqRel) {
             return Err(Error::GrantInProgress);
         }
 
+        // Writer component. Must never write to `read`,
+        // be careful writing to `load`
         let write = inner.write.load(Acquire);
-        let last = inner.last.load(Acquire);
-        let mut read = inner.read.load(Acquire);
-
-        // Resolve the inverted case or end of read
-        if (read == last) && (write < read) {
-            read = 0;
-            // This has some room for error, the other thread reads this
-            // Impact to Grant:
-            //   Grant checks if read < write to see if inverted. If not inverted, but
-            //     no space left, Grant will initiate an inversion, but will not trigger it
-            // Impact to Commit:
-            //   Commit does not check read, but if Grant has started an inversion,
-            //   grant could move Last to the prior write position
-            // MOVING READ BACKWARDS!
-            inner.read.store(0, Release);
-        }
+        let read = inner.read.load(Acquire);
+        let max = inner.capacity();
 
+        // NOTE: Both regions stop one byte before `read`, as write must never
+        // == read in an inverted condition
         let (sz1, sz2) = if write < read {
-            // Inverted, only believe last
-            (last - read, write)
+            // Inverted, only the space up to read is available
+            (min(sz, read - write - 1), 0)
         } else {
-            // Not inverted, only believe write
-            (write - read, 0)
+            // Not inverted, the end of the ring, then the start of the ring
+            let sz1 = min(sz, max - write);
+            (sz1, min(sz - sz1, read.saturating_sub(1)))
         };
 
-        if sz1 == 0 {
-            inner.read_in_progress.store(false, Release);
-            return Err(Error::InsufficientSize);
+        if sz1 + sz2 == 0 {
+            inner.write_in_progress.store(false, Release);
+            return Err(Error::InsufficientSize {
+                requested: sz,
+                available: 0,
+            });
         }
 
+        // Safe write, only viewed by this task
+        let end = if sz2 == 0 { write + sz1 } else { sz2 };
+        inner.reserve.store(end, Release);
+
         // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
         // are all `#[repr(Transparent)]
         let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
-        let grant_slice1 =
-            unsafe { from_raw_parts_mut(start_of_buf_ptr.offset(read as isize), sz1) };
+        let grant_slice1 = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(write), sz1) };
         let grant_slice2 = unsafe { from_raw_parts_mut(start_of_buf_ptr, sz2) };
 
-        Ok(SplitGrantR {
+        Ok(SplitGrantW {
             buf1: grant_slice1.into(),
             buf2: grant_slice2.into(),
             bbq: self.bbq,
-            to_release: 0,
+            to_commit: 0,
             phatom: PhantomData,
         })
     }
 
-    /// Async version of [Self::read].
-    /// Will wait for the buffer to have data to read. When data is available, the grant is returned.
-    pub fn read_async<'b>(&'b mut self) -> GrantReadFuture<'a, 'b, B> {
-        GrantReadFuture { cons: self }
+    /// Async version of [Self::grant_exact].
+    /// Will wait for the buffer to be read until a grant of exactly `sz` bytes can be
+    /// given.
+    ///
+    /// Unlike [Self::grant_exact], the write position doesn't limit the size of the
+    /// grant. For example, given a buffer
+    /// [0|1|2|3|4|5|6|7|8]
+    ///              ^
+    ///              Write pointer
+    /// a grant of 7 bytes would loop over the read pointer, even if the buffer is empty.
+    /// Once all the committed bytes are released, the future moves the read and write
+    /// pointers back to the start of the ring, and the grant is given from there. Only
+    /// a request larger than the capacity can never succeed, and returns
+    /// [Error::RequestTooLarge].
+    #[cfg(feature = "async-api")]
+    pub fn grant_exact_async(&'_ mut self, sz: usize) -> GrantExactFuture<'a, '_, B, I> {
+        GrantExactFuture { prod: self, sz }
     }
 
-    /// Async version of [Self::split_read].
-    /// Will wait just like [Self::read_async], but returns the split grant to obtain all the available data.
-    pub fn split_read_async<'b>(&'b mut self) -> GrantSplitReadFuture<'a, 'b, B> {
-        GrantSplitReadFuture { cons: self }
+    /// Same as [Self::grant_exact_async], but gives up once `timer` completes, and returns
+    /// [Error::Timeout]. If the grant and the timer are ready at the same time, the grant
+    /// is returned.
+    ///
+    /// `timer` can be any future, such as a delay from the timer of the executor, which
+    /// keeps this independent of the runtime. Once this future completes or is dropped,
+    /// the producer is no longer registered to be woken up by releases.
+    #[cfg(feature = "async-api")]
+    pub async fn grant_exact_timeout<T: Future>(
+        &mut self,
+        sz: usize,
+        timer: T,
+    ) -> Result<GrantW<'a, B, I>> {
+        let mut grant = self.grant_exact_async(sz);
+        let mut timer = pin!(timer);
+        poll_fn(|cx| {
+            if let Poll::Ready(res) = Pin::new(&mut grant).poll(cx) {
+                return Poll::Ready(res);
+            }
+            timer.as_mut().poll(cx).map(|_| Err(Error::Timeout))
+        })
+        .await
+    }
+
+    /// Async version of [Self::grant_max_remaining].
+    /// Will wait for the buffer to at least 1 byte available, as soon as it does, return the grant.
+    #[cfg(feature = "async-api")]
+    pub fn grant_max_remaining_async(
+        &'_ mut self,
+        sz: usize,
+    ) -> GrantMaxRemainingFuture<'a, '_, B, I> {
+        GrantMaxRemainingFuture {
+            prod: self,
+            sz,
+            unit: 1,
+        }
+    }
+
+    /// Copies the prefix of `data` that fits in a single contiguous grant, and commits it.
+    /// Returns the number of bytes that were written.
+    ///
+    /// This uses [Self::grant_max_remaining], so at most one contiguous region is written.
+    /// See [Self::send_slice] to also use the space after wrapping around. If no space is
+    /// available for writing, an error will be returned. An empty `data` slice always
+    /// returns `Ok(0)`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider, Error};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// assert_eq!(prod.push_slice(&[1, 2, 3, 4]), Ok(4));
+    /// assert_eq!(prod.push_slice(&[5, 6, 7, 8]), Ok(2));
+    /// assert_eq!(
+    ///     prod.push_slice(&[9]),
+    ///     Err(Error::InsufficientSize { requested: 1, available: 0 })
+    /// );
+    ///
+    /// let rgr = cons.read().unwrap();
+    /// assert_eq!(&*rgr, &[1, 2, 3, 4, 5, 6]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn push_slice(&mut self, data: &[u8]) -> Result<usize> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let mut grant = self.grant_max_remaining(data.len())?;
+        let len = grant.len();
+        grant.copy_from_slice(&data[..len]);
+        grant.commit(len);
+        Ok(len)
+    }
+
+    /// Copies all of `data` into a single contiguous grant, and commits it.
+    ///
+    /// This uses [Self::grant_exact], so if the whole slice doesn't fit, nothing is
+    /// written and an error is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider, Error};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// assert_eq!(prod.push_slice_all(&[1, 2, 3, 4]), Ok(()));
+    /// assert_eq!(
+    ///     prod.push_slice_all(&[5, 6, 7]),
+    ///     Err(Error::InsufficientSize { requested: 3, available: 2 })
+    /// );
+    ///
+    /// let rgr = cons.read().unwrap();
+    /// assert_eq!(&*rgr, &[1, 2, 3, 4]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn push_slice_all(&mut self, data: &[u8]) -> Result<()> {
+        let mut grant = self.grant_exact(data.len())?;
+        grant.copy_from_slice(data);
+        grant.commit(data.len());
+        Ok(())
+    }
+
+    /// Copies as much of `data` as fits into the queue, and commits it. Returns
+    /// the number of bytes that were written.
+    ///
+    /// Unlike [Self::grant_exact], the data does not need to fit contiguously, as up
+    /// to two grants are used internally: one at the end of the ring, and one after
+    /// wrapping around to the start of the ring. If no space is available for writing,
+    /// an error will be returned. An empty `data` slice always returns `Ok(0)`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Only six of the eight bytes fit
+    /// assert_eq!(prod.send_slice(&[1, 2, 3, 4, 5, 6, 7, 8]), Ok(6));
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn send_slice(&mut self, data: &[u8]) -> Result<usize> {
+        let mut written = 0;
+
+        while written < data.len() {
+            let mut grant = match self.grant_max_remaining(data.len() - written) {
+                Ok(grant) => grant,
+                // We already wrote some data, the queue is now full
+                Err(Error::InsufficientSize { .. }) if written != 0 => break,
+                Err(e) => return Err(e),
+            };
+
+            let len = grant.len();
+            grant.copy_from_slice(&data[written..][..len]);
+            grant.commit(len);
+            written += len;
+        }
+
+        Ok(written)
+    }
+
+    /// Returns the number of bytes that could still be granted.
+    ///
+    /// See [BBQueue::free_len] for details.
+    pub fn space_remaining(&self) -> usize {
+        unsafe { self.bbq.as_ref().free_len() }
+    }
+
+    /// Returns `true` if no bytes are committed and not yet released.
+    ///
+    /// See [BBQueue::is_empty] for details.
+    pub fn is_empty(&self) -> bool {
+        unsafe { self.bbq.as_ref().is_empty() }
+    }
+
+    /// Returns `true` if no bytes could be granted to the producer.
+    ///
+    /// See [BBQueue::is_full] for details.
+    pub fn is_full(&self) -> bool {
+        unsafe { self.bbq.as_ref().is_full() }
+    }
+
+    /// Closes the queue, signaling the consumer that no more bytes will be committed.
+    ///
+    /// The bytes already committed can still be read. Once they can't satisfy a request,
+    /// the async reads of the [Consumer] return [Error::Closed] instead of waiting, such
+    /// as [Consumer::read_async] once they are all released. The synchronous reads keep
+    /// returning [Error::InsufficientSize], see [Consumer::is_closed] to tell both cases
+    /// apart.
+    ///
+    /// A write grant still alive may be committed after closing, but the consumer may
+    /// have stopped reading by then. Commit or drop it first.
+    ///
+    /// The queue can't be released without the producer, see [BBQueue::clear] to reuse it.
+    ///
+    /// A producer returned by [Self::reborrow] can't close the queue, as the original
+    /// producer may keep committing bytes. It is returned back as an error instead.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice_all(&[1, 2]).unwrap();
+    ///
+    /// // Only the original producer may close the queue
+    /// assert!(prod.reborrow().close().is_err());
+    /// assert!(!cons.is_closed());
+    /// prod.close().unwrap();
+    ///
+    /// // The committed bytes are still there
+    /// assert!(cons.is_closed());
+    /// cons.read().unwrap().release_all();
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn close(self) -> CoreResult<(), Self> {
+        if self.reborrowed {
+            return Err(self);
+        }
+
+        self.close_inner();
+        Ok(())
+    }
+
+    pub(crate) fn close_inner(&self) {
+        let inner = unsafe { self.bbq.as_ref() };
+        inner.closed.store(true, Release);
+
+        #[cfg(feature = "async-api")]
+        inner.read_waker.wake();
+    }
+
+    /// Borrows this producer as a new `Producer`, for example to pass it by value to a
+    /// function without giving up this one. This one can't be used until the returned
+    /// producer, and its grants, are dropped.
+    ///
+    /// The returned producer can't be used to release the queue, see
+    /// [BBQueue::try_release], nor to close it, see [Self::close], as this one
+    /// would still be alive.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, Producer, StaticStorageProvider};
+    ///
+    /// fn write_one(mut prod: Producer<'_, StaticStorageProvider<6>>) {
+    ///     prod.grant_exact(1).unwrap().commit(1);
+    /// }
+    ///
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    /// write_one(prod.reborrow());
+    /// write_one(prod.reborrow());
+    /// assert_eq!(cons.len(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn reborrow(&mut self) -> Producer<'_, B, I> {
+        Producer {
+            bbq: self.bbq,
+            pd: PhantomData,
+            reborrowed: true,
+        }
+    }
+}
+
+/// `Consumer` is the primary interface for reading data from a `BBQueue`.
+///
+/// Like the [Producer], the `Consumer` is not `Clone`, see [Consumer::reborrow].
+pub struct Consumer<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) bbq: NonNull<BBQueue<B, I>>,
+    pub(crate) pd: PhantomData<&'a ()>,
+    // Obtained with `reborrow`, which can't release the queue
+    pub(crate) reborrowed: bool,
+}
+
+unsafe impl<'a, B, I> Send for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Shows the address and the capacity of the queue
+impl<'a, B, I> fmt::Debug for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("queue", &self.bbq)
+            .field("capacity", &unsafe { self.bbq.as_ref() }.capacity())
+            .field("reborrowed", &self.reborrowed)
+            .finish()
+    }
+}
+
+impl<'a, B, I> Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Obtains a contiguous slice of committed bytes. This slice may not
+    /// contain ALL available bytes, if the writer has wrapped around. The
+    /// remaining bytes will be available after all readable bytes are
+    /// released
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Successfully obtain and commit a grant of four bytes
+    /// let mut grant = prod.grant_max_remaining(4).unwrap();
+    /// assert_eq!(grant.buf().len(), 4);
+    /// grant.commit(4);
+    ///
+    /// // Obtain a read grant
+    /// let mut grant = cons.read().unwrap();
+    /// assert_eq!(grant.buf().len(), 4);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read(&mut self) -> Result<GrantR<'a, B, I>> {
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        let write = inner.write.load(Acquire);
+        let last = inner.last.load(Acquire);
+        let mut read = inner.read.load(Acquire);
+
+        // Resolve the inverted case or end of read
+        if (read == last) && (write < read) {
+            read = 0;
+            // This has some room for error, the other thread reads this
+            // Impact to Grant:
+            //   Grant checks if read < write to see if inverted. If not inverted, but
+            //     no space left, Grant will initiate an inversion, but will not trigger it
+            // Impact to Commit:
+            //   Commit does not check read, but if Grant has started an inversion,
+            //   grant could move Last to the prior write position
+            // MOVING READ BACKWARDS!
+            inner.read.store(0, Release);
+        }
+
+        let sz = if write < read {
+            // Inverted, only believe last
+            last
+        } else {
+            // Not inverted, only believe write
+            write
+        } - read;
+
+        if sz == 0 {
+            inner.read_in_progress.store(false, Release);
+            return Err(Error::InsufficientSize {
+                requested: 1,
+                available: 0,
+            });
+        }
+
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
+        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(read), sz) };
+        inner.post_read(grant_slice);
+
+        Ok(GrantR {
+            buf: grant_slice.into(),
+            bbq: self.bbq,
+            to_release: 0,
+            retained: 0,
+            phatom: PhantomData,
+        })
+    }
+
+    /// Blocking version of [Self::read], for firmware without an async executor.
+    ///
+    /// This retries [Self::read] until some bytes are available, calling `spin` between
+    /// attempts. `spin` can be used to wait in a low power mode until an interrupt
+    /// occurs, or to yield to other tasks. Like [Self::read_async], this also waits
+    /// while a read grant is in progress.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Wait for the producer to commit some bytes
+    /// let grant = cons
+    ///     .read_spin(|| prod.grant_exact(2).unwrap().commit(2))
+    ///     .unwrap();
+    /// assert_eq!(grant.len(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read_spin(&mut self, mut spin: impl FnMut()) -> Result<GrantR<'a, B, I>> {
+        loop {
+            match self.read() {
+                Err(Error::GrantInProgress | Error::InsufficientSize { .. }) => spin(),
+                res => return res,
+            }
+        }
+    }
+
+    /// Non-blocking version of [Self::read], returning `None` instead of an error
+    /// when no bytes can be read right now.
+    ///
+    /// Both [Error::InsufficientSize] and [Error::GrantInProgress] result in `None`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Nothing to read yet
+    /// assert!(cons.try_read().is_none());
+    ///
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// while let Some(grant) = cons.try_read() {
+    ///     assert_eq!(grant.buf().len(), 4);
+    ///     grant.release(4);
+    /// }
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+    pub fn try_read(&mut self) -> Option<GrantR<'a, B, I>> {
+        // `read` only fails when the read would block
+        self.read().ok()
+    }
+
+    /// Obtains a contiguous slice of exactly `sz` committed bytes. If less than `sz`
+    /// contiguous bytes are available, an error will be returned, and no bytes will
+    /// be released.
+    ///
+    /// Like [Self::read], the grant can not span the end of the ring. If the writer
+    /// has wrapped around, and less than `sz` bytes are available before the end of
+    /// the ring, an error will be returned, even if more than `sz` bytes are
+    /// committed in total.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Commit three bytes
+    /// prod.grant_exact(3).unwrap().commit(3);
+    ///
+    /// // Four bytes are not available yet
+    /// assert!(cons.read_exact(4).is_err());
+    ///
+    /// // But two are, and the grant is exactly two bytes long
+    /// let grant = cons.read_exact(2).unwrap();
+    /// assert_eq!(grant.len(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read_exact(&mut self, sz: usize) -> Result<GrantR<'a, B, I>> {
+        let mut grant = self.read().map_err(|err| requested(err, sz))?;
+
+        if grant.len() < sz {
+            let available = grant.len();
+            // Dropping the grant releases nothing
+            drop(grant);
+            return Err(Error::InsufficientSize {
+                requested: sz,
+                available,
+            });
+        }
+
+        grant.shrink(sz);
+        Ok(grant)
+    }
+
+    /// Obtains a contiguous slice of committed bytes, up to and including the first
+    /// occurrence of `delim`. If `delim` is not found, an error will be returned, and no
+    /// bytes will be released, so the caller can wait for more bytes to be committed.
+    ///
+    /// Like [Self::read], only the bytes before the end of the ring are searched. If the
+    /// writer has wrapped around, `delim` is not found until the reader has wrapped
+    /// around too, even if it was committed at the start of the ring. A record spanning
+    /// the end of the ring can be read with [Self::split_read] instead.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 16 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(b"AT\nOK").unwrap();
+    ///
+    /// let grant = cons.read_until(b'\n').unwrap();
+    /// assert_eq!(&*grant, b"AT\n");
+    /// grant.release_all();
+    ///
+    /// // The rest of the record wasn't committed yet
+    /// assert!(cons.read_until(b'\n').is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read_until(&mut self, delim: u8) -> Result<GrantR<'a, B, I>> {
+        let mut grant = self.read()?;
+
+        match grant.iter().position(|&b| b == delim) {
+            Some(pos) => {
+                grant.shrink(pos + 1);
+                Ok(grant)
+            }
+            None => {
+                let available = grant.len();
+                // Dropping the grant releases nothing
+                drop(grant);
+                Err(Error::InsufficientSize {
+                    requested: available + 1,
+                    available,
+                })
+            }
+        }
+    }
+
+    /// Obtains two disjoint slices, which are each contiguous of committed bytes.
+    /// Combined these contain all previously commited data.
+    pub fn split_read(&mut self) -> Result<SplitGrantR<'a, B, I>> {
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        let write = inner.write.load(Acquire);
+        let last = inner.last.load(Acquire);
+        let mut read = inner.read.load(Acquire);
+
+        // Resolve the inverted case or end of read
+        if (read == last) && (write < read) {
+            read = 0;
+            // This has some room for error, the other thread reads this
+            // Impact to Grant:
+            //   Grant checks if read < write to see if inverted. If not inverted, but
+            //     no space left, Grant will initiate an inversion, but will not trigger it
+            // Impact to Commit:
+            //   Commit does not check read, but if Grant has started an inversion,
+            //   grant could move Last to the prior write position
+            // MOVING READ BACKWARDS!
+            inner.read.store(0, Release);
+        }
+
+        let (sz1, sz2) = if write < read {
+            // Inverted, only believe last
+            (last - read, write)
+        } else {
+            // Not inverted, only believe write
+            (write - read, 0)
+        };
+
+        if sz1 == 0 {
+            inner.read_in_progress.store(false, Release);
+            return Err(Error::InsufficientSize {
+                requested: 1,
+                available: 0,
+            });
+        }
+
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (&*inner.buf.get()).storage().as_ptr() as *mut u8 };
+        let grant_slice1 = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(read), sz1) };
+        let grant_slice2 = unsafe { from_raw_parts_mut(start_of_buf_ptr, sz2) };
+        inner.post_read(grant_slice1);
+        inner.post_read(grant_slice2);
+
+        Ok(SplitGrantR {
+            buf1: grant_slice1.into(),
+            buf2: grant_slice2.into(),
+            bbq: self.bbq,
+            to_release: 0,
+            phatom: PhantomData,
+        })
+    }
+
+    /// Obtains exactly `sz` committed bytes, which may span the end of the ring. If less
+    /// than `sz` bytes are available, an error will be returned, and no bytes will be
+    /// released.
+    ///
+    /// Unlike [Self::read_exact], the bytes don't need to be contiguous: if the writer
+    /// has wrapped around, the grant continues at the start of the ring, like
+    /// [Self::split_read]. The combined length of the grant is always `sz`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Fill the end of the ring, and wrap around to the start
+    /// prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+    /// cons.read().unwrap().release(3);
+    /// prod.push_slice_all(&[5, 6]).unwrap();
+    /// prod.push_slice_all(&[7, 8]).unwrap();
+    ///
+    /// // Only three bytes are contiguous, but four are available
+    /// assert!(cons.read_exact(4).is_err());
+    /// let grant = cons.split_read_exact(4).unwrap();
+    /// assert_eq!(grant.bufs(), (&[4, 5, 6][..], &[7][..]));
+    /// grant.release_all();
+    ///
+    /// assert!(cons.split_read_exact(2).is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn split_read_exact(&mut self, sz: usize) -> Result<SplitGrantR<'a, B, I>> {
+        let mut grant = self.split_read().map_err(|err| requested(err, sz))?;
+
+        if grant.combined_len() < sz {
+            let available = grant.combined_len();
+            // Dropping the grant releases nothing
+            drop(grant);
+            return Err(Error::InsufficientSize {
+                requested: sz,
+                available,
+            });
+        }
+
+        grant.shrink(sz);
+        Ok(grant)
+    }
+
+    /// Async version of [Self::read].
+    /// Will wait for the buffer to have data to read. When data is available, the grant is returned.
+    #[cfg(feature = "async-api")]
+    pub fn read_async<'b>(&'b mut self) -> GrantReadFuture<'a, 'b, B, I> {
+        GrantReadFuture { cons: self }
+    }
+
+    /// Waits for at least `sz` committed bytes to be available for reading.
+    ///
+    /// The bytes don't need to be contiguous, use [Self::read_exact_async] to wait for
+    /// a contiguous region. Note that the future is only woken up by commits reaching
+    /// the read watermark, see [BBQueue::set_read_watermark].
+    ///
+    /// If `sz` is larger than the capacity, [Error::RequestTooLarge] is returned.
+    #[cfg(feature = "async-api")]
+    pub fn wait_for_occupied<'b>(&'b mut self, sz: usize) -> WaitForOccupiedFuture<'a, 'b, B, I> {
+        WaitForOccupiedFuture { cons: self, sz }
+    }
+
+    /// Async version of [Self::read_exact].
+    /// Will wait for at least `sz` contiguous bytes to be available, and returns a grant
+    /// of exactly `sz` bytes.
+    ///
+    /// If it's not possible to ever obtain `sz` contiguous bytes without first releasing
+    /// the readable bytes, an error is returned. This is the case if `sz` is larger than
+    /// the capacity ([Error::RequestTooLarge]), or if fewer than `sz` bytes are committed
+    /// before the end of the ring and the writer has already wrapped around.
+    #[cfg(feature = "async-api")]
+    pub fn read_exact_async<'b>(&'b mut self, sz: usize) -> GrantReadExactFuture<'a, 'b, B, I> {
+        GrantReadExactFuture { cons: self, sz }
+    }
+
+    /// Async version of [Self::read_until].
+    /// Will wait for `delim` to be committed, and returns a grant of the bytes up to and
+    /// including its first occurrence.
+    ///
+    /// The future is polled again after each commit, and only searches the contiguous
+    /// readable region. If the writer has wrapped around and `delim` is not found before
+    /// the end of the ring, [Error::InsufficientSize] is returned, as the region can't grow
+    /// until it is released. A record spanning the end of the ring can be read with
+    /// [Self::split_read] instead.
+    ///
+    /// Note that a record without `delim` that fills the whole contiguous region will never
+    /// resolve, as the writer can't commit the rest of it. The buffer must be sized for the
+    /// longest expected record.
+    #[cfg(feature = "async-api")]
+    pub fn read_until_async<'b>(&'b mut self, delim: u8) -> GrantReadUntilFuture<'a, 'b, B, I> {
+        GrantReadUntilFuture { cons: self, delim }
+    }
+
+    /// Async version of [Self::split_read_exact].
+    /// Will wait for at least `sz` bytes to be available, and returns a split grant with
+    /// a combined length of exactly `sz` bytes.
+    ///
+    /// Unlike [Self::read_exact_async], this doesn't fail when the bytes before the end of
+    /// the ring are too few, as the grant continues at the start of the ring. An error is
+    /// still returned if `sz` bytes can never be available without first releasing the
+    /// readable bytes: [Error::RequestTooLarge] if `sz` is larger than the capacity, or
+    /// [Error::InsufficientSize] if the read position leaves less room than `sz`, which
+    /// can only happen when `sz` is close to the capacity.
+    #[cfg(feature = "async-api")]
+    pub fn split_read_exact_async<'b>(
+        &'b mut self,
+        sz: usize,
+    ) -> GrantSplitReadExactFuture<'a, 'b, B, I> {
+        GrantSplitReadExactFuture { cons: self, sz }
+    }
+
+    /// Async version of [Self::split_read].
+    /// Will wait just like [Self::read_async], but returns the split grant to obtain all the available data.
+    #[cfg(feature = "async-api")]
+    pub fn split_read_async<'b>(&'b mut self) -> GrantSplitReadFuture<'a, 'b, B, I> {
+        GrantSplitReadFuture { cons: self }
+    }
+
+    /// Copies as many committed bytes as fit into `out`, and releases them. Returns
+    /// the number of bytes that were read.
+    ///
+    /// Both the end and the start of the ring are read from, using [Self::split_read].
+    /// If no bytes are available for reading, an error will be returned. An empty `out`
+    /// slice always returns `Ok(0)`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.send_slice(&[1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut out = [0u8; 3];
+    /// assert_eq!(cons.read_into(&mut out), Ok(3));
+    /// assert_eq!(out, [1, 2, 3]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read_into(&mut self, out: &mut [u8]) -> Result<usize> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+
+        let grant = self.split_read()?;
+        let (buf1, buf2) = grant.bufs();
+
+        let len1 = min(buf1.len(), out.len());
+        out[..len1].copy_from_slice(&buf1[..len1]);
+
+        let len2 = min(buf2.len(), out.len() - len1);
+        out[len1..][..len2].copy_from_slice(&buf2[..len2]);
+
+        grant.release(len1 + len2);
+        Ok(len1 + len2)
+    }
+
+    /// Copies as many contiguous committed bytes as fit into `out`, and releases them.
+    /// Returns the number of bytes that were read.
+    ///
+    /// This uses [Self::read], so at most one contiguous region is read. See
+    /// [Self::read_into] to also read the bytes after the writer wrapped around. If no
+    /// bytes are available for reading, an error will be returned. An empty `out` slice
+    /// always returns `Ok(0)`.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider, Error};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(&[1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut out = [0u8; 3];
+    /// assert_eq!(cons.pop_slice(&mut out), Ok(3));
+    /// assert_eq!(out, [1, 2, 3]);
+    /// assert_eq!(cons.pop_slice(&mut out), Ok(1));
+    /// assert_eq!(
+    ///     cons.pop_slice(&mut out),
+    ///     Err(Error::InsufficientSize { requested: 1, available: 0 })
+    /// );
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn pop_slice(&mut self, out: &mut [u8]) -> Result<usize> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+
+        let grant = self.read()?;
+        let len = min(grant.len(), out.len());
+        out[..len].copy_from_slice(&grant[..len]);
+        grant.release(len);
+        Ok(len)
+    }
+
+    /// Fills `out` with contiguous committed bytes, and releases them.
+    ///
+    /// This uses [Self::read_exact], so if fewer than `out.len()` contiguous bytes are
+    /// available, nothing is released and an error is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider, Error};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(&[1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut out = [0u8; 3];
+    /// assert_eq!(cons.pop_exact(&mut out), Ok(()));
+    /// assert_eq!(out, [1, 2, 3]);
+    /// assert_eq!(
+    ///     cons.pop_exact(&mut out),
+    ///     Err(Error::InsufficientSize { requested: 3, available: 1 })
+    /// );
+    /// assert_eq!(cons.read().unwrap().len(), 1);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn pop_exact(&mut self, out: &mut [u8]) -> Result<()> {
+        let grant = self.read_exact(out.len())?;
+        out.copy_from_slice(&grant);
+        grant.release(out.len());
+        Ok(())
+    }
+
+    /// Discards up to `n` committed bytes, without obtaining a read grant. Returns the
+    /// number of bytes that were actually discarded, which is less than `n` if less bytes
+    /// were committed.
+    ///
+    /// Unlike [Self::read] followed by [GrantR::release], this also discards the bytes at
+    /// the start of the ring if the writer has wrapped around, in a single call. This is
+    /// useful to resynchronize a corrupted stream.
+    ///
+    /// If a read grant is in progress, an error will be returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(cons.discard(3), Ok(3));
+    /// assert_eq!(cons.discard(3), Ok(1));
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn discard(&mut self, n: usize) -> Result<usize> {
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        let mut discarded = 0;
+
+        // The first region ends at `last` if the writer has wrapped around, and the
+        // second one starts back at the beginning of the ring
+        for _ in 0..2 {
+            let write = inner.write.load(Acquire);
+            let last = inner.last.load(Acquire);
+            let mut read = inner.read.load(Acquire);
+
+            // Resolve the inverted case or end of read, like `Self::read`
+            if (read == last) && (write < read) {
+                read = 0;
+                inner.read.store(0, Release);
+            }
+
+            let available = if write < read {
+                // Inverted, only believe last
+                last
+            } else {
+                // Not inverted, only believe write
+                write
+            } - read;
+
+            let sz = min(n - discarded, available);
+            if sz == 0 {
+                break;
+            }
+
+            // This should be fine, purely incrementing
+            let _ = atomic::fetch_add(&inner.read, sz, Release);
+            discarded += sz;
+        }
+
+        inner.read_in_progress.store(false, Release);
+
+        // Only wake the writer if some space was freed
+        if discarded != 0 {
+            #[cfg(feature = "metrics")]
+            atomic::fetch_add(&inner.counters.released, discarded, AcqRel);
+
+            #[cfg(feature = "async-api")]
+            inner.write_waker.wake();
+        }
+
+        Ok(discarded)
+    }
+
+    /// Releases all committed bytes, discarding them. Returns the number of bytes that
+    /// were discarded.
+    ///
+    /// This is useful to recover from errors, such as a desynchronized framing, without
+    /// having to release and split the queue again. If the writer has wrapped around, both
+    /// the bytes at the end of the ring and the bytes at the start of the ring are discarded.
+    /// Bytes committed concurrently by the producer may or may not be discarded.
+    ///
+    /// If a read grant is in progress, an error will be returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(cons.drain_all(), Ok(4));
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn drain_all(&mut self) -> Result<usize> {
+        self.discard(usize::MAX)
+    }
+
+    /// Discards all committed bytes at once, by moving the read pointer to the write
+    /// pointer. Returns the number of bytes that were discarded.
+    ///
+    /// Unlike [Self::drain_all], this also moves both pointers back to the start of the
+    /// ring if no write grant is in progress, so the whole capacity of the queue can be
+    /// granted again. The pointers of a queue split with
+    /// [MultiBBQueue::try_split_multi](crate::multi::MultiBBQueue::try_split_multi) are
+    /// never moved back, as its shared producers may reserve space at any time. Bytes committed concurrently by the producer may or may not be
+    /// discarded. To reset a queue which isn't split, see [BBQueue::clear].
+    ///
+    /// If a read grant is in progress, an error will be returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(cons.flush(), Ok(4));
+    /// assert!(cons.read().is_err());
+    ///
+    /// // The whole queue is available again
+    /// assert!(prod.grant_exact(6).is_ok());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<usize> {
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        if atomic::swap(&inner.read_in_progress, true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        // Also take the role of the writer if we can, so the pointers can be rewound
+        let writing = atomic::swap(&inner.write_in_progress, true, AcqRel);
+
+        let write = inner.write.load(Acquire);
+        // Never rewind over reserved bytes, even if no grant seems to be in progress
+        let reserved = inner.reserve.load(Acquire) != write;
+        let last = inner.last.load(Acquire);
+        let read = inner.read.load(Acquire);
+
+        let discarded = if write < read {
+            // Inverted, the bytes up to last, then from the start of the ring
+            (last - read) + write
+        } else {
+            write - read
+        };
+
+        if writing || reserved {
+            // The producer only ever grants from `write`, so it can't overlap the read
+            // pointer, even if this moves it backwards out of the inverted region
+            inner.read.store(write, Release);
+        } else {
+            // Same state as a freshly split queue
+            inner.last.store(0, Release);
+            inner.reserve.store(0, Release);
+            inner.write.store(0, Release);
+            inner.read.store(0, Release);
+        }
+
+        if !writing {
+            inner.write_in_progress.store(false, Release);
+        }
+        inner.read_in_progress.store(false, Release);
+
+        if discarded != 0 {
+            #[cfg(feature = "metrics")]
+            atomic::fetch_add(&inner.counters.released, discarded, AcqRel);
+        }
+
+        // Wake the writer even if nothing was discarded, the pointers may have moved
+        #[cfg(feature = "async-api")]
+        inner.write_waker.wake();
+
+        Ok(discarded)
+    }
+
+    /// Returns the number of bytes that have been committed, but not yet released.
+    ///
+    /// See [BBQueue::occupied_len] for details.
+    pub fn len(&self) -> usize {
+        unsafe { self.bbq.as_ref().occupied_len() }
+    }
+
+    /// Returns `true` if no bytes are committed and not yet released.
+    ///
+    /// See [BBQueue::is_empty] for details.
+    pub fn is_empty(&self) -> bool {
+        unsafe { self.bbq.as_ref().is_empty() }
+    }
+
+    /// Returns `true` if no bytes could be granted to the producer.
+    ///
+    /// See [BBQueue::is_full] for details.
+    pub fn is_full(&self) -> bool {
+        unsafe { self.bbq.as_ref().is_full() }
+    }
+
+    /// Returns `true` if the producer closed the queue, see [Producer::close].
+    ///
+    /// Bytes committed before closing may still be available for reading.
+    pub fn is_closed(&self) -> bool {
+        unsafe { self.bbq.as_ref() }.closed.load(Acquire)
+    }
+
+    /// Borrows this consumer as a new `Consumer`, see [Producer::reborrow].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, Consumer, StaticStorageProvider};
+    ///
+    /// fn read_one(mut cons: Consumer<'_, StaticStorageProvider<6>>) -> u8 {
+    ///     let rgr = cons.read().unwrap();
+    ///     let byte = rgr[0];
+    ///     rgr.release(1);
+    ///     byte
+    /// }
+    ///
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    /// let mut wgr = prod.grant_exact(2).unwrap();
+    /// wgr.copy_from_slice(&[1, 2]);
+    /// wgr.commit(2);
+    ///
+    /// assert_eq!(read_one(cons.reborrow()), 1);
+    /// assert_eq!(read_one(cons.reborrow()), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn reborrow(&mut self) -> Consumer<'_, B, I> {
+        Consumer {
+            bbq: self.bbq,
+            pd: PhantomData,
+            reborrowed: true,
+        }
+    }
+
+    /// Waits for more bytes to be committed, unless the queue was closed before the
+    /// failed attempt to read them, as no more bytes will ever be committed then.
+    #[cfg(feature = "async-api")]
+    fn wait_for_commit<T>(&self, closed: bool, cx: &mut Context<'_>) -> Poll<Result<T>> {
+        if closed {
+            return Poll::Ready(Err(Error::Closed));
+        }
+
+        unsafe { self.bbq.as_ref() }.read_waker.register(cx.waker());
+        Poll::Pending
+    }
+}
+
+impl<B, I> BBQueue<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Returns the size of the backing storage.
+    ///
+    /// This is the maximum number of bytes that can be stored in this queue.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// assert_eq!(buffer.capacity(), 6);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Called by the reader after releasing bytes. If the whole end of the ring was
+    /// released, wraps `read` around to the start of the ring, like the next read
+    /// would, so the writer can use the end of the ring right away.
+    fn wrap_read(&self) {
+        let read = self.read.load(Acquire);
+
+        // While inverted, the writer doesn't move `last`, and `write` stays
+        // before `read`, see `read`
+        if read == self.last.load(Acquire) && self.write.load(Acquire) < read {
+            self.read.store(0, Release);
+        }
+    }
+
+    /// Offset of `buf` from the start of the buffer, in bytes
+    pub(crate) fn offset_of(&self, buf: NonNull<[u8]>) -> usize {
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (&*self.buf.get()).storage().as_ptr() as *mut u8 };
+        (buf.as_ptr() as *mut u8 as usize).wrapping_sub(start_of_buf_ptr as usize)
+    }
+
+    /// Sets the number of committed bytes required before the consumer is woken up.
+    ///
+    /// By default, the consumer is woken up on every commit. With a watermark, a commit only
+    /// wakes the consumer if at least `bytes` bytes are available for reading afterwards.
+    /// This avoids waking up a task for every small commit.
+    ///
+    /// The watermark only suppresses wakeups. A future of the [Consumer] polled for another
+    /// reason still resolves with fewer bytes, for instance [Consumer::read_async] returns as
+    /// soon as any byte is available. Use [Consumer::wait_for_occupied] to wait for a number
+    /// of bytes.
+    ///
+    /// Returns an error if `bytes` is larger than the capacity, as the watermark could
+    /// never be reached.
+    ///
+    /// Only available with the async API, see the `async` and `cs-waker` features.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// assert!(buffer.set_read_watermark(4).is_ok());
+    /// assert_eq!(buffer.read_watermark(), 4);
+    ///
+    /// // Larger than the capacity
+    /// assert!(buffer.set_read_watermark(7).is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[cfg(feature = "async-api")]
+    pub fn set_read_watermark(&self, bytes: usize) -> Result<()> {
+        if bytes > self.capacity {
+            return Err(Error::InsufficientSize {
+                requested: bytes,
+                available: self.capacity,
+            });
+        }
+
+        self.read_watermark.store(bytes, Release);
+        Ok(())
+    }
+
+    /// Returns the number of committed bytes required before the consumer is woken up.
+    ///
+    /// See [Self::set_read_watermark] for details.
+    #[cfg(feature = "async-api")]
+    pub fn read_watermark(&self) -> usize {
+        self.read_watermark.load(Acquire)
+    }
+
+    /// Sets the hooks called when bytes are committed by the producer, and when bytes are
+    /// handed out to the consumer, see [BbqHooks].
+    ///
+    /// The hooks can only be set before the queue is split, an [Error::AlreadySplit] is
+    /// returned otherwise.
+    ///
+    /// Only available with the `hooks` feature.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, BbqHooks, StaticStorageProvider};
+    ///
+    /// struct NoHooks;
+    /// impl BbqHooks for NoHooks {}
+    ///
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// assert!(buffer.set_hooks(&NoHooks).is_ok());
+    ///
+    /// // Not possible once split
+    /// let (prod, cons) = buffer.try_split().unwrap();
+    /// assert!(buffer.set_hooks(&NoHooks).is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[cfg(feature = "hooks")]
+    pub fn set_hooks(&self, hooks: &'static dyn BbqHooks) -> Result<()> {
+        // Hold the split flag while the hooks are updated, no grant may exist
+        if atomic::swap(&self.already_split, true, AcqRel) {
+            return Err(Error::AlreadySplit);
+        }
+
+        unsafe { *self.hooks.get() = Some(hooks) };
+        self.already_split.store(false, Release);
+        Ok(())
+    }
+
+    /// Returns the maximum number of occupied bytes the queue has reached, since it was
+    /// created or since the last call to [Self::reset_high_water_mark].
+    ///
+    /// The occupancy is sampled after each commit, and includes the bytes at the end of
+    /// the ring and at the start of the ring when the queue is inverted. See
+    /// [Self::occupied_len] for details. This is useful to tune the size of a queue.
+    ///
+    /// Only available with the `metrics` feature.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// cons.read().unwrap().release(4);
+    /// prod.grant_exact(2).unwrap().commit(2);
+    /// assert_eq!(buffer.high_water_mark(), 4);
+    ///
+    /// buffer.reset_high_water_mark();
+    /// assert_eq!(buffer.high_water_mark(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(all(not(feature = "thumbv6"), feature = "metrics"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn high_water_mark(&self) -> usize {
+        self.high_water_mark.load(Acquire)
+    }
+
+    /// Resets the high water mark to the current number of occupied bytes.
+    ///
+    /// Only available with the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn reset_high_water_mark(&self) {
+        self.high_water_mark.store(self.occupied_len(), Release);
+    }
+
+    /// Returns the activity counters of the queue.
+    ///
+    /// Only available with the `metrics` feature.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// assert!(prod.grant_exact(4).is_err());
+    /// cons.read().unwrap().release(3);
+    ///
+    /// let stats = buffer.stats();
+    /// assert_eq!(stats.committed, 4);
+    /// assert_eq!(stats.released, 3);
+    /// assert_eq!(stats.grants_granted, 1);
+    /// assert_eq!(stats.grants_denied, 1);
+    /// assert_eq!(stats.max_occupied, 4);
+    ///
+    /// // At any quiescent point, the committed bytes are either released or still in the queue
+    /// assert_eq!(stats.committed, stats.released + buffer.occupied_len() as u64);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(all(not(feature = "thumbv6"), feature = "metrics"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            committed: self.counters.committed.load(Acquire) as u64,
+            released: self.counters.released.load(Acquire) as u64,
+            grants_granted: self.counters.grants_granted.load(Acquire) as u64,
+            grants_denied: self.counters.grants_denied.load(Acquire) as u64,
+            max_occupied: self.high_water_mark() as u64,
+        }
+    }
+
+    /// Run the `pre_commit` hook on bytes about to be published to the reader
+    #[inline(always)]
+    #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+    pub(crate) fn pre_commit(&self, data: &[u8]) {
+        #[cfg(feature = "hooks")]
+        if let Some(hooks) = unsafe { *self.hooks.get() } {
+            if !data.is_empty() {
+                hooks.pre_commit(data);
+            }
+        }
+    }
+
+    /// Run the `post_read` hook on bytes about to be handed out to the reader
+    #[inline(always)]
+    #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+    pub(crate) fn post_read(&self, data: &[u8]) {
+        #[cfg(feature = "hooks")]
+        if let Some(hooks) = unsafe { *self.hooks.get() } {
+            if !data.is_empty() {
+                hooks.post_read(data);
+            }
+        }
+    }
+
+    /// Update the metrics and wake the reader, once `used` bytes have been committed
+    pub(crate) fn committed(&self, used: usize) {
+        if used != 0 {
+            #[cfg(any(feature = "metrics", feature = "async-api"))]
+            let occupied = self.occupied_len();
+
+            #[cfg(feature = "metrics")]
+            {
+                atomic::fetch_add(&self.counters.committed, used, AcqRel);
+                self.update_high_water_mark(occupied);
+            }
+
+            // Only wake the reader once enough bytes are available
+            #[cfg(feature = "async-api")]
+            if occupied >= self.read_watermark.load(Acquire) {
+                self.read_waker.wake();
+            }
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    fn update_high_water_mark(&self, occupied: usize) {
+        // Only the producer raises the mark, so this doesn't need to be a single atomic
+        // operation, which isn't available on all targets
+        if occupied > self.high_water_mark.load(Acquire) {
+            self.high_water_mark.store(occupied, Release);
+        }
+    }
+
+    /// Returns the number of bytes that have been committed, but not yet released.
+    ///
+    /// Bytes that are part of an active read grant are still counted as occupied until
+    /// the grant is released.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the other half of the
+    /// queue is in use concurrently, the value may already be outdated when it is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    ///
+    /// // Commit four bytes
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// assert_eq!(buffer.occupied_len(), 4);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn occupied_len(&self) -> usize {
+        let write = self.write.load(Acquire);
+        let read = self.read.load(Acquire);
+
+        if write < read {
+            // Inverted, the readable bytes are from read to last,
+            // and from the start of the ring to write
+            let last = self.last.load(Acquire);
+            last.saturating_sub(read) + write
+        } else {
+            write - read
+        }
+    }
+
+    /// Returns the number of bytes that could still be granted to the producer, summed
+    /// over the region at the end of the ring and the region at the start of the ring.
+    ///
+    /// Bytes reserved by an active write grant are not counted as free. Neither are bytes
+    /// that were skipped at the end of the ring by a wrapping grant, until the reader has
+    /// moved past them. As the write pointer may never catch up to the read pointer in
+    /// an inverted condition, one byte of the ring is also never reported as free in that
+    /// case. Because of this, `occupied_len() + free_len()` may be smaller than `capacity()`.
+    ///
+    /// Note that a grant of `free_len()` bytes is not guaranteed to succeed, as all grants
+    /// must be contiguous.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the other half of the
+    /// queue is in use concurrently, the value may already be outdated when it is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    /// assert_eq!(buffer.free_len(), 6);
+    ///
+    /// // Reserved bytes are not free
+    /// let grant = prod.grant_exact(4).unwrap();
+    /// assert_eq!(buffer.free_len(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn free_len(&self) -> usize {
+        // The reserve pointer is equal to the write pointer when no write grant is
+        // active, and marks the end of the active write grant otherwise
+        let reserve = self.reserve.load(Acquire);
+        let read = self.read.load(Acquire);
+
+        if reserve < read {
+            // Inverted, we may only write up to the byte before the read pointer
+            read - reserve - 1
+        } else {
+            // Not inverted, we may write up to the end of the ring, and then from
+            // the start of the ring up to the byte before the read pointer
+            (self.capacity - reserve) + read.saturating_sub(1)
+        }
+    }
+
+    /// Returns `true` if no bytes are committed and not yet released, like
+    /// `occupied_len() == 0`.
+    ///
+    /// This only loads the pointers of the queue, and has no side effect, so it can be
+    /// used as a cheap check, for instance in an interrupt handler, before trying to
+    /// obtain a read grant.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the other half of the
+    /// queue is in use concurrently, the value may already be outdated when it is returned.
+    pub fn is_empty(&self) -> bool {
+        self.occupied_len() == 0
+    }
+
+    /// Returns `true` if no bytes could be granted to the producer, like
+    /// `free_len() == 0`.
+    ///
+    /// As the write pointer may never catch up to the read pointer in an inverted
+    /// condition, the queue is full once a single byte is left before the read pointer.
+    /// Bytes reserved by an active write grant are not free, see [Self::free_len].
+    ///
+    /// This only loads the pointers of the queue, and has no side effect, so it can be
+    /// used as a cheap check, for instance in an interrupt handler, before trying to
+    /// obtain a write grant.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the other half of the
+    /// queue is in use concurrently, the value may already be outdated when it is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    /// assert!(buffer.is_empty());
+    ///
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// cons.read().unwrap().release(2);
+    /// prod.grant_exact(2).unwrap().commit(2);
+    ///
+    /// // Wrap around, the last byte before the read pointer can't be used
+    /// prod.grant_exact(1).unwrap().commit(1);
+    /// assert!(buffer.is_full());
+    /// assert!(!buffer.is_empty());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.free_len() == 0
+    }
+
+    /// Returns the number of bytes at the end of the ring that were skipped by a
+    /// wrapping grant, and can't be used until the reader has wrapped around too.
+    ///
+    /// When a grant such as [Producer::grant_exact] doesn't fit before the end of the
+    /// ring, it is given from the start of the ring instead, and the bytes between the
+    /// end of the committed data and the end of the ring are left unused. These bytes
+    /// are neither occupied nor free, see [Self::occupied_len] and [Self::free_len].
+    /// Use [Producer::grant_exact_no_wrap] to avoid skipping bytes.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the other half of the
+    /// queue is in use concurrently, the value may already be outdated when it is returned.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(5).unwrap().commit(5);
+    /// cons.read().unwrap().release(5);
+    ///
+    /// // Only one byte is left before the end of the ring, so the grant wraps around
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// assert_eq!(buffer.skipped_len(), 1);
+    ///
+    /// // Once the reader wraps around, no bytes are skipped anymore
+    /// cons.read().unwrap().release(4);
+    /// assert_eq!(buffer.skipped_len(), 0);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn skipped_len(&self) -> usize {
+        let write = self.write.load(Acquire);
+        let read = self.read.load(Acquire);
+
+        if write < read {
+            // Inverted, the bytes from last to the end of the ring are unused
+            self.capacity - self.last.load(Acquire)
+        } else {
+            0
+        }
     }
-}
 
-impl<B> BBQueue<B>
-where
-    B: StorageProvider,
-{
-    /// Returns the size of the backing storage.
+    /// Returns the current positions of the pointers of the queue, for diagnostics.
     ///
-    /// This is the maximum number of bytes that can be stored in this queue.
+    /// NOTE: This is a best-effort snapshot, meant for debugging tools such as a live view
+    /// of the queue occupancy. The pointers are loaded one after the other, so if the queue
+    /// is in use concurrently, they may not be consistent with each other. They must not be
+    /// used to make decisions about the data in the queue.
     ///
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
-    /// // Create a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
-    /// assert_eq!(buffer.capacity(), 6);
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, cons) = buffer.try_split().unwrap();
+    ///
+    /// let grant = prod.grant_exact(4).unwrap();
+    /// let positions = buffer.debug_positions();
+    /// assert_eq!((positions.write, positions.reserve), (0, 4));
+    /// assert!(!positions.inverted);
     /// # // bbqueue test shim!
     /// # }
     /// #
@@ -834,8 +3792,17 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub const fn capacity(&self) -> usize {
-        self.capacity
+    pub fn debug_positions(&self) -> Positions {
+        let write = self.write.load(Acquire);
+        let read = self.read.load(Acquire);
+
+        Positions {
+            write,
+            read,
+            last: self.last.load(Acquire),
+            reserve: self.reserve.load(Acquire),
+            inverted: write < read,
+        }
     }
 }
 
@@ -849,18 +3816,107 @@ where
 ///
 /// If the `thumbv6` feature is selected, dropping the grant
 /// without committing it takes a short critical section,
-#[derive(Debug, PartialEq)]
-pub struct GrantW<'a, B>
+#[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+#[derive(PartialEq)]
+pub struct GrantW<'a, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     pub(crate) buf: NonNull<[u8]>,
-    bbq: NonNull<BBQueue<B>>,
+    bbq: NonNull<BBQueue<B, I>>,
+    pub(crate) to_commit: usize,
+    // Which part of a grant split by `split_at` this is
+    #[cfg(feature = "split-grants")]
+    part: GrantPart,
+    // Was the buffer lent out by `as_static_mut_buf`?
+    #[cfg(debug_assertions)]
+    lent: bool,
+    phatom: PhantomData<&'a mut [u8]>,
+}
+
+/// The part of a write grant, see [GrantW::split_at]
+#[cfg(feature = "split-grants")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum GrantPart {
+    /// A grant which was not split
+    Whole,
+    /// The start of the `whole` grant
+    Head { whole: NonNull<[u8]> },
+    /// The end of the `whole` grant
+    Tail { whole: NonNull<[u8]> },
+}
+
+unsafe impl<'a, B, I> Send for GrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Shows the position of the grant in the buffer of the queue
+impl<'a, B, I> fmt::Debug for GrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = unsafe { self.bbq.as_ref() };
+        f.debug_struct("GrantW")
+            .field("queue", &self.bbq)
+            .field("offset", &inner.offset_of(self.buf))
+            .field("len", &self.buf.len())
+            .field("to_commit", &self.to_commit)
+            .finish()
+    }
+}
+
+/// A structure representing up to two contiguous regions of memory that
+/// may be written to, and potentially "committed" to the queue
+///
+/// NOTE: If the grant is dropped without explicitly commiting
+/// the contents, or by setting a the number of bytes to
+/// automatically be committed with `to_commit()`, then no bytes
+/// will be comitted for writing.
+#[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+#[derive(PartialEq)]
+pub struct SplitGrantW<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) buf1: NonNull<[u8]>,
+    pub(crate) buf2: NonNull<[u8]>,
+    bbq: NonNull<BBQueue<B, I>>,
     pub(crate) to_commit: usize,
     phatom: PhantomData<&'a mut [u8]>,
 }
 
-unsafe impl<'a, B> Send for GrantW<'a, B> where B: StorageProvider {}
+unsafe impl<'a, B, I> Send for SplitGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Shows the position of the grant in the buffer of the queue. The second region
+/// always starts at the beginning of the buffer.
+impl<'a, B, I> fmt::Debug for SplitGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = unsafe { self.bbq.as_ref() };
+        f.debug_struct("SplitGrantW")
+            .field("queue", &self.bbq)
+            .field("offset", &inner.offset_of(self.buf1))
+            .field("len1", &self.buf1.len())
+            .field("len2", &self.buf2.len())
+            .field("to_commit", &self.to_commit)
+            .finish()
+    }
+}
 
 /// A structure representing a contiguous region of memory that
 /// may be read from, and potentially "released" (or cleared)
@@ -874,39 +3930,220 @@ unsafe impl<'a, B> Send for GrantW<'a, B> where B: StorageProvider {}
 ///
 /// If the `thumbv6` feature is selected, dropping the grant
 /// without releasing it takes a short critical section,
-#[derive(Debug, PartialEq)]
-pub struct GrantR<'a, B>
+#[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+#[derive(PartialEq)]
+pub struct GrantR<'a, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     pub(crate) buf: NonNull<[u8]>,
-    bbq: NonNull<BBQueue<B>>,
+    bbq: NonNull<BBQueue<B, I>>,
     pub(crate) to_release: usize,
+    retained: usize,
     phatom: PhantomData<&'a mut [u8]>,
 }
 
 /// A structure representing up to two contiguous regions of memory that
 /// may be read from, and potentially "released" (or cleared)
 /// from the queue
-#[derive(Debug, PartialEq)]
-pub struct SplitGrantR<'a, B>
+#[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+#[derive(PartialEq)]
+pub struct SplitGrantR<'a, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     pub(crate) buf1: NonNull<[u8]>,
     pub(crate) buf2: NonNull<[u8]>,
-    bbq: NonNull<BBQueue<B>>,
+    bbq: NonNull<BBQueue<B, I>>,
     pub(crate) to_release: usize,
     phatom: PhantomData<&'a mut [u8]>,
 }
 
-unsafe impl<'a, B> Send for GrantR<'a, B> where B: StorageProvider {}
+/// Commits a write grant split by [GrantW::into_raw_parts], once the buffer is no
+/// longer accessed through its raw pointer.
+///
+/// The token has the lifetime of the queue, so it is `'static` for a queue in a
+/// `static`, and can be moved to another thread or to an interrupt handler.
+#[must_use = "a commit token commits nothing when dropped"]
+#[derive(Debug, PartialEq)]
+pub struct CommitToken<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    grant: GrantW<'a, B, I>,
+}
+
+impl<'a, B, I> CommitToken<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Commits `used` bytes of the grant, see [GrantW::commit].
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be accessed through the raw pointer given by
+    /// [GrantW::into_raw_parts] anymore, as the committed bytes can be read, and the
+    /// others reused by the next grant, right away.
+    pub unsafe fn commit(self, used: usize) {
+        self.grant.commit(used);
+    }
+}
+
+/// Releases a read grant split by [GrantR::into_raw_parts], once the buffer is no
+/// longer accessed through its raw pointer.
+///
+/// Like [CommitToken], the token has the lifetime of the queue.
+#[must_use = "a release token releases nothing when dropped"]
+#[derive(Debug, PartialEq)]
+pub struct ReleaseToken<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    grant: GrantR<'a, B, I>,
+}
+
+impl<'a, B, I> ReleaseToken<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Releases `used` bytes of the grant, see [GrantR::release].
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be accessed through the raw pointer given by
+    /// [GrantR::into_raw_parts] anymore, as the released bytes can be overwritten by the
+    /// next write grant right away.
+    pub unsafe fn release(self, used: usize) {
+        self.grant.release(used);
+    }
+}
+
+unsafe impl<'a, B, I> Send for GrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Shows the position of the grant in the buffer of the queue
+impl<'a, B, I> fmt::Debug for GrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = unsafe { self.bbq.as_ref() };
+        f.debug_struct("GrantR")
+            .field("queue", &self.bbq)
+            .field("offset", &inner.offset_of(self.buf))
+            .field("len", &self.buf.len())
+            .field("to_release", &self.to_release)
+            .finish()
+    }
+}
+
+unsafe impl<'a, B, I> Send for SplitGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+/// Shows the position of the grant in the buffer of the queue. The second region
+/// always starts at the beginning of the buffer.
+impl<'a, B, I> fmt::Debug for SplitGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = unsafe { self.bbq.as_ref() };
+        f.debug_struct("SplitGrantR")
+            .field("queue", &self.bbq)
+            .field("offset", &inner.offset_of(self.buf1))
+            .field("len1", &self.buf1.len())
+            .field("len2", &self.buf2.len())
+            .field("to_release", &self.to_release)
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for GrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "GrantW {{ len: {=usize}, to_commit: {=usize} }}",
+            self.buf.len(),
+            self.to_commit,
+        );
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for SplitGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "SplitGrantW {{ len1: {=usize}, len2: {=usize}, to_commit: {=usize} }}",
+            self.buf1.len(),
+            self.buf2.len(),
+            self.to_commit,
+        );
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for GrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "GrantR {{ len: {=usize}, to_release: {=usize} }}",
+            self.buf.len(),
+            self.to_release,
+        );
+    }
+}
 
-unsafe impl<'a, B> Send for SplitGrantR<'a, B> where B: StorageProvider {}
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for SplitGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "SplitGrantR {{ len1: {=usize}, len2: {=usize}, to_release: {=usize} }}",
+            self.buf1.len(),
+            self.buf2.len(),
+            self.to_release,
+        );
+    }
+}
 
-impl<'a, B> GrantW<'a, B>
+impl<'a, B, I> GrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Finalizes a writable grant given by `grant()` or `grant_max()`.
     /// This makes the data available to be read via `read()`. This consumes
@@ -922,15 +4159,119 @@ where
         forget(self);
     }
 
+    /// Same as [Self::commit], but `used` is checked instead of saturated. If `used` is
+    /// larger than the grant, nothing is committed, and the grant is given back with
+    /// [Error::InsufficientSize].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, Error, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// let grant = prod.grant_exact(4).unwrap();
+    /// let (grant, err) = grant.try_commit(5).unwrap_err();
+    /// assert_eq!(err, Error::InsufficientSize { requested: 5, available: 4 });
+    /// assert!(cons.read().is_err());
+    ///
+    /// grant.try_commit(4).unwrap();
+    /// assert_eq!(cons.read().unwrap().len(), 4);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn try_commit(self, used: usize) -> CoreResult<(), (Self, Error)> {
+        let available = self.buf.len();
+        if used > available {
+            return Err((
+                self,
+                Error::InsufficientSize {
+                    requested: used,
+                    available,
+                },
+            ));
+        }
+
+        self.commit(used);
+        Ok(())
+    }
+
+    /// Abandons a writable grant without committing any bytes. This consumes
+    /// the grant.
+    ///
+    /// This is equivalent to dropping the grant without calling `to_commit()`,
+    /// and makes the intent explicit. The consumer is not woken up.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.grant_exact(4).unwrap().abort();
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn abort(self) {
+        self.commit(0);
+    }
+
+    /// Finalizes a writable grant, committing ALL of the granted bytes. This is
+    /// equivalent to `commit(grant.len())`. This consumes the grant.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Obtain and commit a full grant of four bytes
+    /// let mut grant = prod.grant_exact(4).unwrap();
+    /// grant.buf().copy_from_slice(&[1, 2, 3, 4]);
+    /// grant.commit_all();
+    ///
+    /// assert_eq!(cons.read().unwrap().buf(), &[1, 2, 3, 4]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn commit_all(self) {
+        let len = self.buf.len();
+        self.commit(len);
+    }
+
     /// Obtain access to the inner buffer for writing
     ///
     /// ```rust
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, mut cons) = buffer.try_split().unwrap();
     ///
     /// // Successfully obtain and commit a grant of four bytes
@@ -945,28 +4286,192 @@ where
     /// # bbqtest();
     /// # }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// With debug assertions enabled, panics if the buffer was lent out by
+    /// [Self::as_static_mut_buf].
     pub fn buf(&mut self) -> &mut [u8] {
+        self.check_not_lent();
         unsafe { from_raw_parts_mut(self.buf.as_ptr() as *mut u8, self.buf.len()) }
     }
 
+    /// Obtain a [GrantWriter] to write formatted text into the grant
+    ///
+    /// The grant is not committed by the writer, commit
+    /// [GrantWriter::written] bytes once done.
+    ///
+    /// # Panics
+    ///
+    /// With debug assertions enabled, panics if the buffer was lent out by
+    /// [Self::as_static_mut_buf].
+    pub fn writer(&mut self) -> GrantWriter<'_> {
+        GrantWriter::new(self.buf())
+    }
+
+    /// Divides the grant into two grants, of the first `n` bytes and of the
+    /// remaining bytes, which can be written and committed independently.
+    ///
+    /// The bytes are committed once both grants are committed or dropped. The
+    /// bytes committed by the second grant are only committed if the first grant
+    /// is committed entirely, otherwise the committed bytes would not be
+    /// contiguous. A size configured by [Self::to_commit] is divided between
+    /// both grants.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Write a header and a body separately
+    /// let (mut header, mut body) = prod.grant_exact(6).unwrap().split_at(2);
+    /// body[..3].copy_from_slice(&[3, 4, 5]);
+    /// body.commit(3);
+    /// header.copy_from_slice(&[1, 2]);
+    /// header.commit(2);
+    ///
+    /// assert_eq!(&*cons.read().unwrap(), &[1, 2, 3, 4, 5]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is larger than the grant, or if the grant is already a part of
+    /// a split grant. With debug assertions enabled, also panics if the buffer was
+    /// lent out by [Self::as_static_mut_buf].
+    ///
+    /// Only available with the `split-grants` feature.
+    #[cfg(feature = "split-grants")]
+    pub fn split_at(self, n: usize) -> (Self, Self) {
+        assert!(n <= self.buf.len(), "the split point is out of the grant");
+        assert!(
+            self.part == GrantPart::Whole,
+            "a part of a split grant can't be split again"
+        );
+        self.check_not_lent();
+
+        let inner = unsafe { self.bbq.as_ref() };
+        inner.split_parts.store(2, Release);
+
+        // Don't create references to the buffer, the whole grant is committed
+        // through `self.buf` once the parts are
+        let whole = self.buf;
+        let ptr = whole.as_ptr() as *mut u8;
+        let part = |start: usize, len: usize, to_commit: usize, part: GrantPart| GrantW {
+            buf: NonNull::slice_from_raw_parts(
+                unsafe { NonNull::new_unchecked(ptr.add(start)) },
+                len,
+            ),
+            bbq: self.bbq,
+            to_commit,
+            part,
+            #[cfg(debug_assertions)]
+            lent: false,
+            phatom: PhantomData,
+        };
+
+        let head = part(0, n, min(self.to_commit, n), GrantPart::Head { whole });
+        let tail = part(
+            n,
+            whole.len() - n,
+            self.to_commit.saturating_sub(n),
+            GrantPart::Tail { whole },
+        );
+
+        // The parts commit the grant
+        forget(self);
+
+        (head, tail)
+    }
+
     /// Sometimes, it's not possible for the lifetimes to check out. For example,
     /// if you need to hand this buffer to a function that expects to receive a
     /// `&'static mut [u8]`, it is not possible for the inner reference to outlive the
     /// grant itself.
     ///
+    /// # Safety
+    ///
     /// You MUST guarantee that in no cases, the reference that is returned here outlives
     /// the grant itself. Once the grant has been released, referencing the data contained
     /// WILL cause undefined behavior.
     ///
     /// Additionally, you must ensure that a separate reference to this data is not created
-    /// to this data, e.g. using `DerefMut` or the `buf()` method of this grant.
+    /// to this data, e.g. using `DerefMut` or the `buf()` method of this grant. With debug
+    /// assertions enabled, this is checked: once the buffer was lent out, accessing it
+    /// through the grant, or lending it out again, panics. Committing the grant is allowed.
     pub unsafe fn as_static_mut_buf(&mut self) -> &'static mut [u8] {
-        transmute::<&mut [u8], &'static mut [u8]>(self.buf())
+        let buf = transmute::<&mut [u8], &'static mut [u8]>(self.buf());
+
+        #[cfg(debug_assertions)]
+        {
+            self.lent = true;
+        }
+
+        buf
+    }
+
+    /// Splits the grant into a raw pointer to its buffer, the length of the buffer, and
+    /// a [CommitToken] to commit it later. This is a safer alternative to
+    /// [Self::as_static_mut_buf] to hand the buffer to a DMA driver: the token doesn't
+    /// give access to the buffer, and can be moved to the interrupt handler which is
+    /// notified of the completion of the transfer.
+    ///
+    /// Dropping the token without committing it is the same as dropping the grant.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// let (ptr, len, token) = prod.grant_exact(4).unwrap().into_raw_parts();
+    /// assert_eq!(len, 4);
+    ///
+    /// // The "DMA transfer" writes to the buffer
+    /// unsafe { ptr.as_ptr().copy_from([1, 2, 3].as_ptr(), 3) };
+    ///
+    /// // Safety: the buffer is no longer accessed
+    /// unsafe { token.commit(3) };
+    /// assert_eq!(&*cons.read().unwrap(), &[1, 2, 3]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn into_raw_parts(self) -> (NonNull<u8>, usize, CommitToken<'a, B, I>) {
+        let ptr = self.buf.cast::<u8>();
+        let len = self.buf.len();
+        (ptr, len, CommitToken { grant: self })
+    }
+
+    /// Panics if the buffer was lent out by `as_static_mut_buf`, with debug assertions
+    #[inline(always)]
+    fn check_not_lent(&self) {
+        #[cfg(debug_assertions)]
+        assert!(
+            !self.lent,
+            "the buffer of the grant was lent out by `as_static_mut_buf`"
+        );
     }
 
     #[inline(always)]
     pub(crate) fn commit_inner(&mut self, used: usize) {
-        let len = self.buf.len();
         let inner = unsafe { &mut self.bbq.as_ref() };
 
         // If there is no grant in progress, return early. This
@@ -976,16 +4481,31 @@ where
             return;
         }
 
+        // Saturate the grant commit
+        let used = min(self.buf.len(), used);
+
+        // Only the last part of a split grant commits, for the whole grant
+        #[cfg(feature = "split-grants")]
+        let (buf, used) = match self.part {
+            GrantPart::Whole => (self.buf, used),
+            _ => match self.commit_part(used) {
+                Some(whole) => whole,
+                None => return,
+            },
+        };
+        #[cfg(not(feature = "split-grants"))]
+        let buf = self.buf;
+        let len = buf.len();
+
         // Writer component. Must never write to READ,
         // be careful writing to LAST
 
-        // Saturate the grant commit
-        let used = min(len, used);
+        inner.pre_commit(unsafe { &buf.as_ref()[..used] });
 
         let write = inner.write.load(Acquire);
         atomic::fetch_sub(&inner.reserve, len - used, AcqRel);
 
-        let max = len;
+        let max = inner.capacity();
         let last = inner.last.load(Acquire);
         let new_write = inner.reserve.load(Acquire);
 
@@ -1015,18 +4535,154 @@ where
 
         // Allow subsequent grants
         inner.write_in_progress.store(false, Release);
-        inner.read_waker.wake();
+
+        inner.committed(used);
+    }
+
+    /// Records the bytes committed by a part of a split grant. Once all parts are
+    /// committed, returns the whole grant, and the number of bytes to commit.
+    #[cfg(feature = "split-grants")]
+    fn commit_part(&self, used: usize) -> Option<(NonNull<[u8]>, usize)> {
+        let inner = unsafe { self.bbq.as_ref() };
+
+        // The head ends where the tail starts
+        let (idx, whole, at) = match self.part {
+            GrantPart::Head { whole } => (0, whole, self.buf.len()),
+            GrantPart::Tail { whole } => (1, whole, whole.len() - self.buf.len()),
+            GrantPart::Whole => unreachable!(),
+        };
+
+        inner.split_used[idx].store(used, Release);
+        if atomic::fetch_sub(&inner.split_parts, 1, AcqRel) != 1 {
+            return None;
+        }
+
+        // The tail only follows a fully committed head, otherwise the
+        // committed bytes would not be contiguous
+        let head = inner.split_used[0].load(Acquire);
+        let tail = inner.split_used[1].load(Acquire);
+        let used = if head == at { at + tail } else { head };
+
+        Some((whole, used))
     }
 
     /// Configures the amount of bytes to be commited on drop.
     pub fn to_commit(&mut self, amt: usize) {
         self.to_commit = self.buf.len().min(amt);
     }
+
+    /// Configures the full grant to be commited on drop.
+    pub fn to_commit_all(&mut self) {
+        self.to_commit = self.buf.len();
+    }
+}
+
+impl<'a, B, I> SplitGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Finalizes a writable grant given by [Producer::grant_split_remaining]. This
+    /// makes the data available to be read. This consumes the grant.
+    ///
+    /// The first `used` bytes of both regions are committed, as one logical sequence.
+    /// If `used` is larger than the first region, the first region is committed up to
+    /// the end of the ring, and the write position wraps around into the second region.
+    ///
+    /// If `used` is larger than the given grant, the maximum amount will
+    /// be commited
+    ///
+    /// NOTE:  If the `thumbv6` feature is selected, this function takes a short critical
+    /// section while committing.
+    pub fn commit(mut self, used: usize) {
+        self.commit_inner(used);
+        forget(self);
+    }
+
+    /// Finalizes a writable grant, committing ALL of the bytes of both regions. This is
+    /// equivalent to `commit(grant.combined_len())`. This consumes the grant.
+    pub fn commit_all(self) {
+        let len = self.combined_len();
+        self.commit(len);
+    }
+
+    /// Obtain access to both inner buffers for writing
+    pub fn bufs_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        let buf1 = unsafe { from_raw_parts_mut(self.buf1.as_ptr() as *mut u8, self.buf1.len()) };
+        let buf2 = unsafe { from_raw_parts_mut(self.buf2.as_ptr() as *mut u8, self.buf2.len()) };
+        (buf1, buf2)
+    }
+
+    /// The combined length of both buffers
+    pub fn combined_len(&self) -> usize {
+        self.buf1.len() + self.buf2.len()
+    }
+
+    #[inline(always)]
+    pub(crate) fn commit_inner(&mut self, used: usize) {
+        let inner = unsafe { &self.bbq.as_ref() };
+
+        // If there is no grant in progress, return early. This
+        // generally means we are dropping the grant within a
+        // wrapper structure
+        if !inner.write_in_progress.load(Acquire) {
+            return;
+        }
+
+        // Writer component. Must never write to READ,
+        // be careful writing to LAST
+
+        // Saturate the grant commit
+        let used = min(self.combined_len(), used);
+        let len1 = self.buf1.len();
+        let (buf1, buf2) = self.bufs_mut();
+        inner.pre_commit(&buf1[..min(len1, used)]);
+        inner.pre_commit(&buf2[..used.saturating_sub(len1)]);
+
+        let write = inner.write.load(Acquire);
+        let max = inner.capacity();
+        let last = inner.last.load(Acquire);
+
+        let new_write = if used > len1 {
+            // The first region was filled up to the end of the ring, nothing is
+            // skipped. Last must be updated before wrapping the write pointer around
+            inner.last.store(max, Release);
+            used - len1
+        } else {
+            // Same as `GrantW`, we may pass a previously skipped end of the ring
+            let new_write = write + used;
+            if new_write > last {
+                inner.last.store(max, Release);
+            }
+            new_write
+        };
+
+        // Write must be updated AFTER last, otherwise read could think it was
+        // time to invert early!
+        inner.reserve.store(new_write, Release);
+        inner.write.store(new_write, Release);
+
+        // Allow subsequent grants
+        inner.write_in_progress.store(false, Release);
+
+        inner.committed(used);
+    }
+
+    /// Configures the amount of bytes to be commited on drop.
+    pub fn to_commit(&mut self, amt: usize) {
+        self.to_commit = self.combined_len().min(amt);
+    }
+
+    /// Configures both regions of the grant to be commited on drop.
+    pub fn to_commit_all(&mut self) {
+        self.to_commit = self.combined_len();
+    }
 }
 
-impl<'a, B> GrantR<'a, B>
+impl<'a, B, I> GrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Release a sequence of bytes from the buffer, allowing the space
     /// to be used by later writes. This consumes the grant.
@@ -1044,11 +4700,113 @@ where
         forget(self);
     }
 
+    /// Same as [Self::release], but `used` is checked instead of saturated. If `used`
+    /// is larger than the grant, nothing is released, and the grant is given back with
+    /// [Error::InsufficientSize].
+    pub fn try_release(self, used: usize) -> CoreResult<(), (Self, Error)> {
+        let available = self.buf.len();
+        if used > available {
+            return Err((
+                self,
+                Error::InsufficientSize {
+                    requested: used,
+                    available,
+                },
+            ));
+        }
+
+        self.release(used);
+        Ok(())
+    }
+
+    /// Abandons a readable grant without releasing any bytes. This consumes
+    /// the grant.
+    ///
+    /// This is equivalent to dropping the grant without calling `to_release()`,
+    /// and makes the intent explicit. The producer is not woken up.
+    pub fn abort(self) {
+        self.release(0);
+    }
+
+    /// Release ALL of the bytes of the grant from the buffer, allowing the space
+    /// to be used by later writes. This is equivalent to `release(grant.len())`.
+    /// This consumes the grant.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Commit four bytes, and release all of them
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// cons.read().unwrap().release_all();
+    ///
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn release_all(self) {
+        let len = self.buf.len();
+        self.release(len);
+    }
+
+    /// Configures the amount of bytes at the end of the grant that are kept by
+    /// [Self::release_remaining]. If `amt` is larger than the grant, the full grant
+    /// will be kept.
+    pub fn retain_tail(&mut self, amt: usize) {
+        self.retained = self.buf.len().min(amt);
+    }
+
+    /// Release all of the bytes of the grant, except for the tail configured with
+    /// [Self::retain_tail]. This consumes the grant.
+    ///
+    /// The retained bytes are not copied, they stay at the start of the next read
+    /// grant. This is useful to keep an incomplete record for the next parsing pass.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// prod.push_slice(b"OK\nA").unwrap();
+    ///
+    /// // Keep the unparsed trailing byte for the next read
+    /// let mut grant = cons.read().unwrap();
+    /// grant.retain_tail(1);
+    /// grant.release_remaining();
+    ///
+    /// prod.push_slice(b"T\n").unwrap();
+    /// assert_eq!(&*cons.read().unwrap(), b"AT\n");
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn release_remaining(self) {
+        let len = self.buf.len() - self.retained;
+        self.release(len);
+    }
+
     pub(crate) fn shrink(&mut self, len: usize) {
-        let mut new_buf: &mut [u8] = &mut [];
-        core::mem::swap(&mut self.buf_mut(), &mut new_buf);
-        let (new, _) = new_buf.split_at_mut(len);
+        let (new, _) = self.buf_mut().split_at_mut(len);
         self.buf = new.into();
+        self.retained = self.retained.min(len);
     }
 
     /// Obtain access to the inner buffer for reading
@@ -1056,10 +4814,10 @@ where
     /// ```
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, mut cons) = buffer.try_split().unwrap();
     ///
     /// // Successfully obtain and commit a grant of four bytes
@@ -1092,11 +4850,20 @@ where
         unsafe { from_raw_parts_mut(self.buf.as_ptr() as *mut u8, self.buf.len()) }
     }
 
+    /// Returns `true` if the grant holds the same bytes as `other`. This is the same as
+    /// comparing the grant with `other` using `==`, see [SplitGrantR::content_eq] for
+    /// grants which may wrap around the end of the ring.
+    pub fn content_eq(&self, other: &[u8]) -> bool {
+        self.buf() == other
+    }
+
     /// Sometimes, it's not possible for the lifetimes to check out. For example,
     /// if you need to hand this buffer to a function that expects to receive a
     /// `&'static [u8]`, it is not possible for the inner reference to outlive the
     /// grant itself.
     ///
+    /// # Safety
+    ///
     /// You MUST guarantee that in no cases, the reference that is returned here outlives
     /// the grant itself. Once the grant has been released, referencing the data contained
     /// WILL cause undefined behavior.
@@ -1107,6 +4874,16 @@ where
         transmute::<&[u8], &'static [u8]>(self.buf())
     }
 
+    /// Splits the grant into a raw pointer to its buffer, the length of the buffer, and
+    /// a [ReleaseToken] to release it later, see [GrantW::into_raw_parts].
+    ///
+    /// Dropping the token without releasing it is the same as dropping the grant.
+    pub fn into_raw_parts(self) -> (NonNull<u8>, usize, ReleaseToken<'a, B, I>) {
+        let ptr = self.buf.cast::<u8>();
+        let len = self.buf.len();
+        (ptr, len, ReleaseToken { grant: self })
+    }
+
     #[inline(always)]
     pub(crate) fn release_inner(&mut self, used: usize) {
         let inner = unsafe { &self.bbq.as_ref() };
@@ -1123,20 +4900,35 @@ where
 
         // This should be fine, purely incrementing
         let _ = atomic::fetch_add(&inner.read, used, Release);
+        inner.wrap_read();
 
         inner.read_in_progress.store(false, Release);
-        unsafe { self.bbq.as_ref().write_waker.wake() };
+
+        // Only wake the writer if some space was freed
+        if used != 0 {
+            #[cfg(feature = "metrics")]
+            atomic::fetch_add(&inner.counters.released, used, AcqRel);
+
+            #[cfg(feature = "async-api")]
+            inner.write_waker.wake();
+        }
     }
 
     /// Configures the amount of bytes to be released on drop.
     pub fn to_release(&mut self, amt: usize) {
         self.to_release = self.buf.len().min(amt);
     }
+
+    /// Configures the full grant to be released on drop.
+    pub fn to_release_all(&mut self) {
+        self.to_release = self.buf.len();
+    }
 }
 
-impl<'a, B> SplitGrantR<'a, B>
+impl<'a, B, I> SplitGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Release a sequence of bytes from the buffer, allowing the space
     /// to be used by later writes. This consumes the grant.
@@ -1154,15 +4946,53 @@ where
         forget(self);
     }
 
+    /// Release ALL of the bytes of both parts of the grant from the buffer, allowing
+    /// the space to be used by later writes. This is equivalent to
+    /// `release(grant.combined_len())`. This consumes the grant.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Fill the end of the ring, and wrap around to the start
+    /// prod.grant_exact(4).unwrap().commit(4);
+    /// cons.read().unwrap().release(4);
+    /// prod.grant_exact(2).unwrap().commit(2);
+    /// prod.grant_exact(3).unwrap().commit(3);
+    ///
+    /// // Release both parts at once
+    /// let grant = cons.split_read().unwrap();
+    /// assert_eq!(grant.combined_len(), 5);
+    /// grant.release_all();
+    ///
+    /// assert!(cons.read().is_err());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn release_all(self) {
+        let len = self.combined_len();
+        self.release(len);
+    }
+
     /// Obtain access to both inner buffers for reading
     ///
     /// ```
     /// # // bbqueue test shim!
     /// # fn bbqtest() {
-    /// use bbqueue::{BBQueue, StaticBufferProvider};
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
     ///
     /// // Create and split a new buffer of 6 elements
-    /// let mut buffer: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
     /// let (mut prod, mut cons) = buffer.try_split().unwrap();
     ///
     /// // Successfully obtain and commit a grant of four bytes
@@ -1170,11 +5000,135 @@ where
     /// grant.buf().copy_from_slice(&[1, 2, 3, 4]);
     /// grant.commit(4);
     ///
-    /// // Obtain a read grant, and copy to a buffer
-    /// let mut grant = cons.read().unwrap();
-    /// let mut buf = [0u8; 4];
-    /// buf.copy_from_slice(grant.buf());
-    /// assert_eq!(&buf, &[1, 2, 3, 4]);
+    /// // Obtain a read grant, and copy to a buffer
+    /// let mut grant = cons.read().unwrap();
+    /// let mut buf = [0u8; 4];
+    /// buf.copy_from_slice(grant.buf());
+    /// assert_eq!(&buf, &[1, 2, 3, 4]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn bufs(&self) -> (&[u8], &[u8]) {
+        let buf1 = unsafe { from_raw_parts(self.buf1.as_ptr() as *const u8, self.buf1.len()) };
+        let buf2 = unsafe { from_raw_parts(self.buf2.as_ptr() as *const u8, self.buf2.len()) };
+        (buf1, buf2)
+    }
+
+    /// Obtain mutable access to both parts of the read grant
+    ///
+    /// This is useful if you are performing in-place operations
+    /// on an incoming packet, such as decryption
+    pub fn bufs_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        let buf1 = unsafe { from_raw_parts_mut(self.buf1.as_ptr() as *mut u8, self.buf1.len()) };
+        let buf2 = unsafe { from_raw_parts_mut(self.buf2.as_ptr() as *mut u8, self.buf2.len()) };
+        (buf1, buf2)
+    }
+
+    /// Returns `true` if both parts of the grant, one after the other, hold the same
+    /// bytes as `other`, wherever the grant wraps around the end of the ring.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Fill the end of the ring, and wrap around to the start
+    /// prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+    /// cons.read().unwrap().release(3);
+    /// prod.push_slice_all(&[5, 6]).unwrap();
+    /// prod.push_slice_all(&[7, 8]).unwrap();
+    ///
+    /// let grant = cons.split_read().unwrap();
+    /// assert!(grant.content_eq(&[4, 5, 6, 7, 8]));
+    /// assert!(!grant.content_eq(&[4, 5, 6]));
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn content_eq(&self, other: &[u8]) -> bool {
+        let (buf1, buf2) = self.bufs();
+        other.len() == buf1.len() + buf2.len() && {
+            let (other1, other2) = other.split_at(buf1.len());
+            buf1 == other1 && buf2 == other2
+        }
+    }
+
+    /// Iterate over the bytes of both parts of the grant, as one logical sequence
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Fill the end of the ring, and wrap around to the start
+    /// prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+    /// cons.read().unwrap().release(3);
+    /// prod.push_slice_all(&[5, 6]).unwrap();
+    /// prod.push_slice_all(&[7, 8]).unwrap();
+    ///
+    /// let grant = cons.split_read().unwrap();
+    /// assert_eq!(grant.bufs(), (&[4, 5, 6][..], &[7, 8][..]));
+    /// assert!(grant.iter().eq(&[4, 5, 6, 7, 8]));
+    /// assert_eq!(grant.bytes().map(u32::from).sum::<u32>(), 30);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &u8> + '_ {
+        let (buf1, buf2) = self.bufs();
+        buf1.iter().chain(buf2.iter())
+    }
+
+    /// Copies the bytes of both parts of the grant into `out`, as one logical sequence.
+    /// Returns the number of bytes that were copied.
+    ///
+    /// If `out` is shorter than [Self::combined_len], only the prefix that fits is copied.
+    /// This does not release any bytes.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// // Create and split a new buffer of 6 elements
+    /// let mut buffer: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = buffer.try_split().unwrap();
+    ///
+    /// // Fill the end of the ring, and wrap around to the start
+    /// prod.push_slice_all(&[1, 2, 3, 4]).unwrap();
+    /// cons.read().unwrap().release(3);
+    /// prod.push_slice_all(&[5, 6]).unwrap();
+    /// prod.push_slice_all(&[7, 8]).unwrap();
+    ///
+    /// let grant = cons.split_read().unwrap();
+    /// let mut out = [0; 8];
+    /// assert_eq!(grant.copy_to_slice(&mut out), 5);
+    /// assert_eq!(&out[..5], &[4, 5, 6, 7, 8]);
+    ///
+    /// // Only the prefix that fits is copied
+    /// assert_eq!(grant.copy_to_slice(&mut out[..4]), 4);
+    /// assert_eq!(&out[..4], &[4, 5, 6, 7]);
     /// # // bbqueue test shim!
     /// # }
     /// #
@@ -1183,20 +5137,27 @@ where
     /// # bbqtest();
     /// # }
     /// ```
-    pub fn bufs(&self) -> (&[u8], &[u8]) {
-        let buf1 = unsafe { from_raw_parts(self.buf1.as_ptr() as *const u8, self.buf1.len()) };
-        let buf2 = unsafe { from_raw_parts(self.buf2.as_ptr() as *const u8, self.buf2.len()) };
-        (buf1, buf2)
+    pub fn copy_to_slice(&self, out: &mut [u8]) -> usize {
+        let (buf1, buf2) = self.bufs();
+
+        let len1 = min(buf1.len(), out.len());
+        out[..len1].copy_from_slice(&buf1[..len1]);
+
+        let len2 = min(buf2.len(), out.len() - len1);
+        out[len1..][..len2].copy_from_slice(&buf2[..len2]);
+
+        len1 + len2
     }
 
-    /// Obtain mutable access to both parts of the read grant
-    ///
-    /// This is useful if you are performing in-place operations
-    /// on an incoming packet, such as decryption
-    pub fn bufs_mut(&mut self) -> (&mut [u8], &mut [u8]) {
-        let buf1 = unsafe { from_raw_parts_mut(self.buf1.as_ptr() as *mut u8, self.buf1.len()) };
-        let buf2 = unsafe { from_raw_parts_mut(self.buf2.as_ptr() as *mut u8, self.buf2.len()) };
-        (buf1, buf2)
+    /// Iterate mutably over the bytes of both parts of the grant, as one logical sequence
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut u8> + '_ {
+        let (buf1, buf2) = self.bufs_mut();
+        buf1.iter_mut().chain(buf2.iter_mut())
+    }
+
+    /// Iterate over the bytes of both parts of the grant by value, as one logical sequence
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.iter().copied()
     }
 
     #[inline(always)]
@@ -1216,12 +5177,25 @@ where
         if used <= self.buf1.len() {
             // This should be fine, purely incrementing
             let _ = atomic::fetch_add(&inner.read, used, Release);
+            inner.wrap_read();
         } else {
-            // Also release parts of the second buffer
+            // Also release parts of the second buffer. Same as releasing the first
+            // buffer, wrapping around, then releasing the rest. `last` is only ever
+            // written by the writer, which moves it back to the end of the ring once
+            // it writes past it
             inner.read.store(used - self.buf1.len(), Release);
         }
 
         inner.read_in_progress.store(false, Release);
+
+        // Only wake the writer if some space was freed
+        if used != 0 {
+            #[cfg(feature = "metrics")]
+            atomic::fetch_add(&inner.counters.released, used, AcqRel);
+
+            #[cfg(feature = "async-api")]
+            inner.write_waker.wake();
+        }
     }
 
     /// Configures the amount of bytes to be released on drop.
@@ -1229,62 +5203,91 @@ where
         self.to_release = self.combined_len().min(amt);
     }
 
+    /// Configures both parts of the grant to be released on drop.
+    pub fn to_release_all(&mut self) {
+        self.to_release = self.combined_len();
+    }
+
     /// The combined length of both buffers
     pub fn combined_len(&self) -> usize {
         self.buf1.len() + self.buf2.len()
     }
+    pub(crate) fn shrink(&mut self, len: usize) {
+        let len1 = min(len, self.buf1.len());
+        let (buf1, buf2) = self.bufs_mut();
+        let (new1, new2) = ((&mut buf1[..len1]).into(), (&mut buf2[..len - len1]).into());
+        self.buf1 = new1;
+        self.buf2 = new2;
+    }
+}
+
+impl<'a, B, I> Drop for GrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        self.commit_inner(self.to_commit)
+    }
 }
 
-impl<'a, B> Drop for GrantW<'a, B>
+impl<'a, B, I> Drop for SplitGrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn drop(&mut self) {
         self.commit_inner(self.to_commit)
     }
 }
 
-impl<'a, B> Drop for GrantR<'a, B>
+impl<'a, B, I> Drop for GrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn drop(&mut self) {
         self.release_inner(self.to_release)
     }
 }
 
-impl<'a, B> Drop for SplitGrantR<'a, B>
+impl<'a, B, I> Drop for SplitGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn drop(&mut self) {
         self.release_inner(self.to_release)
     }
 }
 
-impl<'a, B> Deref for GrantW<'a, B>
+impl<'a, B, I> Deref for GrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        unsafe { from_raw_parts_mut(self.buf.as_ptr() as *mut u8, self.buf.len()) }
+        self.check_not_lent();
+        unsafe { from_raw_parts(self.buf.as_ptr() as *const u8, self.buf.len()) }
     }
 }
 
-impl<'a, B> DerefMut for GrantW<'a, B>
+impl<'a, B, I> DerefMut for GrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn deref_mut(&mut self) -> &mut [u8] {
         self.buf()
     }
 }
 
-impl<'a, B> Deref for GrantR<'a, B>
+impl<'a, B, I> Deref for GrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     type Target = [u8];
 
@@ -1293,51 +5296,94 @@ where
     }
 }
 
-impl<'a, B> DerefMut for GrantR<'a, B>
+impl<'a, B, I> DerefMut for GrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn deref_mut(&mut self) -> &mut [u8] {
         self.buf_mut()
     }
 }
 
+impl<'a, B, I> PartialEq<[u8]> for GrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn eq(&self, other: &[u8]) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl<'a, B, I> PartialEq<&[u8]> for GrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl<'a, B, I> PartialEq<[u8]> for SplitGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn eq(&self, other: &[u8]) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl<'a, B, I> PartialEq<&[u8]> for SplitGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.content_eq(other)
+    }
+}
+
 /// Future returned [Producer::grant_exact_async]
-pub struct GrantExactFuture<'a, 'b, B>
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantExactFuture<'a, 'b, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    prod: &'b mut Producer<'a, B>,
+    prod: &'b mut Producer<'a, B, I>,
     sz: usize,
 }
 
-impl<'a, 'b, B> Future for GrantExactFuture<'a, 'b, B>
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantExactFuture<'a, 'b, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    type Output = Result<GrantW<'a, B>>;
+    type Output = Result<GrantW<'a, B, I>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        // Check if it's event  possible to get the requested size
-        // Ex:
-        // [0|1|2|3|4|5|6|7|8]
-        //              ^
-        //              Write pointer
-        // Check if the buffer from 6 to 8 satisfies or if the buffer from 0 to 5 does.
-        // If so, create the future, if not, we need the return since the future will never resolve.
-        // Ideally, we could just wait for all the read to complete and reset the read and write to 0, but that is currently not supported
-        let max = unsafe { self.prod.bbq.as_ref().capacity() };
-        let write = unsafe { self.prod.bbq.as_ref().write.load(Acquire) };
-        if self.sz > max || (self.sz > max - write && self.sz >= write) {
-            return Poll::Ready(Err(Error::InsufficientSize));
-        }
-
         let sz = self.sz;
 
-        match self.prod.grant_exact(sz) {
+        let mut res = self.prod.grant_exact(sz);
+        if let Err(Error::InsufficientSize { .. }) = res {
+            // The request may not fit around the current write position, even once
+            // everything is released. Start over from the beginning of an empty ring
+            if self.prod.rewind_if_empty() {
+                res = self.prod.grant_exact(sz);
+            }
+        }
+
+        match res {
             Ok(grant) => Poll::Ready(Ok(grant)),
             Err(e) => match e {
-                Error::GrantInProgress | Error::InsufficientSize => {
+                Error::GrantInProgress | Error::InsufficientSize { .. } => {
                     unsafe { self.prod.bbq.as_ref().write_waker.register(cx.waker()) };
                     Poll::Pending
                 }
@@ -1347,28 +5393,49 @@ where
     }
 }
 
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantExactFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Forget the waker, so a later release doesn't wake a task which stopped
+        // waiting. The future borrows the producer, so no other future may be registered
+        drop(unsafe { self.prod.bbq.as_ref() }.write_waker.take());
+    }
+}
+
 /// Future returned [Producer::grant_max_remaining_async]
-pub struct GrantMaxRemainingFuture<'a, 'b, B>
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantMaxRemainingFuture<'a, 'b, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    prod: &'b mut Producer<'a, B>,
+    prod: &'b mut Producer<'a, B, I>,
     sz: usize,
+    pub(crate) unit: usize,
 }
 
-impl<'a, 'b, B> Future for GrantMaxRemainingFuture<'a, 'b, B>
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantMaxRemainingFuture<'a, 'b, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    type Output = Result<GrantW<'a, B>>;
+    type Output = Result<GrantW<'a, B, I>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let sz = self.sz;
+        let (sz, unit) = (self.sz, self.unit);
 
-        match self.prod.grant_max_remaining(sz) {
+        match self.prod.grant_max_remaining_inner(sz, unit) {
             Ok(grant) => Poll::Ready(Ok(grant)),
             Err(e) => match e {
-                Error::GrantInProgress | Error::InsufficientSize => {
+                Error::GrantInProgress | Error::InsufficientSize { .. } => {
                     unsafe { self.prod.bbq.as_ref().write_waker.register(cx.waker()) };
                     Poll::Pending
                 }
@@ -1378,25 +5445,353 @@ where
     }
 }
 
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantMaxRemainingFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantExactFuture`
+        drop(unsafe { self.prod.bbq.as_ref() }.write_waker.take());
+    }
+}
+
 /// Future returned [Consumer::read_async]
-pub struct GrantReadFuture<'a, 'b, B>
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantReadFuture<'a, 'b, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    cons: &'b mut Consumer<'a, B>,
+    cons: &'b mut Consumer<'a, B, I>,
 }
 
-impl<'a, 'b, B> Future for GrantReadFuture<'a, 'b, B>
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantReadFuture<'a, 'b, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    type Output = Result<GrantR<'a, B>>;
+    type Output = Result<GrantR<'a, B, I>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Before reading, so the bytes committed right before closing aren't missed
+        let closed = self.cons.is_closed();
+
         match self.cons.read() {
             Ok(grant) => Poll::Ready(Ok(grant)),
             Err(e) => match e {
-                Error::InsufficientSize | Error::GrantInProgress => {
+                Error::InsufficientSize { .. } => self.cons.wait_for_commit(closed, cx),
+                Error::GrantInProgress => {
+                    unsafe { self.cons.bbq.as_ref().read_waker.register(cx.waker()) };
+                    Poll::Pending
+                }
+                _ => Poll::Ready(Err(e)),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantReadFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Forget the waker, so a later commit doesn't wake a task which stopped
+        // waiting. The future borrows the consumer, so no other future may be registered
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
+/// Future returned [Consumer::wait_for_occupied]
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitForOccupiedFuture<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    cons: &'b mut Consumer<'a, B, I>,
+    sz: usize,
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for WaitForOccupiedFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = unsafe { self.cons.bbq.as_ref() };
+        if self.sz > inner.capacity() {
+            return Poll::Ready(Err(Error::RequestTooLarge));
+        }
+
+        // Register before checking, so a commit in between is not missed
+        inner.read_waker.register(cx.waker());
+        let closed = inner.closed.load(Acquire);
+
+        if inner.occupied_len() >= self.sz {
+            Poll::Ready(Ok(()))
+        } else if closed {
+            Poll::Ready(Err(Error::Closed))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for WaitForOccupiedFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantReadFuture`
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
+/// Future returned [Consumer::read_exact_async]
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantReadExactFuture<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    cons: &'b mut Consumer<'a, B, I>,
+    sz: usize,
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantReadExactFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Output = Result<GrantR<'a, B, I>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sz = self.sz;
+        let max = unsafe { self.cons.bbq.as_ref().capacity() };
+        if sz > max {
+            return Poll::Ready(Err(Error::RequestTooLarge));
+        }
+
+        // Same as `GrantReadFuture`
+        let closed = self.cons.is_closed();
+
+        match self.cons.read() {
+            Ok(mut grant) if grant.len() >= sz => {
+                grant.shrink(sz);
+                Poll::Ready(Ok(grant))
+            }
+            Ok(grant) => {
+                // Not enough contiguous bytes yet. We hold the read grant, so the read
+                // pointer is stable. If the writer has wrapped around, the bytes up to
+                // `last` will never grow. Otherwise, the writer will wrap around before
+                // filling `sz` bytes if they don't fit before the end of the ring.
+                let inner = unsafe { self.cons.bbq.as_ref() };
+                let read = inner.read.load(Acquire);
+                let write = inner.write.load(Acquire);
+                let available = grant.len();
+
+                // Dropping the grant releases nothing
+                drop(grant);
+
+                if write < read || read + sz > max {
+                    return Poll::Ready(Err(Error::InsufficientSize {
+                        requested: sz,
+                        available,
+                    }));
+                }
+
+                self.cons.wait_for_commit(closed, cx)
+            }
+            Err(e) => match e {
+                Error::InsufficientSize { .. } => self.cons.wait_for_commit(closed, cx),
+                Error::GrantInProgress => {
+                    unsafe { self.cons.bbq.as_ref().read_waker.register(cx.waker()) };
+                    Poll::Pending
+                }
+                _ => Poll::Ready(Err(e)),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantReadExactFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantReadFuture`
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
+/// Future returned [Consumer::read_until_async]
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantReadUntilFuture<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    cons: &'b mut Consumer<'a, B, I>,
+    delim: u8,
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantReadUntilFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Output = Result<GrantR<'a, B, I>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let delim = self.delim;
+
+        // Same as `GrantReadFuture`
+        let closed = self.cons.is_closed();
+
+        match self.cons.read() {
+            Ok(mut grant) => match grant.iter().position(|&b| b == delim) {
+                Some(pos) => {
+                    grant.shrink(pos + 1);
+                    Poll::Ready(Ok(grant))
+                }
+                None => {
+                    // We hold the read grant, so the read pointer is stable. If the writer
+                    // has wrapped around, the bytes up to `last` will never grow.
+                    let inner = unsafe { self.cons.bbq.as_ref() };
+                    let read = inner.read.load(Acquire);
+                    let write = inner.write.load(Acquire);
+                    let available = grant.len();
+
+                    // Dropping the grant releases nothing
+                    drop(grant);
+
+                    if write < read {
+                        return Poll::Ready(Err(Error::InsufficientSize {
+                            requested: available + 1,
+                            available,
+                        }));
+                    }
+
+                    self.cons.wait_for_commit(closed, cx)
+                }
+            },
+            Err(e) => match e {
+                Error::InsufficientSize { .. } => self.cons.wait_for_commit(closed, cx),
+                Error::GrantInProgress => {
+                    unsafe { self.cons.bbq.as_ref().read_waker.register(cx.waker()) };
+                    Poll::Pending
+                }
+                _ => Poll::Ready(Err(e)),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantReadUntilFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantReadFuture`
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
+/// Future returned [Consumer::split_read_exact_async]
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantSplitReadExactFuture<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    cons: &'b mut Consumer<'a, B, I>,
+    sz: usize,
+}
+
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantSplitReadExactFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Output = Result<SplitGrantR<'a, B, I>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sz = self.sz;
+        let max = unsafe { self.cons.bbq.as_ref().capacity() };
+        if sz > max {
+            return Poll::Ready(Err(Error::RequestTooLarge));
+        }
+
+        // Same as `GrantReadFuture`
+        let closed = self.cons.is_closed();
+
+        match self.cons.split_read() {
+            Ok(mut grant) if grant.combined_len() >= sz => {
+                grant.shrink(sz);
+                Poll::Ready(Ok(grant))
+            }
+            Ok(grant) => {
+                // Not enough bytes yet. We hold the read grant, so the read pointer is
+                // stable. The writer must stay strictly behind it once wrapped around,
+                // so unless reading from the start of the ring, at most one byte less
+                // than the end of the readable region can ever be available.
+                let inner = unsafe { self.cons.bbq.as_ref() };
+                let read = inner.read.load(Acquire);
+                let write = inner.write.load(Acquire);
+                let last = inner.last.load(Acquire);
+                let available = grant.combined_len();
+
+                // Dropping the grant releases nothing
+                drop(grant);
+
+                let possible = if read == 0 {
+                    max
+                } else if write < read {
+                    last - 1
+                } else {
+                    max - 1
+                };
+
+                if sz > possible {
+                    return Poll::Ready(Err(Error::InsufficientSize {
+                        requested: sz,
+                        available,
+                    }));
+                }
+
+                self.cons.wait_for_commit(closed, cx)
+            }
+            Err(e) => match e {
+                Error::InsufficientSize { .. } => self.cons.wait_for_commit(closed, cx),
+                Error::GrantInProgress => {
                     unsafe { self.cons.bbq.as_ref().read_waker.register(cx.waker()) };
                     Poll::Pending
                 }
@@ -1406,25 +5801,48 @@ where
     }
 }
 
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantSplitReadExactFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantReadFuture`
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
 /// Future returned [Consumer::split_read_async]
-pub struct GrantSplitReadFuture<'a, 'b, B>
+///
+/// This future is cancellation safe, see the [crate level documentation](crate#async).
+#[cfg(feature = "async-api")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GrantSplitReadFuture<'a, 'b, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    cons: &'b mut Consumer<'a, B>,
+    cons: &'b mut Consumer<'a, B, I>,
 }
 
-impl<'a, 'b, B> Future for GrantSplitReadFuture<'a, 'b, B>
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Future for GrantSplitReadFuture<'a, 'b, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    type Output = Result<SplitGrantR<'a, B>>;
+    type Output = Result<SplitGrantR<'a, B, I>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Same as `GrantReadFuture`
+        let closed = self.cons.is_closed();
+
         match self.cons.split_read() {
             Ok(grant) => Poll::Ready(Ok(grant)),
             Err(e) => match e {
-                Error::InsufficientSize | Error::GrantInProgress => {
+                Error::InsufficientSize { .. } => self.cons.wait_for_commit(closed, cx),
+                Error::GrantInProgress => {
                     unsafe { self.cons.bbq.as_ref().read_waker.register(cx.waker()) };
                     Poll::Pending
                 }
@@ -1434,16 +5852,43 @@ where
     }
 }
 
-#[cfg(feature = "thumbv6")]
-mod atomic {
-    use core::sync::atomic::{
-        AtomicBool, AtomicUsize,
-        Ordering::{self, Acquire, Release},
-    };
-    use cortex_m::interrupt::free;
+#[cfg(feature = "async-api")]
+impl<'a, 'b, B, I> Drop for GrantSplitReadFuture<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        // Same as `GrantReadFuture`
+        drop(unsafe { self.cons.bbq.as_ref() }.read_waker.take());
+    }
+}
+
+/// Round `val` down to a multiple of `unit`
+#[inline(always)]
+fn round_down(val: usize, unit: usize) -> usize {
+    val - (val % unit)
+}
+
+/// Replace the requested size of an [Error::InsufficientSize] with `sz`
+fn requested(err: Error, sz: usize) -> Error {
+    match err {
+        Error::InsufficientSize { available, .. } => Error::InsufficientSize {
+            requested: sz,
+            available,
+        },
+        err => err,
+    }
+}
+
+#[cfg(feature = "portable-cs")]
+pub(crate) mod atomic {
+    use crate::{index::private::AtomicIndex, sync::AtomicFlag};
+    use core::sync::atomic::Ordering::{self, Acquire, Release};
+    use critical_section::with as free;
 
     #[inline(always)]
-    pub fn fetch_add(atomic: &AtomicUsize, val: usize, _order: Ordering) -> usize {
+    pub fn fetch_add<A: AtomicIndex>(atomic: &A, val: usize, _order: Ordering) -> usize {
         free(|_| {
             let prev = atomic.load(Acquire);
             atomic.store(prev.wrapping_add(val), Release);
@@ -1452,7 +5897,7 @@ mod atomic {
     }
 
     #[inline(always)]
-    pub fn fetch_sub(atomic: &AtomicUsize, val: usize, _order: Ordering) -> usize {
+    pub fn fetch_sub<A: AtomicIndex>(atomic: &A, val: usize, _order: Ordering) -> usize {
         free(|_| {
             let prev = atomic.load(Acquire);
             atomic.store(prev.wrapping_sub(val), Release);
@@ -1461,7 +5906,26 @@ mod atomic {
     }
 
     #[inline(always)]
-    pub fn swap(atomic: &AtomicBool, val: bool, _order: Ordering) -> bool {
+    pub fn compare_exchange<A: AtomicIndex>(
+        atomic: &A,
+        current: usize,
+        new: usize,
+        _success: Ordering,
+        _failure: Ordering,
+    ) -> Result<usize, usize> {
+        free(|_| {
+            let prev = atomic.load(Acquire);
+            if prev == current {
+                atomic.store(new, Release);
+                Ok(prev)
+            } else {
+                Err(prev)
+            }
+        })
+    }
+
+    #[inline(always)]
+    pub fn swap<A: AtomicFlag>(atomic: &A, val: bool, _order: Ordering) -> bool {
         free(|_| {
             let prev = atomic.load(Acquire);
             atomic.store(val, Release);
@@ -1470,22 +5934,34 @@ mod atomic {
     }
 }
 
-#[cfg(not(feature = "thumbv6"))]
-mod atomic {
-    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(feature = "portable-cs"))]
+pub(crate) mod atomic {
+    use crate::{index::private::AtomicIndex, sync::AtomicFlag};
+    use core::sync::atomic::Ordering;
 
     #[inline(always)]
-    pub fn fetch_add(atomic: &AtomicUsize, val: usize, order: Ordering) -> usize {
+    pub fn fetch_add<A: AtomicIndex>(atomic: &A, val: usize, order: Ordering) -> usize {
         atomic.fetch_add(val, order)
     }
 
     #[inline(always)]
-    pub fn fetch_sub(atomic: &AtomicUsize, val: usize, order: Ordering) -> usize {
+    pub fn fetch_sub<A: AtomicIndex>(atomic: &A, val: usize, order: Ordering) -> usize {
         atomic.fetch_sub(val, order)
     }
 
     #[inline(always)]
-    pub fn swap(atomic: &AtomicBool, val: bool, order: Ordering) -> bool {
+    pub fn compare_exchange<A: AtomicIndex>(
+        atomic: &A,
+        current: usize,
+        new: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<usize, usize> {
+        atomic.compare_exchange(current, new, success, failure)
+    }
+
+    #[inline(always)]
+    pub fn swap<A: AtomicFlag>(atomic: &A, val: bool, order: Ordering) -> bool {
         atomic.swap(val, order)
     }
 }
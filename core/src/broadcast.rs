@@ -0,0 +1,472 @@
+//! A Broadcast flavor of BBQueue, allowing multiple independent consumers
+//!
+//! This module provides a [BroadcastBBQueue], which may be split into a single
+//! [Producer] and `C` [BroadcastConsumer]s. Every consumer observes the exact
+//! same stream of bytes, and keeps track of its own read position. This is
+//! useful when the same data must be drained by multiple sinks, for example
+//! a log buffer that is sent over both USB and RTT.
+//!
+//! Space in the queue is only reclaimed once ALL consumers have released past
+//! a given byte. When the slowest consumer falls too far behind, the producer
+//! will fail to obtain a grant with [Error::InsufficientSize], rather than
+//! overwriting data that has not yet been seen by every consumer.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::{broadcast::BroadcastBBQueue, StaticStorageProvider};
+//!
+//! // Create a queue with two consumers
+//! let bb: BroadcastBBQueue<StaticStorageProvider<8>, 2> = BroadcastBBQueue::new_static();
+//! let (mut prod, [mut cons_a, mut cons_b]) = bb.try_split_broadcast().unwrap();
+//!
+//! let mut wgrant = prod.grant_exact(6).unwrap();
+//! wgrant.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+//! wgrant.commit(6);
+//!
+//! // The first consumer sees the data...
+//! let rgrant = cons_a.read().unwrap();
+//! assert_eq!(&*rgrant, &[1, 2, 3, 4, 5, 6]);
+//! rgrant.release(6);
+//!
+//! // ...but the space is not reclaimed until the second consumer is done
+//! assert!(prod.grant_exact(4).is_err());
+//!
+//! let rgrant = cons_b.read().unwrap();
+//! assert_eq!(&*rgrant, &[1, 2, 3, 4, 5, 6]);
+//! rgrant.release(6);
+//!
+//! assert!(prod.grant_exact(4).is_ok());
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
+//!
+//! ## Limitations
+//!
+//! As the data is shared between all consumers, read grants only provide
+//! immutable access to the data. Consumers do not currently support async
+//! reads, though the producer may still use the async grant methods.
+
+#[cfg(feature = "async-api")]
+use crate::waker::WakerCell;
+use crate::{
+    bbqueue::atomic,
+    sync::{AtomicBool, AtomicUsize},
+    BBQueue, Consumer, Error, Producer, Result, SliceStorageProvider, StaticStorageProvider,
+    StorageProvider,
+};
+use core::{
+    array,
+    cmp::min,
+    marker::PhantomData,
+    mem::forget,
+    ops::Deref,
+    ptr::NonNull,
+    result::Result as CoreResult,
+    slice::from_raw_parts,
+    sync::atomic::Ordering::{AcqRel, Acquire, Release},
+};
+
+/// A backing structure for a BBQueue with `C` independent consumers.
+/// Can be split into a [Producer] and `C` [BroadcastConsumer]s
+#[derive(Debug)]
+pub struct BroadcastBBQueue<B, const C: usize>
+where
+    B: StorageProvider,
+{
+    // The underlying queue. Its `read` pointer is maintained as the
+    // position of the slowest consumer, so that the producer will
+    // never write over unread data
+    bbq: BBQueue<B>,
+
+    // Where the next byte will be read from, for each consumer
+    cursors: [AtomicUsize; C],
+
+    // Is there an active read grant, for each consumer?
+    read_in_progress: [AtomicBool; C],
+
+    // Is a consumer currently updating the shared read pointer?
+    reclaiming: AtomicBool,
+
+    // Has any cursor moved since the shared read pointer was last updated?
+    reclaim_pending: AtomicBool,
+}
+
+impl<B, const C: usize> BroadcastBBQueue<B, C>
+where
+    B: StorageProvider,
+{
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const CURSOR: AtomicUsize = AtomicUsize::new(0);
+
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NOT_READING: AtomicBool = AtomicBool::new(false);
+
+    /// Create a new BroadcastBBQueue with abstraction over the memory provider
+    pub fn new(buf: B) -> Self {
+        Self {
+            bbq: BBQueue::new(buf),
+            cursors: array::from_fn(|_| AtomicUsize::new(0)),
+            read_in_progress: array::from_fn(|_| AtomicBool::new(false)),
+            reclaiming: AtomicBool::new(false),
+            reclaim_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the size of the backing storage.
+    ///
+    /// See [BBQueue::capacity] for details.
+    pub const fn capacity(&self) -> usize {
+        self.bbq.capacity()
+    }
+}
+
+impl<const N: usize, const C: usize> BroadcastBBQueue<StaticStorageProvider<N>, C> {
+    /// Create a new constant static BroadcastBBQueue, using static memory allocation
+    #[cfg(not(feature = "loom"))]
+    pub const fn new_static() -> Self {
+        Self {
+            bbq: BBQueue::new_static(),
+            cursors: [Self::CURSOR; C],
+            read_in_progress: [Self::NOT_READING; C],
+            reclaiming: AtomicBool::new(false),
+            reclaim_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a new BroadcastBBQueue, using static memory allocation
+    #[cfg(feature = "loom")]
+    pub fn new_static() -> Self {
+        Self::new(StaticStorageProvider::new())
+    }
+}
+
+impl<'a, const C: usize> BroadcastBBQueue<SliceStorageProvider<'a>, C> {
+    /// Create a new BroadcastBBQueue using userspace provided memory in the form of a slice.
+    pub fn new_from_slice(buf: &'a mut [u8]) -> Self {
+        Self::new(SliceStorageProvider::new(buf))
+    }
+}
+
+impl<'a, B, const C: usize> BroadcastBBQueue<B, C>
+where
+    B: StorageProvider,
+{
+    /// Attempt to split the `BroadcastBBQueue` into a `Producer` and `C`
+    /// `BroadcastConsumer`s to gain access to the buffer. If buffer has already
+    /// been split, an error will be returned.
+    ///
+    /// See [BBQueue::try_split] for details on initialization of the buffer.
+    pub fn try_split_broadcast(
+        &'a self,
+    ) -> Result<(Producer<'a, B>, [BroadcastConsumer<'a, B, C>; C])> {
+        // The single consumer is never used, all reads are
+        // performed through the broadcast consumers
+        let (producer, _) = self.bbq.try_split()?;
+
+        // Hold its read flag, so the producer never takes the role of the reader to
+        // move the shared read pointer, which would leave the cursors behind
+        self.bbq.read_in_progress.store(true, Release);
+
+        // Consumers may have been left anywhere by a previous split
+        for cursor in self.cursors.iter() {
+            cursor.store(0, Release);
+        }
+
+        let consumers = array::from_fn(|idx| BroadcastConsumer {
+            bbq: NonNull::from(self),
+            idx,
+            pd: PhantomData,
+        });
+
+        Ok((producer, consumers))
+    }
+
+    /// Attempt to release the Producer and all BroadcastConsumers
+    ///
+    /// This re-initializes the buffer so it may be split in a different mode at a later
+    /// time. There must be no read or write grants active, and every consumer must have
+    /// released all the committed bytes, or an error will be returned.
+    ///
+    /// The `Producer` and `BroadcastConsumer`s must be from THIS `BroadcastBBQueue`, or
+    /// an error will be returned.
+    #[allow(clippy::type_complexity)]
+    pub fn try_release_broadcast(
+        &'a self,
+        prod: Producer<'a, B>,
+        cons: [BroadcastConsumer<'a, B, C>; C],
+    ) -> CoreResult<(), (Producer<'a, B>, [BroadcastConsumer<'a, B, C>; C])> {
+        // Are these our consumers? Each consumer must also be present exactly once
+        let ours = cons
+            .iter()
+            .enumerate()
+            .all(|(idx, c)| c.bbq.as_ptr() as *const Self == self && c.idx == idx);
+        let rd_in_progress = self.read_in_progress.iter().any(|r| r.load(Acquire));
+
+        if !ours || rd_in_progress {
+            return Err((prod, cons));
+        }
+
+        let consumer = Consumer {
+            bbq: NonNull::from(&self.bbq),
+            pd: PhantomData,
+            reborrowed: false,
+        };
+
+        self.bbq.read_in_progress.store(false, Release);
+        if let Err((prod, _)) = self.bbq.try_release(prod, consumer) {
+            self.bbq.read_in_progress.store(true, Release);
+            return Err((prod, cons));
+        }
+
+        // Drop the consumers
+        let _ = cons;
+
+        Ok(())
+    }
+
+    /// Move the shared read pointer to the position of the slowest consumer,
+    /// releasing the space that has been read by all consumers.
+    fn reclaim(&self) {
+        // Flag the change with a swap, so it is ordered with the swaps of the
+        // consumer holding the lock, and that consumer synchronizes with us
+        atomic::swap(&self.reclaim_pending, true, AcqRel);
+
+        loop {
+            if atomic::swap(&self.reclaiming, true, AcqRel) {
+                // Someone else is currently updating the read pointer, and
+                // will pick up our change before they are done
+                return;
+            }
+
+            while atomic::swap(&self.reclaim_pending, false, AcqRel) {
+                // The shared read pointer is only moved here, and is behind all the
+                // cursors. The write pointer can't be used instead, as a cursor may
+                // wrap around and pass a stale write pointer while we are looking
+                let read = self.bbq.read.load(Acquire);
+
+                // Cursors at or past the read pointer have not yet wrapped around,
+                // and are therefore behind all cursors that have
+                let mut wrapped = usize::MAX;
+                let mut not_wrapped = usize::MAX;
+                for cursor in self.cursors.iter() {
+                    let cursor = cursor.load(Acquire);
+                    if cursor >= read {
+                        not_wrapped = min(not_wrapped, cursor);
+                    } else {
+                        wrapped = min(wrapped, cursor);
+                    }
+                }
+
+                let slowest = if not_wrapped != usize::MAX {
+                    not_wrapped
+                } else {
+                    wrapped
+                };
+
+                self.bbq.read.store(slowest, Release);
+            }
+
+            // Release the lock with a swap, which synchronizes with the swap of a
+            // consumer that found it taken, so its pending flag is seen below
+            atomic::swap(&self.reclaiming, false, AcqRel);
+
+            // Check whether a cursor moved while we were releasing the lock
+            if !self.reclaim_pending.load(Acquire) {
+                break;
+            }
+        }
+
+        #[cfg(feature = "async-api")]
+        self.bbq.write_waker.wake();
+    }
+}
+
+/// `BroadcastConsumer` is one of the `C` independent readers of a `BroadcastBBQueue`.
+pub struct BroadcastConsumer<'a, B, const C: usize>
+where
+    B: StorageProvider,
+{
+    bbq: NonNull<BroadcastBBQueue<B, C>>,
+    idx: usize,
+    pd: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, B, const C: usize> Send for BroadcastConsumer<'a, B, C> where B: StorageProvider {}
+
+impl<'a, B, const C: usize> BroadcastConsumer<'a, B, C>
+where
+    B: StorageProvider,
+{
+    /// Obtains a contiguous slice of committed bytes, that have not yet been
+    /// released by THIS consumer. This slice may not contain ALL available bytes,
+    /// if the writer has wrapped around. The remaining bytes will be available
+    /// after all readable bytes are released
+    pub fn read(&mut self) -> Result<BroadcastGrantR<'a, B, C>> {
+        let bq = unsafe { self.bbq.as_ref() };
+        let inner = &bq.bbq;
+        let cursor = &bq.cursors[self.idx];
+
+        if atomic::swap(&bq.read_in_progress[self.idx], true, AcqRel) {
+            return Err(Error::GrantInProgress);
+        }
+
+        let write = inner.write.load(Acquire);
+        let last = inner.last.load(Acquire);
+        let mut read = cursor.load(Acquire);
+
+        // Resolve the inverted case or end of read
+        if (read == last) && (write < read) {
+            read = 0;
+            // Only this consumer's cursor is moved backwards. The writer is
+            // still bound by the shared read pointer, until it is reclaimed
+            cursor.store(0, Release);
+            bq.reclaim();
+        }
+
+        let sz = if write < read {
+            // Inverted, only believe last
+            last
+        } else {
+            // Not inverted, only believe write
+            write
+        } - read;
+
+        if sz == 0 {
+            bq.read_in_progress[self.idx].store(false, Release);
+            return Err(Error::InsufficientSize {
+                requested: 1,
+                available: 0,
+            });
+        }
+
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (*inner.buf.get()).storage().as_ptr() as *mut u8 };
+        let grant_slice = unsafe { from_raw_parts(start_of_buf_ptr.add(read), sz) };
+
+        Ok(BroadcastGrantR {
+            buf: grant_slice.into(),
+            bbq: self.bbq,
+            idx: self.idx,
+            to_release: 0,
+            phatom: PhantomData,
+        })
+    }
+
+    /// Returns the number of bytes that have been committed, but not yet
+    /// released by THIS consumer.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the queue
+    /// is in use concurrently, the value may already be outdated when it is returned.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        let bq = unsafe { self.bbq.as_ref() };
+        let write = bq.bbq.write.load(Acquire);
+        let read = bq.cursors[self.idx].load(Acquire);
+
+        if write < read {
+            let last = bq.bbq.last.load(Acquire);
+            last.saturating_sub(read) + write
+        } else {
+            write - read
+        }
+    }
+}
+
+/// A structure representing a contiguous region of memory that
+/// may be read from, and potentially "released" by one consumer
+/// of a `BroadcastBBQueue`
+///
+/// NOTE: If the grant is dropped without explicitly releasing
+/// the contents, or by setting the number of bytes to automatically
+/// be released with `to_release()`, then no bytes will be released
+/// as read.
+#[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+#[derive(Debug, PartialEq)]
+pub struct BroadcastGrantR<'a, B, const C: usize>
+where
+    B: StorageProvider,
+{
+    buf: NonNull<[u8]>,
+    bbq: NonNull<BroadcastBBQueue<B, C>>,
+    idx: usize,
+    to_release: usize,
+    phatom: PhantomData<&'a [u8]>,
+}
+
+unsafe impl<'a, B, const C: usize> Send for BroadcastGrantR<'a, B, C> where B: StorageProvider {}
+
+impl<'a, B, const C: usize> BroadcastGrantR<'a, B, C>
+where
+    B: StorageProvider,
+{
+    /// Release a sequence of bytes for THIS consumer. The space will be available
+    /// to later writes once all consumers have released it. This consumes the grant.
+    ///
+    /// If `used` is larger than the given grant, the full grant will
+    /// be released.
+    pub fn release(mut self, used: usize) {
+        // Saturate the grant release
+        let used = min(self.buf.len(), used);
+
+        self.release_inner(used);
+        forget(self);
+    }
+
+    /// Obtain access to the inner buffer for reading
+    pub fn buf(&self) -> &[u8] {
+        unsafe { from_raw_parts(self.buf.as_ptr() as *const u8, self.buf.len()) }
+    }
+
+    /// Configures the amount of bytes to be released on drop.
+    pub fn to_release(&mut self, amt: usize) {
+        self.to_release = self.buf.len().min(amt);
+    }
+
+    #[inline(always)]
+    fn release_inner(&mut self, used: usize) {
+        let bq = unsafe { self.bbq.as_ref() };
+
+        // This should always be checked by the public interfaces
+        debug_assert!(used <= self.buf.len());
+
+        // This should be fine, purely incrementing
+        let _ = atomic::fetch_add(&bq.cursors[self.idx], used, Release);
+
+        bq.read_in_progress[self.idx].store(false, Release);
+
+        if used != 0 {
+            bq.reclaim();
+        }
+    }
+}
+
+impl<'a, B, const C: usize> Drop for BroadcastGrantR<'a, B, C>
+where
+    B: StorageProvider,
+{
+    fn drop(&mut self) {
+        self.release_inner(self.to_release)
+    }
+}
+
+impl<'a, B, const C: usize> Deref for BroadcastGrantR<'a, B, C>
+where
+    B: StorageProvider,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.buf()
+    }
+}
@@ -0,0 +1,199 @@
+//! Implementations of the `embedded-io` traits, enabled with the `embedded-io` feature,
+//! and of the `embedded-io-async` traits, enabled with the `embedded-io-async` feature
+
+use crate::{BbqIndex, Consumer, Error, Producer, StorageProvider};
+use embedded_io::{ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+
+/// Error type used by the `embedded-io` trait implementations
+///
+/// The queue never blocks, instead [IoError::Full] is returned when writing
+/// to a full queue, and [IoError::WouldBlock] when reading from an empty queue.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum IoError {
+    /// The queue is currently empty, the read should be retried later
+    WouldBlock,
+
+    /// The queue is currently full, no byte could be written. The write should
+    /// be retried later. Its kind is [ErrorKind::WriteZero]
+    Full,
+
+    /// Unable to produce another grant, a grant of this type is already in
+    /// progress
+    GrantInProgress,
+}
+
+impl From<Error> for IoError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::GrantInProgress => IoError::GrantInProgress,
+            // Splitting errors can't happen on an already split queue
+            _ => IoError::WouldBlock,
+        }
+    }
+}
+
+impl IoError {
+    /// Same as the [From] conversion, for the errors of a write
+    fn from_write(err: Error) -> Self {
+        match err {
+            Error::InsufficientSize { .. } => IoError::Full,
+            err => err.into(),
+        }
+    }
+}
+
+impl embedded_io::Error for IoError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            IoError::Full => ErrorKind::WriteZero,
+            IoError::WouldBlock | IoError::GrantInProgress => ErrorKind::Other,
+        }
+    }
+}
+
+impl<'a, B, I> ErrorType for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Error = IoError;
+}
+
+impl<'a, B, I> ErrorType for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Error = IoError;
+}
+
+/// Writes as many bytes as fit contiguously in the queue, using
+/// [Producer::grant_max_remaining].
+///
+/// When the queue is full, [IoError::Full] is returned.
+impl<'a, B, I> Write for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut grant = self
+            .grant_max_remaining(buf.len())
+            .map_err(IoError::from_write)?;
+        let len = grant.len();
+        grant.copy_from_slice(&buf[..len]);
+        grant.commit(len);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, B, I> WriteReady for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.space_remaining() != 0)
+    }
+}
+
+/// Reads the first contiguous region of committed bytes, using [Consumer::read].
+///
+/// When the queue is empty, [IoError::WouldBlock] is returned, or `Ok(0)` once the
+/// producer closed the queue, see [Producer::close].
+impl<'a, B, I> Read for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // Before reading, so the bytes committed right before closing aren't missed
+        let closed = self.is_closed();
+        let grant = match Consumer::read(self) {
+            Ok(grant) => grant,
+            Err(Error::InsufficientSize { .. }) if closed => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let len = grant.len().min(buf.len());
+        buf[..len].copy_from_slice(&grant[..len]);
+        grant.release(len);
+        Ok(len)
+    }
+}
+
+impl<'a, B, I> ReadReady for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_empty())
+    }
+}
+
+/// Waits for space to become available, and writes as many bytes as fit
+/// contiguously in the queue, using [Producer::grant_max_remaining_async].
+#[cfg(feature = "embedded-io-async")]
+impl<'a, B, I> embedded_io_async::Write for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut grant = self
+            .grant_max_remaining_async(buf.len())
+            .await
+            .map_err(IoError::from_write)?;
+        let len = grant.len();
+        grant.copy_from_slice(&buf[..len]);
+        grant.commit(len);
+        Ok(len)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Waits for data to become available, and reads the first contiguous region
+/// of committed bytes, using [Consumer::read_async]. Returns `Ok(0)` once the
+/// producer closed the queue and all bytes were read.
+#[cfg(feature = "embedded-io-async")]
+impl<'a, B, I> embedded_io_async::Read for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let grant = match self.read_async().await {
+            Ok(grant) => grant,
+            Err(Error::Closed) => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let len = grant.len().min(buf.len());
+        buf[..len].copy_from_slice(&grant[..len]);
+        grant.release(len);
+        Ok(len)
+    }
+}
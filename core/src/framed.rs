@@ -11,9 +11,9 @@
 //! ```rust
 //! # // bbqueue test shim!
 //! # fn bbqtest() {
-//! use bbqueue::{BBQueue, StaticBufferProvider};
+//! use bbqueue::{BBQueue, StaticStorageProvider};
 //!
-//! let bb: BBQueue<StaticBufferProvider<1000>> = BBQueue::new_static();
+//! let bb: BBQueue<StaticStorageProvider<1000>> = BBQueue::new_static();
 //! let (mut prod, mut cons) = bb.try_split_framed().unwrap();
 //!
 //! // One frame in, one frame out
@@ -42,8 +42,8 @@
 //! ## Frame header
 //!
 //! An internal header is required for each frame stored
-//! inside of the `BBQueue`. This header is never exposed to end
-//! users of the bbqueue library.
+//! inside of the `BBQueue`. This header is not part of the payload
+//! of the grants, and is only exposed through [FrameGrantR::raw].
 //!
 //! A variable sized integer is used for the header size, and the
 //! size of this header is based on the max size requested for the grant.
@@ -69,70 +69,550 @@
 //! | (2^49)..(2^56)        | 8                    |
 //! | (2^56)..(2^64)        | 9                    |
 //!
+//! The header stores the payload length (the committed size, not the
+//! requested size) as a little-endian varint, where the number of trailing
+//! zero bits in the first byte, plus one, gives the length of the header.
+//! As the header size is based on the requested size, it is not always the
+//! shortest possible encoding of the payload length.
+//!
+//! [frame_header_len] and [max_frame_payload] can be used to size buffers
+//! at compile time.
+//!
+//! ## Wire format
+//!
+//! The header encoding is stable, and does not depend on the pointer width
+//! of the platform: the same frame is stored as the same bytes on 16, 32 and
+//! 64-bit targets. The header is a [vint64](https://docs.rs/vint64)-style
+//! varint of up to 9 bytes:
+//!
+//! * For headers of 1 to 8 bytes, the payload length is shifted left by the
+//!   header length, with a single set bit marking the end of the prefix, and
+//!   stored in little-endian order. For example, a payload of 3 bytes with a
+//!   one byte header is stored as `0x07`, and a payload of 128 bytes with a
+//!   two byte header is stored as `[0x02, 0x02]`.
+//! * A 9 byte header starts with `0x00`, followed by the payload length as a
+//!   little-endian `u64`.
+//!
+//! Previous versions of this crate computed the header on a `usize`, so on 16
+//! and 32-bit platforms, frames of at least 2^14 and 2^28 bytes respectively used
+//! a different, platform specific header. Frames below these sizes, and all
+//! frames on 64-bit platforms, are unchanged.
+//!
+//! ## Tagged frames
+//!
+//! A queue split with [BBQueue::try_split_framed_tagged](crate::BBQueue::try_split_framed_tagged)
+//! stores a tag byte with each frame, for example to identify the type of a message. The
+//! tag is stored as the first byte of the frame, counted in the payload length of the
+//! header, so the wire format is the same as an untagged frame starting with that byte.
+//! See [TaggedFrameProducer].
+//!
+//! ## Forwarding frames
+//!
+//! [FrameGrantR::raw] exposes the bytes of a frame as they are stored in the
+//! queue, including the header. These can be pushed as-is into another framed
+//! queue with [FrameProducer::push_raw], without decoding and re-encoding the
+//! header.
+//!
+//! To forward many frames at once, for instance to a transport which only deals
+//! with bytes, [FrameConsumer::read_raw] gives a plain read grant over all the
+//! frames available contiguously, and [FrameProducer::grant_raw] a plain write
+//! grant to store them in another framed queue.
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::{BBQueue, StaticStorageProvider};
+//!
+//! let bb1: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+//! let bb2: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+//! let (mut prod1, mut cons1) = bb1.try_split_framed().unwrap();
+//! let (mut prod2, mut cons2) = bb2.try_split_framed().unwrap();
+//!
+//! let mut wgrant = prod1.grant(4).unwrap();
+//! wgrant.copy_from_slice(&[1, 2, 3, 4]);
+//! wgrant.commit(4);
+//!
+//! // Forward the frame, header included
+//! let rgrant = cons1.read().unwrap();
+//! assert_eq!(rgrant.frame_len(), 5);
+//! prod2.push_raw(rgrant.raw()).unwrap();
+//! rgrant.release();
+//!
+//! let rgrant = cons2.read().unwrap();
+//! assert_eq!(&*rgrant, &[1, 2, 3, 4]);
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
 
-use crate::{StorageProvider, Consumer, GrantR, GrantW, Producer};
+use crate::{BbqIndex, Consumer, GrantR, GrantW, GrantWriter, Producer, StorageProvider};
 
 use crate::{
+    index::private::AtomicIndex,
     vusize::{decode_usize, decoded_len, encode_usize_to_slice, encoded_len},
-    Result,
+    Error, Result,
 };
 
+#[cfg(feature = "futures")]
+use crate::{waker::WakerCell, GrantExactFuture};
 use core::{
     cmp::min,
+    fmt,
+    iter::Take,
     ops::{Deref, DerefMut},
+    result::Result as CoreResult,
+    slice::from_raw_parts,
+    sync::atomic::Ordering::Acquire,
 };
+#[cfg(feature = "futures")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+#[cfg(feature = "futures")]
+use futures_sink::Sink;
+
+/// Returns the size of the frame header, in bytes, of a grant of `payload_len` bytes.
+///
+/// See the [module level documentation](crate::framed) for details on the header.
+///
+/// ```rust
+/// use bbqueue::framed::frame_header_len;
+///
+/// const HEADER: usize = frame_header_len(128);
+///
+/// assert_eq!(frame_header_len(127), 1);
+/// assert_eq!(HEADER, 2);
+/// ```
+pub const fn frame_header_len(payload_len: usize) -> usize {
+    encoded_len(payload_len)
+}
+
+/// Returns the largest payload, in bytes, of a single frame that fits in a queue
+/// of `capacity` bytes, or `0` if no frame fits.
+///
+/// ```rust
+/// use bbqueue::{framed::max_frame_payload, BBQueue, StaticStorageProvider};
+///
+/// const CAPACITY: usize = 1000;
+/// const MAX_PAYLOAD: usize = max_frame_payload(CAPACITY);
+/// assert_eq!(MAX_PAYLOAD, 998);
+///
+/// let bb: BBQueue<StaticStorageProvider<CAPACITY>> = BBQueue::new_static();
+/// let (mut prod, _cons) = bb.try_split_framed().unwrap();
+/// assert!(prod.grant(MAX_PAYLOAD).is_ok());
+/// ```
+pub const fn max_frame_payload(capacity: usize) -> usize {
+    // Try the shortest headers first, as they leave the most room for the payload
+    let mut hdr_len = 1;
+    while hdr_len <= capacity && hdr_len <= 9 {
+        let payload_len = capacity - hdr_len;
+        if encoded_len(payload_len) <= hdr_len {
+            return payload_len;
+        }
+        hdr_len += 1;
+    }
+    0
+}
 
 /// A producer of Framed data
-pub struct FrameProducer<'a, B>
+pub struct FrameProducer<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) producer: Producer<'a, B, I>,
+}
+
+impl<'a, B, I> fmt::Debug for FrameProducer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    pub(crate) producer: Producer<'a, B>,
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameProducer")
+            .field("producer", &self.producer)
+            .finish()
+    }
 }
 
-impl<'a, B> FrameProducer<'a, B>
+impl<'a, B, I> FrameProducer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
+    /// Returns the size of the frame header, in bytes, of a grant of `max_sz` bytes.
+    ///
+    /// See the [module level documentation](crate::framed) for details on the header.
+    ///
+    /// ```rust
+    /// use bbqueue::{framed::FrameProducer, StaticStorageProvider};
+    ///
+    /// type Prod<'a> = FrameProducer<'a, StaticStorageProvider<1000>>;
+    ///
+    /// assert_eq!(Prod::frame_overhead(127), 1);
+    /// assert_eq!(Prod::frame_overhead(128), 2);
+    /// ```
+    pub fn frame_overhead(max_sz: usize) -> usize {
+        frame_header_len(max_sz)
+    }
+
+    /// Returns the total size of a frame in the queue, in bytes, including the frame
+    /// header, of a grant of `max_sz` bytes.
+    ///
+    /// This is the contiguous space required by [Self::grant] for that size. As the header
+    /// size is based on the requested size, this is the size of the frame in the queue
+    /// even if less than `max_sz` bytes are committed.
+    ///
+    /// ```rust
+    /// use bbqueue::{framed::FrameProducer, StaticStorageProvider};
+    ///
+    /// type Prod<'a> = FrameProducer<'a, StaticStorageProvider<1000>>;
+    ///
+    /// assert_eq!(Prod::frame_len(127), 128);
+    /// assert_eq!(Prod::frame_len(128), 130);
+    /// ```
+    pub fn frame_len(max_sz: usize) -> usize {
+        max_sz + frame_header_len(max_sz)
+    }
+
     /// Receive a grant for a frame with a maximum size of `max_sz` in bytes.
     ///
     /// This size does not include the size of the frame header. The exact size
     /// of the frame can be set on `commit`.
-    pub fn grant(&mut self, max_sz: usize) -> Result<FrameGrantW<'a, B>> {
+    pub fn grant(&mut self, max_sz: usize) -> Result<FrameGrantW<'a, B, I>> {
         let hdr_len = encoded_len(max_sz);
         Ok(FrameGrantW {
             grant_w: self.producer.grant_exact(max_sz + hdr_len)?,
             hdr_len: hdr_len as u8,
+            tagged: false,
         })
     }
 
+    /// Push a raw frame, including its header, as obtained from [FrameGrantR::raw].
+    ///
+    /// The frame is copied as-is, so the header is not re-encoded. If the frame doesn't
+    /// fit contiguously in the queue, an error is returned and nothing is written.
+    ///
+    /// `raw` must be a complete frame, with a header matching the length of the payload.
+    /// This is checked in debug mode only.
+    pub fn push_raw(&mut self, raw: &[u8]) -> Result<()> {
+        debug_assert!(!raw.is_empty());
+        debug_assert_eq!(decoded_len(raw[0]) + decode_usize(raw), raw.len());
+
+        self.producer.push_slice_all(raw)
+    }
+
+    /// Receive a plain write grant of exactly `sz` bytes, to store raw frames,
+    /// including their headers, as obtained from [FrameConsumer::read_raw].
+    ///
+    /// Only whole frames must be committed, otherwise the consumer will misread the
+    /// following frames. Unlike [Self::push_raw], this is not checked.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// // Two frames of one and two bytes, as received from a transport
+    /// let wire = [0x03, 1, 0x05, 2, 3];
+    /// let mut wgrant = prod.grant_raw(wire.len()).unwrap();
+    /// wgrant.copy_from_slice(&wire);
+    /// wgrant.commit(wire.len());
+    ///
+    /// assert_eq!(&*cons.read().unwrap(), &[1]);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn grant_raw(&mut self, sz: usize) -> Result<GrantW<'a, B, I>> {
+        self.producer.grant_exact(sz)
+    }
+
+    /// Write many frames at once, and commit them together. Returns the number of
+    /// frames that were written, which may be less than `frames.len()` if not all of
+    /// them fit.
+    ///
+    /// The frames are written to a single contiguous grant, so the atomics are only
+    /// updated once for the whole batch. If not all frames fit, as many frames as fit
+    /// contiguously are written, and the remaining ones can be pushed by calling this
+    /// again once the consumer has released some space. Each frame uses a header based
+    /// on its own length, like a grant of exactly that size.
+    ///
+    /// If not even the first frame can be written, an error is returned, like
+    /// [Producer::push_slice].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<8>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// // Each frame takes one more byte for its header, the last one doesn't fit
+    /// assert_eq!(prod.commit_frames(&[&[1, 2], &[3], &[4, 5, 6]]), Ok(2));
+    ///
+    /// let rgrant = cons.read().unwrap();
+    /// assert_eq!(&*rgrant, &[1, 2]);
+    /// rgrant.release();
+    /// let rgrant = cons.read().unwrap();
+    /// assert_eq!(&*rgrant, &[3]);
+    /// rgrant.release();
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn commit_frames(&mut self, frames: &[&[u8]]) -> Result<usize> {
+        if frames.is_empty() {
+            return Ok(0);
+        }
+
+        let total = frames.iter().fold(0usize, |acc, f| {
+            acc.saturating_add(Self::frame_len(f.len()))
+        });
+
+        let mut grant = match self.producer.grant_exact(total) {
+            Ok(grant) => grant,
+            Err(Error::InsufficientSize { .. } | Error::RequestTooLarge) => {
+                let grant = self.producer.grant_max_remaining(total)?;
+                if grant.len() >= Self::frame_len(frames[0].len()) {
+                    grant
+                } else {
+                    // Not even one frame fits before the end of the ring, or before
+                    // the read pointer. Dropping the grant commits nothing, and a
+                    // grant of a single frame may wrap around to the start instead
+                    drop(grant);
+                    self.producer
+                        .grant_exact(Self::frame_len(frames[0].len()))?
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut used = 0;
+        let mut count = 0;
+        for frame in frames {
+            let hdr_len = encoded_len(frame.len());
+            let Some(buf) = grant.get_mut(used..used + hdr_len + frame.len()) else {
+                break;
+            };
+
+            let (hdr, payload) = buf.split_at_mut(hdr_len);
+            encode_usize_to_slice(frame.len(), hdr_len, hdr);
+            payload.copy_from_slice(frame);
+
+            used += buf.len();
+            count += 1;
+        }
+
+        grant.commit(used);
+        Ok(count)
+    }
+
     /// Async version of [Self::grant]
-    pub async fn grant_async(&mut self, max_sz: usize) -> Result<FrameGrantW<'a, B>> {
+    #[cfg(feature = "async-api")]
+    pub async fn grant_async(&mut self, max_sz: usize) -> Result<FrameGrantW<'a, B, I>> {
         let hdr_len = encoded_len(max_sz);
         Ok(FrameGrantW {
             grant_w: self.producer.grant_exact_async(max_sz + hdr_len).await?,
             hdr_len: hdr_len as u8,
+            tagged: false,
         })
     }
+
+    /// Closes the queue, signaling the consumer that no more frames will be committed.
+    ///
+    /// See [Producer::close] for details.
+    pub fn close(self) {
+        // Framed producers are never reborrowed
+        self.producer.close_inner()
+    }
+
+    /// Returns a [Sink] storing each byte slice sent to it as a frame of up to `max_sz`
+    /// bytes.
+    ///
+    /// As the size of the next frame isn't known until it is sent, the sink waits for room
+    /// for a frame of `max_sz` bytes when polled for readiness, like [Self::grant_async],
+    /// and keeps that grant until the next frame is sent. Sending a frame larger than
+    /// `max_sz` returns an [Error::InsufficientSize](crate::Error::InsufficientSize).
+    ///
+    /// Frames are committed as soon as they are sent, so flushing the sink does nothing.
+    /// Closing the sink gives back the space waited for, without storing a frame.
+    #[cfg(feature = "futures")]
+    pub fn sink(&mut self, max_sz: usize) -> FrameSink<'a, '_, B, I> {
+        let hdr_len = encoded_len(max_sz);
+        FrameSink {
+            fut: self.producer.grant_exact_async(max_sz + hdr_len),
+            max_sz,
+            hdr_len: hdr_len as u8,
+            grant: None,
+        }
+    }
+}
+
+/// A producer of Framed data, where each frame carries a tag byte
+///
+/// The tag is stored as the first byte of the frame, right after the header, and is
+/// returned by [FrameGrantR::tag]. All the frames of a queue split with
+/// [BBQueue::try_split_framed_tagged](crate::BBQueue::try_split_framed_tagged) are
+/// tagged, so tagged and untagged frames can't be mixed in a queue.
+///
+/// ```rust
+/// # // bbqueue test shim!
+/// # fn bbqtest() {
+/// use bbqueue::{BBQueue, StaticStorageProvider};
+///
+/// let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+/// let (mut prod, mut cons) = bb.try_split_framed_tagged().unwrap();
+///
+/// let mut wgrant = prod.grant_with_tag(4, 0x42).unwrap();
+/// wgrant[..2].copy_from_slice(&[1, 2]);
+/// wgrant.commit(2);
+///
+/// let rgrant = cons.read().unwrap();
+/// assert_eq!(rgrant.tag(), Some(0x42));
+/// assert_eq!(&*rgrant, &[1, 2]);
+/// # // bbqueue test shim!
+/// # }
+/// #
+/// # fn main() {
+/// # #[cfg(not(feature = "thumbv6"))]
+/// # bbqtest();
+/// # }
+/// ```
+pub struct TaggedFrameProducer<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) producer: Producer<'a, B, I>,
+}
+
+impl<'a, B, I> fmt::Debug for TaggedFrameProducer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedFrameProducer")
+            .field("producer", &self.producer)
+            .finish()
+    }
+}
+
+impl<'a, B, I> TaggedFrameProducer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Returns the total size of a frame in the queue, in bytes, including the frame
+    /// header and the tag, of a grant of `max_sz` bytes.
+    ///
+    /// See [FrameProducer::frame_len] for details.
+    pub fn frame_len(max_sz: usize) -> usize {
+        FrameProducer::<B, I>::frame_len(max_sz + 1)
+    }
+
+    /// Receive a grant for a frame with a maximum size of `max_sz` in bytes, tagged
+    /// with `tag`.
+    ///
+    /// This size does not include the size of the frame header and of the tag. The
+    /// exact size of the frame can be set on `commit`.
+    pub fn grant_with_tag(&mut self, max_sz: usize, tag: u8) -> Result<FrameGrantW<'a, B, I>> {
+        let hdr_len = encoded_len(max_sz + 1);
+        let grant_w = self.producer.grant_exact(max_sz + 1 + hdr_len)?;
+        Ok(tag_grant(grant_w, hdr_len, tag))
+    }
+
+    /// Async version of [Self::grant_with_tag]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_with_tag_async(
+        &mut self,
+        max_sz: usize,
+        tag: u8,
+    ) -> Result<FrameGrantW<'a, B, I>> {
+        let hdr_len = encoded_len(max_sz + 1);
+        let grant_w = self
+            .producer
+            .grant_exact_async(max_sz + 1 + hdr_len)
+            .await?;
+        Ok(tag_grant(grant_w, hdr_len, tag))
+    }
+}
+
+/// Stores the tag right after the header of the frame
+fn tag_grant<B, I>(mut grant_w: GrantW<'_, B, I>, hdr_len: usize, tag: u8) -> FrameGrantW<'_, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    grant_w[hdr_len] = tag;
+    FrameGrantW {
+        grant_w,
+        hdr_len: hdr_len as u8,
+        tagged: true,
+    }
 }
 
 /// A consumer of Framed data
-pub struct FrameConsumer<'a, B>
+pub struct FrameConsumer<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    pub(crate) consumer: Consumer<'a, B, I>,
+
+    // Whether each frame starts with a tag byte, see `TaggedFrameProducer`
+    pub(crate) tagged: bool,
+}
+
+impl<'a, B, I> fmt::Debug for FrameConsumer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    pub(crate) consumer: Consumer<'a, B>,
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameConsumer")
+            .field("consumer", &self.consumer)
+            .field("tagged", &self.tagged)
+            .finish()
+    }
 }
 
-impl<'a, B> FrameConsumer<'a, B>
+impl<'a, B, I> FrameConsumer<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    /// Obtain the next available frame, if any
-    pub fn read(&mut self) -> Option<FrameGrantR<'a, B>> {
+    /// Obtain the next available frame.
+    ///
+    /// Like [Consumer::read], an [Error::InsufficientSize](crate::Error::InsufficientSize)
+    /// is returned if no frame is available, and an
+    /// [Error::GrantInProgress](crate::Error::GrantInProgress) if a frame is already being read.
+    /// See [Self::try_read] to get `None` in both cases instead.
+    pub fn read(&mut self) -> Result<FrameGrantR<'a, B, I>> {
         // Get all available bytes. We never wrap a frame around,
         // so if a header is available, the whole frame will be.
-        let mut grant_r = self.consumer.read().ok()?;
+        let mut grant_r = self.consumer.read()?;
 
         // Additionally, we never commit less than a full frame with
         // a header, so if we have ANY data, we'll have a full header
@@ -151,11 +631,225 @@ where
         // Reduce the grant down to the size of the frame with a header
         grant_r.shrink(total_len);
 
-        Some(FrameGrantR { grant_r, hdr_len })
+        Ok(FrameGrantR {
+            grant_r,
+            hdr_len,
+            tagged: self.tagged,
+        })
+    }
+
+    /// Obtain a plain read grant over all the frames available contiguously, including
+    /// their headers.
+    ///
+    /// The grant always ends on a frame boundary, so the bytes can be forwarded as-is,
+    /// for instance to a transport, and stored in another framed queue with
+    /// [FrameProducer::grant_raw]. Frames committed after the writer wrapped around
+    /// are returned by the next call, once these are released.
+    ///
+    /// Only whole frames must be released, such as with [GrantR::release_all],
+    /// otherwise the following frames will be misread.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// prod.commit_frames(&[&[1], &[2, 3]]).unwrap();
+    ///
+    /// let rgrant = cons.read_raw().unwrap();
+    /// assert_eq!(&*rgrant, &[0x03, 1, 0x05, 2, 3]);
+    /// rgrant.release_all();
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn read_raw(&mut self) -> Result<GrantR<'a, B, I>> {
+        let mut grant_r = self.consumer.read()?;
+
+        // Frames never wrap around, and are always committed whole, so the
+        // grant should already end on a frame boundary. Make sure it does.
+        let (_, _, end) = walk_frames(grant_r.as_ptr(), 0, grant_r.len());
+        grant_r.shrink(end);
+
+        Ok(grant_r)
+    }
+
+    /// Non-blocking version of [Self::read], returning `None` if no frame can be
+    /// read right now.
+    #[must_use = "a frame is not released when the grant is dropped, unless configured with `to_release_all`"]
+    pub fn try_read(&mut self) -> Option<FrameGrantR<'a, B, I>> {
+        self.read().ok()
+    }
+
+    /// Returns an iterator over the frames that are available for reading.
+    ///
+    /// Each frame is automatically released when its grant is dropped, see
+    /// [FrameGrantR::auto_release]. The iterator returns `None` once no frame is
+    /// available, but may return more frames later if the producer commits them.
+    ///
+    /// As only one read grant may exist at a time, the previous frame must be dropped
+    /// or released before calling `next()` again. Otherwise `next()` returns `None`,
+    /// like [Self::try_read] when a read grant is in progress.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// for i in 0..3 {
+    ///     let mut wgrant = prod.grant(1).unwrap();
+    ///     wgrant[0] = i;
+    ///     wgrant.commit(1);
+    /// }
+    ///
+    /// for (i, frame) in cons.frames().enumerate() {
+    ///     assert_eq!(&*frame, &[i as u8]);
+    /// }
+    /// assert!(cons.try_read().is_none());
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn frames(&mut self) -> FrameIter<'a, '_, B, I> {
+        FrameIter { cons: self }
+    }
+
+    /// Returns an iterator over the frames that are available for reading when this is
+    /// called.
+    ///
+    /// Like [Self::frames], each frame is automatically released when its grant is
+    /// dropped, and the previous frame must be dropped or released before calling `next()`
+    /// again, otherwise `next()` returns `None`. Unlike [Self::frames], the frames
+    /// committed by the producer while draining are left in the queue, so draining ends
+    /// even if the producer keeps committing frames.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// prod.commit_frames(&[&[1], &[2]]).unwrap();
+    ///
+    /// for frame in cons.drain() {
+    ///     // Committed after the drain started, left for later
+    ///     prod.commit_frames(&[&[frame[0] + 2]]).unwrap();
+    /// }
+    /// assert_eq!(cons.frames_available(), 2);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn drain(&mut self) -> Take<FrameIter<'a, '_, B, I>> {
+        let available = self.frames_available();
+        self.frames().take(available)
+    }
+
+    /// Inspect the payload of the next available frame, if any, without consuming it.
+    ///
+    /// No grant is held once this returns, so the next call to [Self::read] will
+    /// return the same frame.
+    pub fn peek(&mut self) -> Option<&[u8]> {
+        let grant_r = self.consumer.read().ok()?;
+
+        let frame_len = decode_usize(&grant_r);
+        let hdr_len = decoded_len(grant_r[0]);
+
+        debug_assert!(grant_r.len() >= frame_len + hdr_len);
+
+        let frame = &grant_r[hdr_len..][..frame_len][usize::from(self.tagged)..];
+
+        // SAFETY: The frame is never released while we hold the mutable borrow of the
+        // consumer, and the producer never writes to committed bytes, so the data stays
+        // valid even once the grant is dropped.
+        let frame = unsafe { from_raw_parts(frame.as_ptr(), frame.len()) };
+
+        // Dropping the grant releases nothing, but allows the next read
+        drop(grant_r);
+
+        Some(frame)
+    }
+
+    /// Returns the number of complete frames that are available for reading,
+    /// without consuming any of them.
+    ///
+    /// This walks the headers of all committed frames, so it takes a time
+    /// proportional to the number of frames in the queue. A frame that is
+    /// currently held by a read grant is still counted.
+    ///
+    /// NOTE: This is a best-effort snapshot of the queue state. If the producer
+    /// is in use concurrently, the value may already be outdated when it is returned.
+    pub fn frames_available(&self) -> usize {
+        self.walk_frames().0
+    }
+
+    /// Returns the total size of the payloads of the frames that are available for
+    /// reading, in bytes, without consuming any of them. Frame headers are not counted.
+    ///
+    /// See [Self::frames_available] for details.
+    pub fn queued_bytes(&self) -> usize {
+        let (frames, bytes) = self.walk_frames();
+
+        // The tags are stored as the first byte of the payloads
+        if self.tagged {
+            bytes - frames
+        } else {
+            bytes
+        }
+    }
+
+    /// Returns `true` if the producer closed the queue, see [Consumer::is_closed].
+    pub fn is_closed(&self) -> bool {
+        self.consumer.is_closed()
+    }
+
+    /// Walk the headers of all committed frames, returning the number of frames
+    /// and the total size of their payloads
+    fn walk_frames(&self) -> (usize, usize) {
+        let inner = unsafe { self.consumer.bbq.as_ref() };
+
+        // Same ordering as `Consumer::read`: `last` is always updated before `write`
+        let write = inner.write.load(Acquire);
+        let last = inner.last.load(Acquire);
+        let read = inner.read.load(Acquire);
+
+        let start_of_buf_ptr = unsafe { (*inner.buf.get()).storage().as_ptr() as *const u8 };
+
+        if write < read {
+            // Inverted, frames never wrap around, so the frames up to `last`
+            // and the frames from the start of the ring are walked separately
+            let (frames1, bytes1, _) = walk_frames(start_of_buf_ptr, read, last);
+            let (frames2, bytes2, _) = walk_frames(start_of_buf_ptr, 0, write);
+            (frames1 + frames2, bytes1 + bytes2)
+        } else {
+            let (frames, bytes, _) = walk_frames(start_of_buf_ptr, read, write);
+            (frames, bytes)
+        }
     }
 
     /// Async version of [Self::read]
-    pub async fn read_async(&mut self) -> Result<FrameGrantR<'a, B>> {
+    #[cfg(feature = "async-api")]
+    pub async fn read_async(&mut self) -> Result<FrameGrantR<'a, B, I>> {
         // Get all available bytes. We never wrap a frame around,
         // so if a header is available, the whole frame will be.
         let mut grant_r = self.consumer.read_async().await?;
@@ -177,8 +871,174 @@ where
         // Reduce the grant down to the size of the frame with a header
         grant_r.shrink(total_len);
 
-        Ok(FrameGrantR { grant_r, hdr_len })
+        Ok(FrameGrantR {
+            grant_r,
+            hdr_len,
+            tagged: self.tagged,
+        })
+    }
+}
+
+/// Iterator returned by [FrameConsumer::frames]
+pub struct FrameIter<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    cons: &'b mut FrameConsumer<'a, B, I>,
+}
+
+impl<'a, 'b, B, I> Iterator for FrameIter<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Item = FrameGrantR<'a, B, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut grant_r = self.cons.try_read()?;
+        grant_r.auto_release(true);
+        Some(grant_r)
+    }
+}
+
+/// Yields the frames as they are committed, like [FrameConsumer::read_async].
+///
+/// Each frame is automatically released when its grant is dropped, see
+/// [FrameGrantR::auto_release], so the stream can be used with the `StreamExt`
+/// combinators. The stream ends once the producer closed the queue, see
+/// [FrameProducer::close], and all frames were read.
+///
+/// As only one read grant may exist at a time, the previous frame must be dropped
+/// or released before polling the stream again. Otherwise the stream stays pending
+/// until the producer commits another frame.
+#[cfg(feature = "futures")]
+impl<'a, B, I> Stream for FrameConsumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Item = FrameGrantR<'a, B, I>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Same as `GrantReadFuture`
+        let closed = self.is_closed();
+
+        match self.read() {
+            Ok(mut grant_r) => {
+                grant_r.auto_release(true);
+                Poll::Ready(Some(grant_r))
+            }
+            Err(Error::InsufficientSize { .. }) if closed => Poll::Ready(None),
+            Err(Error::InsufficientSize { .. } | Error::GrantInProgress) => {
+                unsafe { self.consumer.bbq.as_ref() }
+                    .read_waker
+                    .register(cx.waker());
+                Poll::Pending
+            }
+            Err(_) => Poll::Ready(None),
+        }
+    }
+}
+
+/// Sink returned by [FrameProducer::sink]
+#[cfg(feature = "futures")]
+pub struct FrameSink<'a, 'b, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    // Not a future to await, only polled for each frame
+    fut: GrantExactFuture<'a, 'b, B, I>,
+    max_sz: usize,
+    hdr_len: u8,
+    grant: Option<FrameGrantW<'a, B, I>>,
+}
+
+#[cfg(feature = "futures")]
+impl<'a, 'b, 'c, B, I> Sink<&'c [u8]> for FrameSink<'a, 'b, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.grant.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let grant_w = match Pin::new(&mut self.fut).poll(cx) {
+            Poll::Ready(Ok(grant_w)) => grant_w,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let hdr_len = self.hdr_len;
+        self.grant = Some(FrameGrantW {
+            grant_w,
+            hdr_len,
+            tagged: false,
+        });
+        Poll::Ready(Ok(()))
     }
+
+    fn start_send(mut self: Pin<&mut Self>, item: &'c [u8]) -> Result<()> {
+        // Only given by `poll_ready`, which must be called first
+        let Some(mut grant) = self.grant.take() else {
+            return Err(Error::GrantInProgress);
+        };
+
+        if item.len() > self.max_sz {
+            // Keep the grant for a smaller frame
+            self.grant = Some(grant);
+            return Err(Error::InsufficientSize {
+                requested: item.len(),
+                available: self.max_sz,
+            });
+        }
+
+        grant[..item.len()].copy_from_slice(item);
+        grant.commit(item.len());
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // Frames are committed by `start_send`
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // Dropping the grant commits nothing
+        self.grant = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Walk the complete frames stored between `from` and `to`, returning the
+/// number of frames, the total size of their payloads, and the end of the last frame
+fn walk_frames(start_of_buf_ptr: *const u8, mut from: usize, to: usize) -> (usize, usize, usize) {
+    let mut frames = 0;
+    let mut bytes = 0;
+
+    while from < to {
+        // Only the headers are accessed, as the payload of a frame may be
+        // mutably borrowed through a read grant
+        let hdr_len = decoded_len(unsafe { start_of_buf_ptr.add(from).read() });
+        let hdr = unsafe { from_raw_parts(start_of_buf_ptr.add(from), hdr_len) };
+        let frame_len = decode_usize(hdr);
+
+        // We never commit less than a full frame, so this should not happen
+        if from + hdr_len + frame_len > to {
+            debug_assert!(false, "Partial frame in the committed region");
+            break;
+        }
+
+        from += hdr_len + frame_len;
+        frames += 1;
+        bytes += frame_len;
+    }
+
+    (frames, bytes, from)
 }
 
 /// A write grant for a single frame
@@ -186,76 +1046,162 @@ where
 /// NOTE: If the grant is dropped without explicitly commiting
 /// the contents without first calling `to_commit()`, then no
 /// frame will be comitted for writing.
-#[derive(Debug, PartialEq)]
-pub struct FrameGrantW<'a, B>
+///
+/// When dropped, the grant commits exactly what [Self::commit] would have
+/// committed with the size configured by [Self::to_commit], [Self::to_commit_all]
+/// or [Self::auto_commit]. A frame of zero bytes is a valid, empty frame: both
+/// `commit(0)` and `to_commit(0)` store one for the consumer. Use
+/// `auto_commit(false)` to drop the grant without storing a frame.
+#[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+#[derive(PartialEq)]
+pub struct FrameGrantW<'a, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    grant_w: GrantW<'a, B>,
+    grant_w: GrantW<'a, B, I>,
     hdr_len: u8,
+    tagged: bool,
+}
+
+impl<'a, B, I> fmt::Debug for FrameGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameGrantW")
+            .field("grant_w", &self.grant_w)
+            .field("hdr_len", &self.hdr_len)
+            .field("tagged", &self.tagged)
+            .finish()
+    }
 }
 
 /// A read grant for a single frame
 ///
 /// NOTE: If the grant is dropped without explicitly releasing
 /// the contents, then no frame will be released.
-#[derive(Debug, PartialEq)]
-pub struct FrameGrantR<'a, B>
+///
+/// A frame is always released as a whole. Use [Self::to_release_all] or
+/// [Self::auto_release] to release the frame when the grant is dropped.
+#[must_use = "a frame is not released when the grant is dropped, unless configured with `to_release_all`"]
+#[derive(PartialEq)]
+pub struct FrameGrantR<'a, B, I = usize>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
-    grant_r: GrantR<'a, B>,
+    grant_r: GrantR<'a, B, I>,
     hdr_len: u8,
+    tagged: bool,
+}
+
+impl<'a, B, I> fmt::Debug for FrameGrantR<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameGrantR")
+            .field("grant_r", &self.grant_r)
+            .field("hdr_len", &self.hdr_len)
+            .field("tagged", &self.tagged)
+            .finish()
+    }
+}
+
+/// Compact representation of the frame, see the [fmt::Debug] implementation for details.
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for FrameGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "FrameGrantW {{ len: {=usize}, hdr_len: {=u8} }}",
+            self.len(),
+            self.hdr_len,
+        );
+    }
 }
 
-impl<'a, B> Deref for FrameGrantW<'a, B>
+/// Compact representation of the frame, see the [fmt::Debug] implementation for details.
+#[cfg(feature = "defmt")]
+impl<'a, B, I> defmt::Format for FrameGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "FrameGrantR {{ len: {=usize}, tag: {} }}",
+            self.len(),
+            self.tag(),
+        );
+    }
+}
+
+impl<'a, B, I> Deref for FrameGrantW<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
 {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.grant_w[self.hdr_len.into()..]
+        &self.grant_w[self.payload_start()..]
     }
 }
 
-impl<'a, B> DerefMut for FrameGrantW<'a, B>
+impl<'a, B, I> DerefMut for FrameGrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn deref_mut(&mut self) -> &mut [u8] {
-        &mut self.grant_w[self.hdr_len.into()..]
+        let start = self.payload_start();
+        &mut self.grant_w[start..]
     }
 }
 
-impl<'a, B> Deref for FrameGrantR<'a, B>
+impl<'a, B, I> Deref for FrameGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.grant_r[self.hdr_len.into()..]
+        &self.grant_r[self.payload_start()..]
     }
 }
 
-impl<'a, B> DerefMut for FrameGrantR<'a, B>
+impl<'a, B, I> DerefMut for FrameGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     fn deref_mut(&mut self) -> &mut [u8] {
-        &mut self.grant_r[self.hdr_len.into()..]
+        let start = self.payload_start();
+        &mut self.grant_r[start..]
     }
 }
 
-impl<'a, B> FrameGrantW<'a, B>
+impl<'a, B, I> FrameGrantW<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Commit a frame to make it available to the Consumer half.
     ///
     /// `used` is the size of the payload, in bytes, not
-    /// including the frame header
+    /// including the frame header. If `used` is larger than the payload
+    /// of the grant, the whole payload is committed, see [Self::try_commit]
+    /// to reject it instead.
     pub fn commit(mut self, used: usize) {
         let total_len = self.set_header(used);
 
@@ -263,43 +1209,171 @@ where
         self.grant_w.commit(total_len);
     }
 
+    /// Same as [Self::commit], but `used` is checked instead of saturated. If `used` is
+    /// larger than the payload of the grant, nothing is committed, and the grant is given
+    /// back with [Error::InsufficientSize].
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, Error, StaticStorageProvider};
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// let grant = prod.grant(4).unwrap();
+    /// let (grant, err) = grant.try_commit(5).unwrap_err();
+    /// assert_eq!(err, Error::InsufficientSize { requested: 5, available: 4 });
+    /// assert!(cons.read().is_err());
+    ///
+    /// grant.try_commit(4).unwrap();
+    /// assert_eq!(cons.read().unwrap().len(), 4);
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn try_commit(self, used: usize) -> CoreResult<(), (Self, Error)> {
+        let available = self.grant_w.len() - self.payload_start();
+        if used > available {
+            return Err((
+                self,
+                Error::InsufficientSize {
+                    requested: used,
+                    available,
+                },
+            ));
+        }
+
+        self.commit(used);
+        Ok(())
+    }
+
     /// Set the header and return the total size
     fn set_header(&mut self, used: usize) -> usize {
         // Saturate the commit size to the available frame size
         let grant_len = self.grant_w.len();
         let hdr_len: usize = self.hdr_len.into();
-        let frame_len = min(used, grant_len - hdr_len);
-        let total_len = frame_len + hdr_len;
+        let start = self.payload_start();
+        let frame_len = min(used, grant_len - start);
+        let total_len = frame_len + start;
 
-        // Write the actual frame length to the header
-        encode_usize_to_slice(frame_len, hdr_len, &mut self.grant_w[..hdr_len]);
+        // Write the actual frame length to the header. The tag, if any,
+        // is stored as part of the frame
+        encode_usize_to_slice(total_len - hdr_len, hdr_len, &mut self.grant_w[..hdr_len]);
 
         total_len
     }
 
-    /// Configures the amount of bytes to be commited on drop.
+    /// Offset of the payload in the grant, after the header and the tag
+    fn payload_start(&self) -> usize {
+        usize::from(self.hdr_len) + usize::from(self.tagged)
+    }
+
+    /// Obtain a [GrantWriter] to write formatted text into the payload of the frame
+    ///
+    /// The frame is not committed by the writer, commit
+    /// [GrantWriter::written] bytes once done.
+    ///
+    /// ```rust
+    /// # // bbqueue test shim!
+    /// # fn bbqtest() {
+    /// use bbqueue::{BBQueue, StaticStorageProvider};
+    /// use core::fmt::Write;
+    ///
+    /// let bb: BBQueue<StaticStorageProvider<64>> = BBQueue::new_static();
+    /// let (mut prod, mut cons) = bb.try_split_framed().unwrap();
+    ///
+    /// let mut grant = prod.grant(32).unwrap();
+    /// let mut writer = grant.writer();
+    /// write!(writer, "temp: {}C", 21).unwrap();
+    /// let written = writer.written();
+    /// grant.commit(written);
+    ///
+    /// assert_eq!(&*cons.read().unwrap(), b"temp: 21C");
+    /// # // bbqueue test shim!
+    /// # }
+    /// #
+    /// # fn main() {
+    /// # #[cfg(not(feature = "thumbv6"))]
+    /// # bbqtest();
+    /// # }
+    /// ```
+    pub fn writer(&mut self) -> GrantWriter<'_> {
+        GrantWriter::new(self)
+    }
+
+    /// Configures the size of the payload, in bytes, of the frame to be
+    /// commited on drop.
+    ///
+    /// As with [Self::commit], the size is saturated to the size of the grant,
+    /// and a size of zero commits an empty frame.
     pub fn to_commit(&mut self, amt: usize) {
-        if amt == 0 {
-            self.grant_w.to_commit(0);
+        let size = self.set_header(amt);
+        self.grant_w.to_commit(size);
+    }
+
+    /// Configures the full grant to be commited as a frame on drop.
+    pub fn to_commit_all(&mut self) {
+        self.to_commit(self.len());
+    }
+
+    /// Set whether the full grant should be automatically commited as a
+    /// frame on drop. If `false`, no frame is commited.
+    pub fn auto_commit(&mut self, is_auto: bool) {
+        if is_auto {
+            self.to_commit_all();
         } else {
-            let size = self.set_header(amt);
-            self.grant_w.to_commit(size);
+            self.grant_w.to_commit(0);
         }
     }
 }
 
-impl<'a, B> FrameGrantR<'a, B>
+impl<'a, B, I> FrameGrantR<'a, B, I>
 where
     B: StorageProvider,
+    I: BbqIndex,
 {
     /// Release a frame to make the space available for future writing
     ///
     /// Note: The full frame is always released
-    pub fn release(mut self) {
+    pub fn release(self) {
         // For a read grant, we have already shrunk the grant
         // size down to the correct size
-        let len = self.grant_r.len();
-        self.grant_r.release_inner(len);
+        self.grant_r.release_all();
+    }
+
+    /// Returns the total size of the frame in the queue, in bytes, including
+    /// the frame header
+    pub fn frame_len(&self) -> usize {
+        self.grant_r.len()
+    }
+
+    /// Returns the tag of the frame, if the queue was split with
+    /// [BBQueue::try_split_framed_tagged](crate::BBQueue::try_split_framed_tagged).
+    pub fn tag(&self) -> Option<u8> {
+        self.tagged.then(|| self.grant_r[usize::from(self.hdr_len)])
+    }
+
+    /// Offset of the payload in the grant, after the header and the tag
+    fn payload_start(&self) -> usize {
+        usize::from(self.hdr_len) + usize::from(self.tagged)
+    }
+
+    /// Returns the raw bytes of the frame, including the frame header.
+    ///
+    /// See the [module level documentation](crate::framed) for the header encoding. The
+    /// raw frame can be forwarded to another framed queue with [FrameProducer::push_raw].
+    pub fn raw(&self) -> &[u8] {
+        &self.grant_r
+    }
+
+    /// Configures the frame to be released on drop.
+    pub fn to_release_all(&mut self) {
+        self.grant_r.to_release_all();
     }
 
     /// Set whether the read fram should be automatically released
@@ -0,0 +1,52 @@
+//! Hooks called around commits and reads, enabled with the `hooks` feature
+
+/// Callbacks invoked by the queue when bytes are published to the consumer, and when
+/// bytes are handed out to the consumer, see [BBQueue::set_hooks](crate::BBQueue::set_hooks).
+///
+/// This is useful for DMA transfers on targets with a data cache: the bytes written by the
+/// CPU must be cleaned from the cache before the DMA reads them, and the cache lines of the
+/// bytes written by the DMA must be invalidated before the CPU reads them.
+///
+/// The hooks are called from both the producer and the consumer side, so they must be
+/// `Sync`. They are only called for non-empty regions, and once per contiguous region, so
+/// a split grant results in up to two calls.
+///
+/// ```rust
+/// # // bbqueue test shim!
+/// # fn bbqtest() {
+/// use bbqueue::{BBQueue, BbqHooks, StaticStorageProvider};
+///
+/// struct DcacheHooks;
+///
+/// impl BbqHooks for DcacheHooks {
+///     fn pre_commit(&self, data: &[u8]) {
+///         // e.g. SCB::clean_dcache_by_slice(data)
+///     }
+///
+///     fn post_read(&self, data: &[u8]) {
+///         // e.g. SCB::invalidate_dcache_by_slice(data)
+///     }
+/// }
+///
+/// static HOOKS: DcacheHooks = DcacheHooks;
+///
+/// let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
+/// bb.set_hooks(&HOOKS).unwrap();
+/// let (prod, cons) = bb.try_split().unwrap();
+/// # // bbqueue test shim!
+/// # }
+/// #
+/// # fn main() {
+/// # #[cfg(not(feature = "thumbv6"))]
+/// # bbqtest();
+/// # }
+/// ```
+pub trait BbqHooks: Sync {
+    /// Called with the committed bytes of a write grant, right before they become
+    /// visible to the consumer
+    fn pre_commit(&self, _data: &[u8]) {}
+
+    /// Called with the readable bytes of a read grant, right before the grant is
+    /// returned to the consumer
+    fn post_read(&self, _data: &[u8]) {}
+}
@@ -0,0 +1,199 @@
+use crate::sync::{AtomicU16, AtomicU8, AtomicUsize};
+use core::sync::atomic::Ordering;
+
+/// The unsigned integer type used to store the pointers of a [BBQueue](crate::BBQueue)
+///
+/// A queue stores four pointers into its buffer. On small targets, using an index type
+/// smaller than `usize` reduces the RAM used by each queue, at the cost of limiting its
+/// capacity to [Self::MAX] bytes.
+///
+/// ```rust
+/// # // bbqueue test shim!
+/// # fn bbqtest() {
+/// use bbqueue::{BBQueue, StaticStorageProvider};
+///
+/// // Create and split a new buffer of 64 elements, using u8 indices
+/// let buffer: BBQueue<StaticStorageProvider<64>, u8> = BBQueue::new_static();
+/// let (mut prod, mut cons) = buffer.try_split().unwrap();
+///
+/// prod.grant_exact(4).unwrap().commit(4);
+/// assert_eq!(cons.read().unwrap().len(), 4);
+/// # // bbqueue test shim!
+/// # }
+/// #
+/// # fn main() {
+/// # #[cfg(not(feature = "thumbv6"))]
+/// # bbqtest();
+/// # }
+/// ```
+///
+/// The capacity of a `static` queue is checked at compile time:
+///
+/// ```rust,compile_fail
+/// use bbqueue::{BBQueue, StaticStorageProvider};
+///
+/// // 256 bytes can't be indexed with a u8
+/// static BUF: BBQueue<StaticStorageProvider<256>, u8> = BBQueue::new_static();
+/// ```
+///
+/// This trait is sealed, and implemented for `u8`, `u16` and `usize`.
+pub trait BbqIndex: private::Sealed {
+    /// The largest capacity of a queue using this index type
+    const MAX: usize;
+
+    #[doc(hidden)]
+    type Atomic: private::AtomicIndex;
+
+    #[doc(hidden)]
+    #[cfg(not(feature = "loom"))]
+    const ZERO: Self::Atomic;
+}
+
+/// Returns a new atomic index of zero
+#[cfg(not(feature = "loom"))]
+pub(crate) const fn zero<I: BbqIndex>() -> I::Atomic {
+    I::ZERO
+}
+
+/// Returns a new atomic index of zero
+#[cfg(feature = "loom")]
+pub(crate) fn zero<I: BbqIndex>() -> I::Atomic {
+    private::AtomicIndex::new(0)
+}
+
+/// Returns a new atomic index of `N`
+#[cfg(not(feature = "loom"))]
+pub(crate) const fn from_const<I: private::ConstIndex<N>, const N: usize>() -> I::Atomic {
+    I::VALUE
+}
+
+/// Returns a new atomic index of `N`
+#[cfg(feature = "loom")]
+pub(crate) fn from_const<I: private::ConstIndex<N>, const N: usize>() -> I::Atomic {
+    private::AtomicIndex::new(N)
+}
+
+pub(crate) mod private {
+    use core::sync::atomic::Ordering;
+
+    pub trait Sealed {}
+
+    /// An index type which can create an atomic index of the constant `N`
+    ///
+    /// Implemented for every index type and every `N`. Values larger than
+    /// [super::BbqIndex::MAX] are truncated, they must be checked by the caller.
+    pub trait ConstIndex<const N: usize>: super::BbqIndex {
+        #[cfg(not(feature = "loom"))]
+        const VALUE: Self::Atomic;
+    }
+
+    /// An atomic index, accessed as a `usize`
+    ///
+    /// Values stored are never larger than the capacity of the queue, which is
+    /// checked against [super::BbqIndex::MAX] when the queue is created.
+    pub trait AtomicIndex: Send + Sync {
+        #[cfg(feature = "loom")]
+        fn new(val: usize) -> Self;
+
+        fn load(&self, order: Ordering) -> usize;
+
+        fn store(&self, val: usize, order: Ordering);
+
+        #[cfg(not(feature = "portable-cs"))]
+        fn fetch_add(&self, val: usize, order: Ordering) -> usize;
+
+        #[cfg(not(feature = "portable-cs"))]
+        fn fetch_sub(&self, val: usize, order: Ordering) -> usize;
+
+        #[cfg(not(feature = "portable-cs"))]
+        fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<usize, usize>;
+    }
+}
+
+macro_rules! impl_index {
+    ($($ty:ty => $atomic:ty),*) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl BbqIndex for $ty {
+                const MAX: usize = <$ty>::MAX as usize;
+
+                type Atomic = $atomic;
+
+                #[cfg(not(feature = "loom"))]
+                #[allow(clippy::declare_interior_mutable_const)]
+                const ZERO: $atomic = <$atomic>::new(0);
+            }
+
+            impl<const N: usize> private::ConstIndex<N> for $ty {
+                #[cfg(not(feature = "loom"))]
+                #[allow(clippy::declare_interior_mutable_const)]
+                const VALUE: $atomic = <$atomic>::new(N as $ty);
+            }
+
+            impl_atomic_index!($ty => $atomic);
+        )*
+    };
+}
+
+macro_rules! impl_atomic_index {
+    ($($ty:ty => $atomic:ty),*) => {
+        $(
+            impl private::AtomicIndex for $atomic {
+                #[cfg(feature = "loom")]
+                #[inline(always)]
+                fn new(val: usize) -> Self {
+                    <$atomic>::new(val as $ty)
+                }
+
+                #[inline(always)]
+                fn load(&self, order: Ordering) -> usize {
+                    <$atomic>::load(self, order) as usize
+                }
+
+                #[inline(always)]
+                fn store(&self, val: usize, order: Ordering) {
+                    <$atomic>::store(self, val as $ty, order)
+                }
+
+                #[cfg(not(feature = "portable-cs"))]
+                #[inline(always)]
+                fn fetch_add(&self, val: usize, order: Ordering) -> usize {
+                    <$atomic>::fetch_add(self, val as $ty, order) as usize
+                }
+
+                #[cfg(not(feature = "portable-cs"))]
+                #[inline(always)]
+                fn fetch_sub(&self, val: usize, order: Ordering) -> usize {
+                    <$atomic>::fetch_sub(self, val as $ty, order) as usize
+                }
+
+                #[cfg(not(feature = "portable-cs"))]
+                #[inline(always)]
+                fn compare_exchange(
+                    &self,
+                    current: usize,
+                    new: usize,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<usize, usize> {
+                    <$atomic>::compare_exchange(self, current as $ty, new as $ty, success, failure)
+                        .map(|v| v as usize)
+                        .map_err(|v| v as usize)
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(u8 => AtomicU8, u16 => AtomicU16, usize => AtomicUsize);
+
+// The other atomic counters of the crate are not replaced by the loom ones
+#[cfg(feature = "loom")]
+impl_atomic_index!(usize => core::sync::atomic::AtomicUsize);
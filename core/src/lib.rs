@@ -20,10 +20,10 @@
 //! ## Local usage
 //!
 //! ```rust, no_run
-//! # use bbqueue::{BBQueue, StaticBufferProvider};
+//! # use bbqueue::{BBQueue, StaticStorageProvider};
 //! #
 //! // Create a buffer with six elements
-//! let bb: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+//! let bb: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
 //! let (mut prod, mut cons) = bb.try_split().unwrap();
 //!
 //! // Request space for one byte
@@ -49,10 +49,10 @@
 //! ## Static usage
 //!
 //! ```rust, no_run
-//! # use bbqueue::{BBQueue, StaticBufferProvider};
+//! # use bbqueue::{BBQueue, StaticStorageProvider};
 //! #
 //! // Create a ststic buffer with six elements
-//! static BB: BBQueue<StaticBufferProvider<6>> = BBQueue::new_static();
+//! static BB: BBQueue<StaticStorageProvider<6>> = BBQueue::new_static();
 //!
 //! fn main() {
 //!     // Split the bbqueue into producer and consumer halves.
@@ -123,29 +123,152 @@
 //! }
 //! ```
 //!
+//! ## Async
+//!
+//! The `_async` methods, such as [Producer::grant_exact_async] and [Consumer::read_async],
+//! return futures which wait for space or data to become available, and can be awaited
+//! from any executor.
+//!
+//! These futures are cancellation safe. A grant is only taken from the queue in the poll that
+//! completes the future, and nothing is held between polls, so a pending future can be dropped
+//! without leaking a grant or leaving a grant flagged as in progress. This makes it possible to
+//! race them against a timer with a `select`, to give up waiting after a deadline, which
+//! [Producer::grant_exact_timeout] does for write grants. The queue can be used again,
+//! synchronously or asynchronously, as soon as the future is dropped.
+//!
+//! To shut a pipeline down, the producer can be closed with [Producer::close]. The reading futures
+//! then return [Error::Closed] once the bytes committed before closing can't satisfy them, rather
+//! than waiting forever.
+//!
+//! The async API is only available with the `async` feature, which is enabled by default. Without
+//! it, the queue doesn't store the wakers and the commits and releases don't try to wake anyone,
+//! which saves a few words of memory and cycles for purely synchronous applications.
+//!
+//! The `async` feature stores the wakers in an [AtomicWaker](https://docs.rs/atomic-waker). The
+//! `cs-waker` feature enables the async API with the wakers protected by critical sections instead,
+//! see the [Features](#features) section, which doesn't require the `atomic-waker` crate nor atomic
+//! read-modify-write operations. It takes precedence over `async` if both are enabled. The
+//! `embedded-io-async` and `futures` features require one of them.
+//!
 //! ## Features
 //!
 //! By default BBQueue uses atomic operations which are available on most platforms. However on some
 //! (mostly embedded) platforms atomic support is limited and with the default features you will get
 //! a compiler error about missing atomic methods.
 //!
-//! This crate contains special support for Cortex-M0(+) targets with the `thumbv6` feature. By
-//! enabling the feature, unsupported atomic operations will be replaced with critical sections
-//! implemented by disabling interrupts. The critical sections are very short, a few instructions at
-//! most, so they should make no difference to most applications.
+//! This crate contains special support for targets without atomic read-modify-write operations,
+//! such as Cortex-M0(+) or RISC-V cores without the `A` extension, with the `portable-cs` feature.
+//! By enabling the feature, unsupported atomic operations will be replaced with critical sections
+//! provided by the [critical-section] crate, which usually disable interrupts. The critical
+//! sections are very short, a few instructions at most, so they should make no difference to
+//! most applications. The wakers of the async API use the same critical sections.
+//!
+//! The application must provide a critical section implementation, for example by enabling the
+//! `critical-section-single-core` feature of the `cortex-m` crate on single core Cortex-M
+//! targets. The `thumbv6` feature is an alias of `portable-cs`, kept for backwards compatibility.
+//!
+//! [critical-section]: https://docs.rs/critical-section
+//!
+//! The `std` feature implements [std::io::Write] for the [Producer] and [std::io::Read] for
+//! the [Consumer], which makes it possible to use the queue with the standard library I/O
+//! ecosystem. As the queue never blocks, an [std::io::ErrorKind::WouldBlock] error is returned
+//! when the operation can't make progress. It also provides [BBQueue::try_split_arc], which
+//! splits a queue shared through an `Arc` into owned halves that can be moved into spawned
+//! threads without a `static`.
+//!
+//! The `embedded-io` feature implements the [embedded-io] `Write` and `Read` traits for the
+//! [Producer] and [Consumer], for use in `no_std` byte pipelines. As the queue never blocks,
+//! writing to a full queue returns `IoError::Full`, of kind `WriteZero`, and reading from an
+//! empty queue returns `IoError::WouldBlock`.
+//!
+//! The `embedded-io-async` feature additionally implements the [embedded-io-async] `Write` and
+//! `Read` traits, which wait for space or data to become available using the same wakers as
+//! [Producer::grant_max_remaining_async] and [Consumer::read_async].
+//!
+//! The `futures` feature implements the [futures] `Stream` trait for the [framed::FrameConsumer],
+//! yielding each frame as it is committed, and provides a `Sink` of frames with
+//! [framed::FrameProducer::sink].
+//!
+//! The `defmt-03` feature implements [defmt] `Format` for the [Error] type, the grants, including
+//! the framed and typed ones, and the [BBQueue] itself, which prints a snapshot of the queue
+//! pointers and flags. The [BBQueue] `Debug` implementation prints the same snapshot.
+//!
+//! The `metrics` feature tracks the maximum number of occupied bytes the queue has reached,
+//! see [BBQueue::high_water_mark]. It also counts the bytes committed and released, and the
+//! write grants given or denied, see [BBQueue::stats]. This costs a few extra atomic operations
+//! on each grant, commit and release. The `stats` feature is an alias of `metrics`.
+//!
+//! The `hooks` feature allows running code right before committed bytes become visible to
+//! the consumer, and right after bytes are handed out to the consumer, see [BbqHooks]. This
+//! is useful for cache maintenance around DMA transfers. This costs one pointer in the
+//! [BBQueue], and a check on each commit and read.
+//!
+//! The `split-grants` feature provides `GrantW::split_at`, which divides a write grant into
+//! two grants that can be committed independently, for instance a header and a body. This
+//! costs three words in the [BBQueue], and a few words in each [GrantW].
+//!
+//! The `log` feature provides [logger::BBQueueLogger], a [log] sink writing one frame per
+//! record to a framed queue, which is useful for deferred logging from interrupt handlers.
+//!
+//! The `loom` feature replaces the atomics of the queue with the [loom] ones, to model check
+//! the producer/consumer handoff. It is only meant for testing this crate: the `const`
+//! constructors are not `const` with this feature.
+//!
+//! [defmt]: https://docs.rs/defmt
+//!
+//! [log]: https://docs.rs/log
+//!
+//! [loom]: https://docs.rs/loom
+//!
+//! [embedded-io-async]: https://docs.rs/embedded-io-async
+//!
+//! [futures]: https://docs.rs/futures
+//!
+//! [embedded-io]: https://docs.rs/embedded-io
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 // #![deny(warnings)]
 
+mod sync;
+
 mod bbqueue;
 pub use crate::bbqueue::*;
 
 mod storage_provider;
 pub use storage_provider::*;
 
+mod index;
+pub use index::BbqIndex;
+
+pub mod broadcast;
 pub mod framed;
+#[cfg(feature = "hooks")]
+mod hooks;
+#[cfg(feature = "hooks")]
+pub use hooks::BbqHooks;
+#[cfg(feature = "log")]
+pub mod logger;
+pub mod multi;
+pub mod typed;
 mod vusize;
+#[cfg(feature = "async-api")]
+mod waker;
+mod writer;
+pub use writer::GrantWriter;
+
+#[cfg(feature = "std")]
+mod std_io;
+
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(feature = "std")]
+pub use owned::{ConsumerOwned, ProducerOwned};
+
+#[cfg(feature = "embedded-io")]
+mod embedded_io_impl;
+#[cfg(feature = "embedded-io")]
+pub use embedded_io_impl::IoError;
 
 use core::result::Result as CoreResult;
 
@@ -153,16 +276,83 @@ use core::result::Result as CoreResult;
 pub type Result<T> = CoreResult<T, Error>;
 
 /// Error type used by the `BBQueue` interfaces
+///
+/// More variants may be added in the future, so matches on it must include a
+/// wildcard arm.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
 pub enum Error {
     /// The buffer does not contain sufficient size for the requested action
-    InsufficientSize,
+    ///
+    /// The request may succeed later. Grants and reads always need at least one byte,
+    /// so `requested` is `1` for requests without a size, such as [Consumer::read].
+    InsufficientSize {
+        /// The number of bytes requested
+        requested: usize,
+        /// The number of bytes available to the request when it was made, such as
+        /// the largest contiguous grant that could be given instead
+        available: usize,
+    },
+
+    /// The requested size is larger than the capacity of the queue, so the
+    /// request can never succeed, no matter how many bytes are released
+    RequestTooLarge,
 
     /// Unable to produce another grant, a grant of this type is already in
     /// progress
+    ///
+    /// Grants are tied to the lifetime of the queue rather than to a borrow of
+    /// the [Producer] or [Consumer], so this is returned while a previous grant
+    /// is still alive. A grant leaked with [core::mem::forget] is never committed
+    /// or released, and every later request will fail with this error.
     GrantInProgress,
 
     /// Unable to split the buffer, as it has already been split
     AlreadySplit,
+
+    /// The storage is not suitably aligned for the requested element type,
+    /// or for the requested grant alignment
+    Misaligned,
+
+    /// The capacity of the queue is too small for the requested mode, such as
+    /// a framed queue which can't hold a single frame of one byte
+    CapacityTooSmall,
+
+    /// The requested element type is zero sized, which can't be passed through
+    /// a typed queue, see [BBQueue::try_split_typed]
+    ZeroSizedElement,
+
+    /// The timer given to an async request completed before the request, see
+    /// [Producer::grant_exact_timeout]
+    Timeout,
+
+    /// The producer closed the queue, and all the bytes committed before closing
+    /// were released, see [Producer::close]
+    Closed,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InsufficientSize {
+                requested,
+                available,
+            } => write!(
+                f,
+                "insufficient size: {requested} bytes requested, {available} available"
+            ),
+            Error::RequestTooLarge => f.write_str("request too large"),
+            Error::GrantInProgress => f.write_str("grant in progress"),
+            Error::AlreadySplit => f.write_str("already split"),
+            Error::Misaligned => f.write_str("misaligned storage"),
+            Error::CapacityTooSmall => f.write_str("capacity too small"),
+            Error::ZeroSizedElement => f.write_str("zero sized element"),
+            Error::Timeout => f.write_str("timed out"),
+            Error::Closed => f.write_str("closed"),
+        }
+    }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,196 @@
+//! A [log] sink writing records to a framed BBQueue, enabled with the `log` feature
+//!
+//! Formatting and storing a log record is cheap compared to sending it over a slow
+//! transport, such as a UART. The [BBQueueLogger] writes each record as one frame in
+//! a [framed](crate::framed) queue, which can be called from any thread or interrupt
+//! handler, and the frames are later sent out from the main loop with [drain].
+//!
+//! Logging never blocks. If the queue doesn't have room for a record, or if another
+//! record is being written at the same time, the record is dropped and counted, see
+//! [BBQueueLogger::dropped].
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::{logger::{drain, BBQueueLogger}, BBQueue, StaticStorageProvider};
+//!
+//! static BB: BBQueue<StaticStorageProvider<256>> = BBQueue::new_static();
+//!
+//! let (prod, mut cons) = BB.try_split_framed().unwrap();
+//!
+//! // The logger must live forever to be installed
+//! let logger: &'static BBQueueLogger<_> = Box::leak(Box::new(BBQueueLogger::new(prod, 64)));
+//! log::set_logger(logger).unwrap();
+//! log::set_max_level(log::LevelFilter::Info);
+//!
+//! log::info!(target: "app", "hello {}", 42);
+//!
+//! // Pump the records out, in the main loop
+//! let mut records = Vec::new();
+//! drain(&mut cons, |record| records.push(record.to_vec()));
+//! assert_eq!(records, [b"INFO app: hello 42".to_vec()]);
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
+//!
+//! [log]: https://docs.rs/log
+
+use crate::{
+    bbqueue::atomic,
+    framed::{FrameConsumer, FrameProducer},
+    BBQueue, BbqIndex, Producer, StorageProvider,
+};
+use core::{
+    fmt::{self, Write},
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A [log::Log] implementation writing one frame per record to a BBQueue
+///
+/// Each record is formatted as `LEVEL target: message`. Records longer than the
+/// maximum record length given to [Self::new] are truncated.
+pub struct BBQueueLogger<'a, B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    bbq: NonNull<BBQueue<B, I>>,
+    pd: PhantomData<&'a ()>,
+    max_len: usize,
+    dropped: AtomicUsize,
+}
+
+// The logger owns the only producer of the queue. A temporary producer is created
+// for each record, and only one of them can hold a write grant at any time, as
+// checked by the queue.
+unsafe impl<'a, B, I> Send for BBQueueLogger<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+unsafe impl<'a, B, I> Sync for BBQueueLogger<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+impl<'a, B, I> BBQueueLogger<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Create a new logger, taking ownership of the producer of a framed queue.
+    ///
+    /// `max_len` is the maximum length of a record, in bytes, excluding the frame
+    /// header. A frame of this size is granted for each record, so the queue must have
+    /// at least [FrameProducer::frame_len] of `max_len` contiguous free bytes for the
+    /// record to be stored.
+    pub fn new(prod: FrameProducer<'a, B, I>, max_len: usize) -> Self {
+        Self {
+            bbq: prod.producer.bbq,
+            pd: PhantomData,
+            max_len,
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of records dropped, because the queue was full or another
+    /// record was being written at the same time.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn producer(&self) -> FrameProducer<'a, B, I> {
+        FrameProducer {
+            producer: Producer {
+                bbq: self.bbq,
+                pd: PhantomData,
+                reborrowed: false,
+            },
+        }
+    }
+}
+
+impl<'a, B, I> log::Log for BBQueueLogger<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let mut grant = match self.producer().grant(self.max_len) {
+            Ok(grant) => grant,
+            Err(_) => {
+                atomic::fetch_add(&self.dropped, 1, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        let mut writer = SliceWriter {
+            buf: &mut grant,
+            pos: 0,
+        };
+        // An error means the record was truncated, keep what fits
+        let _ = write!(
+            writer,
+            "{} {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let used = writer.pos;
+        grant.commit(used);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Release all the records available in the queue, calling `f` with each of them.
+///
+/// Returns the number of records drained.
+pub fn drain<B, I>(cons: &mut FrameConsumer<'_, B, I>, mut f: impl FnMut(&[u8])) -> usize
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    let mut count = 0;
+    for frame in cons.frames() {
+        f(&frame);
+        count += 1;
+    }
+    count
+}
+
+/// Writes formatted text to a slice, failing once the slice is full
+struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    pos: usize,
+}
+
+impl<'b> Write for SliceWriter<'b> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = &mut self.buf[self.pos..];
+        let len = s.len().min(remaining.len());
+        remaining[..len].copy_from_slice(&s.as_bytes()[..len]);
+        self.pos += len;
+
+        if len < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
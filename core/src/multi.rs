@@ -0,0 +1,534 @@
+//! A Multi-producer flavor of BBQueue, allowing multiple writers to share a stream
+//!
+//! This module provides a [MultiBBQueue], which may be split into a [SharedProducer]
+//! and a regular [Consumer]. The [SharedProducer] can be cloned, for example to push
+//! into the same byte stream from several interrupt handlers.
+//!
+//! Each producer reserves its own region of the queue with a compare-and-swap, so
+//! producers never wait for each other. Up to `S` write grants may be active at the
+//! same time, and they may be committed in any order: the bytes of a grant are only
+//! made available to the consumer once all the grants reserved before it have been
+//! committed, so the consumer always sees the grants in the order they were
+//! reserved.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::{multi::MultiBBQueue, StaticStorageProvider};
+//!
+//! // Create a queue allowing two active write grants
+//! let bb: MultiBBQueue<StaticStorageProvider<8>, 2> = MultiBBQueue::new_static();
+//! let (mut prod_a, mut cons) = bb.try_split_multi().unwrap();
+//! let mut prod_b = prod_a.clone();
+//!
+//! let mut wgr_a = prod_a.grant_exact(2).unwrap();
+//! let mut wgr_b = prod_b.grant_exact(3).unwrap();
+//! wgr_a.copy_from_slice(&[1, 2]);
+//! wgr_b.copy_from_slice(&[3, 4, 5]);
+//!
+//! // The second grant is committed first, but isn't readable until the
+//! // first one is committed
+//! wgr_b.commit(3);
+//! assert!(cons.read().is_err());
+//! wgr_a.commit(2);
+//!
+//! let rgr = cons.read().unwrap();
+//! assert_eq!(&*rgr, &[1, 2, 3, 4, 5]);
+//! rgr.release(5);
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
+//!
+//! ## Limitations
+//!
+//! The queue can't contain holes, so a grant which is committed partially, or
+//! dropped without being committed, only gives the unused bytes back if no other
+//! grant was reserved after it. Otherwise the unused bytes are zeroed, and
+//! committed along with the grant. [SharedGrantW::commit] returns the number of
+//! zeroed bytes, so they can be told apart from real data, for example by a framing
+//! layer on top of the stream.
+//!
+//! A split `MultiBBQueue` can't be released, and the [SharedProducer] doesn't
+//! currently support async grants.
+
+use crate::{
+    bbqueue::atomic, sync::AtomicUsize, BBQueue, Consumer, Error, Result, SliceStorageProvider,
+    StaticStorageProvider, StorageProvider,
+};
+use core::{
+    cmp::min,
+    marker::PhantomData,
+    mem::forget,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    slice::from_raw_parts_mut,
+    sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release},
+};
+
+// States of a grant slot
+const FREE: usize = 0;
+const RESERVED: usize = 1;
+const COMMITTED: usize = 2;
+
+/// A backing structure for a BBQueue with multiple producers, allowing up to `S`
+/// active write grants. Can be split into a [SharedProducer] and a [Consumer]
+#[derive(Debug)]
+pub struct MultiBBQueue<B, const S: usize>
+where
+    B: StorageProvider,
+{
+    // The underlying queue. Its `reserve` pointer is the end of the last
+    // reserved grant, and its `write` pointer the end of the last grant that
+    // was made available to the consumer
+    bbq: BBQueue<B>,
+
+    // The active write grants
+    slots: [Slot; S],
+
+    // The number of commits not yet handled by the producer currently moving
+    // the write pointer. Whoever increments it from zero becomes that producer
+    publish_requests: AtomicUsize,
+}
+
+/// An active write grant
+#[derive(Debug)]
+struct Slot {
+    // FREE, RESERVED or COMMITTED
+    state: AtomicUsize,
+
+    // The end of the previous grant, where `write` will be when this grant
+    // is the next one to be made available to the consumer
+    prev_end: AtomicUsize,
+
+    // The start of the grant, either `prev_end` or 0 if it wraps around
+    start: AtomicUsize,
+
+    // The end of the grant, once committed
+    end: AtomicUsize,
+}
+
+impl Slot {
+    #[cfg(not(feature = "loom"))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Self = Self {
+        state: AtomicUsize::new(FREE),
+        prev_end: AtomicUsize::new(0),
+        start: AtomicUsize::new(0),
+        end: AtomicUsize::new(0),
+    };
+
+    fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(FREE),
+            prev_end: AtomicUsize::new(0),
+            start: AtomicUsize::new(0),
+            end: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<B, const S: usize> MultiBBQueue<B, S>
+where
+    B: StorageProvider,
+{
+    /// Create a new MultiBBQueue with abstraction over the memory provider
+    pub fn new(buf: B) -> Self {
+        Self {
+            bbq: BBQueue::new(buf),
+            slots: core::array::from_fn(|_| Slot::new()),
+            publish_requests: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the size of the backing storage.
+    ///
+    /// See [BBQueue::capacity] for details.
+    pub const fn capacity(&self) -> usize {
+        self.bbq.capacity()
+    }
+}
+
+impl<const N: usize, const S: usize> MultiBBQueue<StaticStorageProvider<N>, S> {
+    /// Create a new constant static MultiBBQueue, using static memory allocation
+    #[cfg(not(feature = "loom"))]
+    pub const fn new_static() -> Self {
+        Self {
+            bbq: BBQueue::new_static(),
+            slots: [Slot::NEW; S],
+            publish_requests: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a new MultiBBQueue, using static memory allocation
+    #[cfg(feature = "loom")]
+    pub fn new_static() -> Self {
+        Self::new(StaticStorageProvider::new())
+    }
+}
+
+impl<'a, const S: usize> MultiBBQueue<SliceStorageProvider<'a>, S> {
+    /// Create a new MultiBBQueue using userspace provided memory in the form of a slice.
+    pub fn new_from_slice(buf: &'a mut [u8]) -> Self {
+        Self::new(SliceStorageProvider::new(buf))
+    }
+}
+
+impl<'a, B, const S: usize> MultiBBQueue<B, S>
+where
+    B: StorageProvider,
+{
+    /// Attempt to split the `MultiBBQueue` into a `SharedProducer` and a `Consumer`
+    /// to gain access to the buffer. If buffer has already been split, an error will
+    /// be returned.
+    ///
+    /// See [BBQueue::try_split] for details on initialization of the buffer.
+    pub fn try_split_multi(&'a self) -> Result<(SharedProducer<'a, B, S>, Consumer<'a, B>)> {
+        // The single producer is never used, all writes are
+        // performed through the shared producers
+        let (_, consumer) = self.bbq.try_split()?;
+
+        // The shared producers don't take the role of the single producer, mark
+        // it as always in use, so the consumer never rewinds the pointers under
+        // a reserved grant, see `Consumer::flush`
+        self.bbq.write_in_progress.store(true, Release);
+
+        let producer = SharedProducer {
+            bbq: NonNull::from(self),
+            pd: PhantomData,
+        };
+
+        Ok((producer, consumer))
+    }
+
+    /// Make the committed grants available to the consumer, in the order they
+    /// were reserved, up to the first grant which isn't committed yet.
+    fn publish(&self) {
+        let inner = &self.bbq;
+        let mut published = 0;
+
+        if atomic::fetch_add(&self.publish_requests, 1, AcqRel) != 0 {
+            // Someone else is currently moving the write pointer, and
+            // will pick up our grant before they are done
+            return;
+        }
+
+        let mut requests = 1;
+        loop {
+            // Only the publisher frees committed slots, so they can't
+            // change under our feet
+            while let Some(slot) = self.slots.iter().find(|slot| {
+                slot.state.load(Acquire) == COMMITTED
+                    && slot.prev_end.load(Relaxed) == inner.write.load(Acquire)
+            }) {
+                let write = inner.write.load(Acquire);
+                let new_write = slot.end.load(Relaxed);
+                let max = inner.capacity();
+                let last = inner.last.load(Acquire);
+
+                // Same as `GrantW::commit`
+                if (new_write < write) && (write != max) {
+                    // Wrapped around, skipping some bytes at the end of the ring
+                    inner.last.store(write, Release);
+                } else if new_write > last {
+                    // Passed a previously skipped end of the ring
+                    inner.last.store(max, Release);
+                }
+
+                // Write must be updated AFTER last, otherwise read could think it was
+                // time to invert early!
+                inner.write.store(new_write, Release);
+
+                published += new_write - slot.start.load(Relaxed);
+                slot.state.store(FREE, Release);
+            }
+
+            // Stop publishing, unless a grant was committed in the meantime
+            let prev = atomic::fetch_sub(&self.publish_requests, requests, AcqRel);
+            if prev == requests {
+                break;
+            }
+            requests = prev - requests;
+        }
+
+        inner.committed(published);
+    }
+}
+
+/// `SharedProducer` is a cloneable writer of a `MultiBBQueue`.
+pub struct SharedProducer<'a, B, const S: usize>
+where
+    B: StorageProvider,
+{
+    bbq: NonNull<MultiBBQueue<B, S>>,
+    pd: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, B, const S: usize> Send for SharedProducer<'a, B, S> where B: StorageProvider {}
+
+impl<'a, B, const S: usize> Clone for SharedProducer<'a, B, S>
+where
+    B: StorageProvider,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bbq: self.bbq,
+            pd: PhantomData,
+        }
+    }
+}
+
+impl<'a, B, const S: usize> SharedProducer<'a, B, S>
+where
+    B: StorageProvider,
+{
+    /// Request a writable, contiguous section of memory of exactly
+    /// `sz` bytes. If the buffer size requested is not available,
+    /// an error will be returned.
+    ///
+    /// An [Error::GrantInProgress] is returned if `S` grants are already active.
+    ///
+    /// This method may wrap around the ring to obtain a contiguous grant,
+    /// like [Producer::grant_exact](crate::Producer::grant_exact).
+    pub fn grant_exact(&mut self, sz: usize) -> Result<SharedGrantW<'a, B, S>> {
+        let res = self.grant_exact_inner(sz);
+
+        #[cfg(feature = "metrics")]
+        unsafe { self.bbq.as_ref() }.bbq.counters.record_grant(&res);
+
+        res
+    }
+
+    fn grant_exact_inner(&mut self, sz: usize) -> Result<SharedGrantW<'a, B, S>> {
+        let bq = unsafe { self.bbq.as_ref() };
+        let inner = &bq.bbq;
+        let max = inner.capacity();
+
+        // This is sound, as UnsafeCell, MaybeUninit, and GenericArray
+        // are all `#[repr(Transparent)]
+        let start_of_buf_ptr = unsafe { (*inner.buf.get()).storage().as_ptr() as *mut u8 };
+
+        if sz == 0 {
+            // Nothing to reserve or commit
+            return Ok(SharedGrantW {
+                buf: unsafe { from_raw_parts_mut(start_of_buf_ptr, 0) }.into(),
+                bbq: self.bbq,
+                slot: None,
+                to_commit: 0,
+                pd: PhantomData,
+            });
+        }
+
+        if sz > max {
+            return Err(Error::RequestTooLarge);
+        }
+
+        let idx = bq
+            .slots
+            .iter()
+            .position(|slot| {
+                atomic::compare_exchange(&slot.state, FREE, RESERVED, AcqRel, Relaxed).is_ok()
+            })
+            .ok_or(Error::GrantInProgress)?;
+        let slot = &bq.slots[idx];
+
+        // Same as `Producer::grant_exact`, but starting from the end of the
+        // last reserved grant rather than from the write pointer
+        let mut reserve = inner.reserve.load(Acquire);
+        let start = loop {
+            let read = inner.read.load(Acquire);
+
+            let start = if reserve < read {
+                // Inverted, is room still available?
+                (reserve + sz < read).then_some(reserve)
+            } else if reserve + sz <= max {
+                // Non inverted condition
+                Some(reserve)
+            } else {
+                // Not inverted, but need to go inverted. As for `Producer::grant_exact`,
+                // we check sz < read, NOT <=, so write never == read when inverted
+                (sz < read).then_some(0)
+            };
+
+            let Some(start) = start else {
+                // The read pointer may have been loaded after another producer
+                // moved `reserve`, only give up if our view of it is up to date
+                if let Err(current) =
+                    atomic::compare_exchange(&inner.reserve, reserve, reserve, AcqRel, Acquire)
+                {
+                    reserve = current;
+                    continue;
+                }
+
+                slot.state.store(FREE, Release);
+                let available = if reserve < read {
+                    read - reserve - 1
+                } else {
+                    (max - reserve).max(read.saturating_sub(1))
+                };
+                return Err(Error::InsufficientSize {
+                    requested: sz,
+                    available,
+                });
+            };
+
+            match atomic::compare_exchange(&inner.reserve, reserve, start + sz, AcqRel, Acquire) {
+                Ok(_) => break start,
+                // Another producer reserved a grant, try again after it
+                Err(current) => reserve = current,
+            }
+        };
+
+        // Only read by the publisher once the slot is committed
+        slot.prev_end.store(reserve, Relaxed);
+        slot.start.store(start, Relaxed);
+
+        let grant_slice = unsafe { from_raw_parts_mut(start_of_buf_ptr.add(start), sz) };
+
+        Ok(SharedGrantW {
+            buf: grant_slice.into(),
+            bbq: self.bbq,
+            slot: Some(idx),
+            to_commit: 0,
+            pd: PhantomData,
+        })
+    }
+}
+
+/// A write grant of a `SharedProducer`
+///
+/// NOTE: If the grant is dropped without explicitly commiting
+/// the contents, or without first calling `to_commit()`, then no
+/// bytes will be comitted for writing, see the [module level
+/// documentation](crate::multi) for details.
+#[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+#[derive(Debug, PartialEq)]
+pub struct SharedGrantW<'a, B, const S: usize>
+where
+    B: StorageProvider,
+{
+    buf: NonNull<[u8]>,
+    bbq: NonNull<MultiBBQueue<B, S>>,
+    slot: Option<usize>,
+    to_commit: usize,
+    pd: PhantomData<&'a mut [u8]>,
+}
+
+unsafe impl<'a, B, const S: usize> Send for SharedGrantW<'a, B, S> where B: StorageProvider {}
+
+impl<'a, B, const S: usize> SharedGrantW<'a, B, S>
+where
+    B: StorageProvider,
+{
+    /// Finalizes a writable grant given by `grant_exact()`.
+    /// This makes the data available to be read, once all the grants
+    /// reserved before this one have been committed.
+    ///
+    /// If `used` is larger than the given grant, the maximum amount will
+    /// be commited. If `used` is smaller than the given grant and another
+    /// grant was reserved after this one, the unused bytes are zeroed and
+    /// committed as well, see the [module level documentation](crate::multi).
+    ///
+    /// Returns the number of zeroed bytes committed after the `used` bytes,
+    /// which the consumer will read like any other data.
+    pub fn commit(mut self, used: usize) -> usize {
+        let padded = self.commit_inner(used);
+        forget(self);
+        padded
+    }
+
+    /// Obtain access to the inner buffer for writing
+    pub fn buf(&mut self) -> &mut [u8] {
+        unsafe { self.buf.as_mut() }
+    }
+
+    /// Configures the amount of bytes to be commited on drop.
+    ///
+    /// Like [Self::commit], the unused bytes are zeroed and committed if another
+    /// grant was reserved after this one. Use [Self::commit] to know how many.
+    pub fn to_commit(&mut self, amt: usize) {
+        self.to_commit = self.buf.len().min(amt);
+    }
+
+    /// Configures the full grant to be commited on drop.
+    pub fn to_commit_all(&mut self) {
+        self.to_commit = self.buf.len();
+    }
+
+    /// Returns the number of zeroed bytes committed
+    fn commit_inner(&mut self, used: usize) -> usize {
+        // Empty grants don't use a slot
+        let Some(idx) = self.slot.take() else {
+            return 0;
+        };
+
+        let bq = unsafe { self.bbq.as_ref() };
+        let inner = &bq.bbq;
+        let slot = &bq.slots[idx];
+
+        // Saturate the grant commit
+        let len = self.buf.len();
+        let used = min(len, used);
+        let start = slot.start.load(Relaxed);
+        let mut end = start + len;
+
+        if used < len {
+            // Give the unused bytes back, if no other grant was reserved after this one
+            if atomic::compare_exchange(&inner.reserve, end, start + used, AcqRel, Relaxed).is_ok()
+            {
+                end = start + used;
+            } else {
+                // The queue can't contain holes, commit the unused bytes zeroed
+                self.buf()[used..].fill(0);
+            }
+        }
+
+        if end == slot.prev_end.load(Relaxed) {
+            // Nothing was committed, and the reservation was undone
+            slot.state.store(FREE, Release);
+            return 0;
+        }
+
+        inner.pre_commit(&self.buf()[..end - start]);
+        slot.end.store(end, Relaxed);
+        slot.state.store(COMMITTED, Release);
+
+        bq.publish();
+        end - start - used
+    }
+}
+
+impl<'a, B, const S: usize> Drop for SharedGrantW<'a, B, S>
+where
+    B: StorageProvider,
+{
+    fn drop(&mut self) {
+        self.commit_inner(self.to_commit);
+    }
+}
+
+impl<'a, B, const S: usize> Deref for SharedGrantW<'a, B, S>
+where
+    B: StorageProvider,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.buf.as_ref() }
+    }
+}
+
+impl<'a, B, const S: usize> DerefMut for SharedGrantW<'a, B, S>
+where
+    B: StorageProvider,
+{
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { self.buf.as_mut() }
+    }
+}
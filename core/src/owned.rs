@@ -0,0 +1,387 @@
+//! Owned producer and consumer halves, enabled with the `std` feature
+//!
+//! The [Producer] and [Consumer] halves borrow the [BBQueue] they were split
+//! from, which means the queue must usually be placed in a `static` to move
+//! the halves into spawned threads. The owned halves instead keep the queue
+//! alive through an [Arc], and can be moved freely.
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::{BBQueue, StaticStorageProvider};
+//! use std::{sync::Arc, thread::spawn};
+//!
+//! let bb: Arc<BBQueue<StaticStorageProvider<16>>> = Arc::new(BBQueue::new_static());
+//! let (mut prod, mut cons) = bb.try_split_arc().unwrap();
+//!
+//! spawn(move || {
+//!     prod.send_slice(&[1, 2, 3]).unwrap();
+//! })
+//! .join()
+//! .unwrap();
+//!
+//! let grant = cons.read().unwrap();
+//! assert_eq!(&*grant, &[1, 2, 3]);
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
+
+use crate::{
+    bbqueue::atomic, BBQueue, BbqIndex, Consumer, GrantR, GrantW, Producer, Result, SplitGrantR,
+    SplitGrantW, StorageProvider,
+};
+#[cfg(feature = "async-api")]
+use core::future::Future;
+use core::{
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicBool,
+        Ordering::{AcqRel, Acquire},
+    },
+};
+use std::sync::Arc;
+
+impl<B, I> BBQueue<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// Attempt to split a shared `BBQueue` into owned `ConsumerOwned` and `ProducerOwned`
+    /// halves. If buffer has already been split, an error will be returned.
+    ///
+    /// Each half keeps the queue alive, and the queue may be split again once both
+    /// halves have been dropped.
+    ///
+    /// See [Self::try_split] for details on initialization of the buffer.
+    pub fn try_split_arc(self: Arc<Self>) -> Result<(ProducerOwned<B, I>, ConsumerOwned<B, I>)> {
+        // We only need to mark the queue as split here, the borrowed halves
+        // are recreated on demand by the owned halves
+        let _ = self.try_split()?;
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        Ok((
+            ProducerOwned {
+                bbq: self.clone(),
+                dropped: dropped.clone(),
+            },
+            ConsumerOwned { bbq: self, dropped },
+        ))
+    }
+
+    /// Called when one of the owned halves is dropped. The last half to be
+    /// dropped releases the queue, so it may be split again.
+    fn release_owned(&self, dropped: &AtomicBool) {
+        if !atomic::swap(dropped, true, AcqRel) {
+            // The other half is still alive
+            return;
+        }
+
+        let prod = Producer {
+            bbq: NonNull::from(self),
+            pd: PhantomData,
+            reborrowed: false,
+        };
+        let cons = Consumer {
+            bbq: NonNull::from(self),
+            pd: PhantomData,
+            reborrowed: false,
+        };
+
+        // Dropping both halves discards the unread bytes. This can only
+        // fail if a grant was leaked, in which case the queue remains split
+        let _ = self.release_inner(prod, cons, true);
+    }
+}
+
+/// An owned version of the [Producer], which keeps the [BBQueue] alive
+///
+/// Grants borrow the `ProducerOwned` they were obtained from, see the
+/// [Producer] for details on each method.
+pub struct ProducerOwned<B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    bbq: Arc<BBQueue<B, I>>,
+    dropped: Arc<AtomicBool>,
+}
+
+unsafe impl<B, I> Send for ProducerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+impl<B, I> ProducerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// A producer whose grants can not outlive the borrow of `self`
+    fn producer(&mut self) -> Producer<'_, B, I> {
+        Producer {
+            bbq: NonNull::from(&*self.bbq),
+            pd: PhantomData,
+            reborrowed: true,
+        }
+    }
+
+    /// See [Producer::grant_exact]
+    pub fn grant_exact(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact(sz)
+    }
+
+    /// See [Producer::grant_exact_aligned]
+    pub fn grant_exact_aligned(&mut self, sz: usize, align: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_aligned(sz, align)
+    }
+
+    /// See [Producer::grant_exact_no_wrap]
+    pub fn grant_exact_no_wrap(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_no_wrap(sz)
+    }
+
+    /// See [Producer::try_grant_exact]
+    #[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+    pub fn try_grant_exact(&mut self, sz: usize) -> Option<GrantW<'_, B, I>> {
+        self.producer().try_grant_exact(sz)
+    }
+
+    /// See [Producer::grant_exact_zeroed]
+    pub fn grant_exact_zeroed(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_zeroed(sz)
+    }
+
+    /// See [Producer::grant_max_remaining]
+    pub fn grant_max_remaining(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_max_remaining(sz)
+    }
+
+    /// See [Producer::grant_largest]
+    pub fn grant_largest(&mut self) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_largest()
+    }
+
+    /// See [Producer::grant_split_remaining]
+    pub fn grant_split_remaining(&mut self, sz: usize) -> Result<SplitGrantW<'_, B, I>> {
+        self.producer().grant_split_remaining(sz)
+    }
+
+    /// See [Producer::grant_exact_spin]
+    pub fn grant_exact_spin(&mut self, sz: usize, spin: impl FnMut()) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_spin(sz, spin)
+    }
+
+    /// See [Producer::grant_exact_async]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_exact_async(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_async(sz).await
+    }
+
+    /// See [Producer::grant_exact_timeout]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_exact_timeout<T: Future>(
+        &mut self,
+        sz: usize,
+        timer: T,
+    ) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_exact_timeout(sz, timer).await
+    }
+
+    /// See [Producer::grant_max_remaining_async]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_max_remaining_async(&mut self, sz: usize) -> Result<GrantW<'_, B, I>> {
+        self.producer().grant_max_remaining_async(sz).await
+    }
+
+    /// See [Producer::push_slice]
+    pub fn push_slice(&mut self, data: &[u8]) -> Result<usize> {
+        self.producer().push_slice(data)
+    }
+
+    /// See [Producer::push_slice_all]
+    pub fn push_slice_all(&mut self, data: &[u8]) -> Result<()> {
+        self.producer().push_slice_all(data)
+    }
+
+    /// See [Producer::send_slice]
+    pub fn send_slice(&mut self, data: &[u8]) -> Result<usize> {
+        self.producer().send_slice(data)
+    }
+
+    /// See [Producer::space_remaining]
+    pub fn space_remaining(&self) -> usize {
+        self.bbq.free_len()
+    }
+
+    /// See [Producer::close]
+    ///
+    /// The queue is still released once the consumer is dropped too.
+    pub fn close(mut self) {
+        self.producer().close_inner()
+    }
+}
+
+impl<B, I> Drop for ProducerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        self.bbq.release_owned(&self.dropped);
+    }
+}
+
+/// An owned version of the [Consumer], which keeps the [BBQueue] alive
+///
+/// Grants borrow the `ConsumerOwned` they were obtained from, see the
+/// [Consumer] for details on each method.
+pub struct ConsumerOwned<B, I = usize>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    bbq: Arc<BBQueue<B, I>>,
+    dropped: Arc<AtomicBool>,
+}
+
+unsafe impl<B, I> Send for ConsumerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+}
+
+impl<B, I> ConsumerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    /// A consumer whose grants can not outlive the borrow of `self`
+    fn consumer(&mut self) -> Consumer<'_, B, I> {
+        Consumer {
+            bbq: NonNull::from(&*self.bbq),
+            pd: PhantomData,
+            reborrowed: true,
+        }
+    }
+
+    /// See [Consumer::read]
+    pub fn read(&mut self) -> Result<GrantR<'_, B, I>> {
+        self.consumer().read()
+    }
+
+    /// See [Consumer::read_spin]
+    pub fn read_spin(&mut self, spin: impl FnMut()) -> Result<GrantR<'_, B, I>> {
+        self.consumer().read_spin(spin)
+    }
+
+    /// See [Consumer::try_read]
+    #[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+    pub fn try_read(&mut self) -> Option<GrantR<'_, B, I>> {
+        self.consumer().try_read()
+    }
+
+    /// See [Consumer::read_exact]
+    pub fn read_exact(&mut self, sz: usize) -> Result<GrantR<'_, B, I>> {
+        self.consumer().read_exact(sz)
+    }
+
+    /// See [Consumer::split_read]
+    pub fn split_read(&mut self) -> Result<SplitGrantR<'_, B, I>> {
+        self.consumer().split_read()
+    }
+
+    /// See [Consumer::split_read_exact]
+    pub fn split_read_exact(&mut self, sz: usize) -> Result<SplitGrantR<'_, B, I>> {
+        self.consumer().split_read_exact(sz)
+    }
+
+    /// See [Consumer::read_async]
+    #[cfg(feature = "async-api")]
+    pub async fn read_async(&mut self) -> Result<GrantR<'_, B, I>> {
+        self.consumer().read_async().await
+    }
+
+    /// See [Consumer::wait_for_occupied]
+    #[cfg(feature = "async-api")]
+    pub async fn wait_for_occupied(&mut self, sz: usize) -> Result<()> {
+        self.consumer().wait_for_occupied(sz).await
+    }
+
+    /// See [Consumer::read_exact_async]
+    #[cfg(feature = "async-api")]
+    pub async fn read_exact_async(&mut self, sz: usize) -> Result<GrantR<'_, B, I>> {
+        self.consumer().read_exact_async(sz).await
+    }
+
+    /// See [Consumer::split_read_exact_async]
+    #[cfg(feature = "async-api")]
+    pub async fn split_read_exact_async(&mut self, sz: usize) -> Result<SplitGrantR<'_, B, I>> {
+        self.consumer().split_read_exact_async(sz).await
+    }
+
+    /// See [Consumer::split_read_async]
+    #[cfg(feature = "async-api")]
+    pub async fn split_read_async(&mut self) -> Result<SplitGrantR<'_, B, I>> {
+        self.consumer().split_read_async().await
+    }
+
+    /// See [Consumer::read_into]
+    pub fn read_into(&mut self, out: &mut [u8]) -> Result<usize> {
+        self.consumer().read_into(out)
+    }
+
+    /// See [Consumer::pop_slice]
+    pub fn pop_slice(&mut self, out: &mut [u8]) -> Result<usize> {
+        self.consumer().pop_slice(out)
+    }
+
+    /// See [Consumer::pop_exact]
+    pub fn pop_exact(&mut self, out: &mut [u8]) -> Result<()> {
+        self.consumer().pop_exact(out)
+    }
+
+    /// See [Consumer::discard]
+    pub fn discard(&mut self, n: usize) -> Result<usize> {
+        self.consumer().discard(n)
+    }
+
+    /// See [Consumer::drain_all]
+    pub fn drain_all(&mut self) -> Result<usize> {
+        self.consumer().drain_all()
+    }
+
+    /// See [Consumer::flush]
+    pub fn flush(&mut self) -> Result<usize> {
+        self.consumer().flush()
+    }
+
+    /// See [Consumer::len]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.bbq.occupied_len()
+    }
+
+    /// See [Consumer::is_closed]
+    pub fn is_closed(&self) -> bool {
+        self.bbq.closed.load(Acquire)
+    }
+}
+
+impl<B, I> Drop for ConsumerOwned<B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn drop(&mut self) {
+        self.bbq.release_owned(&self.dropped);
+    }
+}
@@ -0,0 +1,77 @@
+//! Implementations of the `std::io` traits, enabled with the `std` feature
+
+use crate::{BbqIndex, Consumer, Error, Producer, StorageProvider};
+use std::io::{self, Read, Write};
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::other(err)
+    }
+}
+
+/// Writes as many bytes as fit contiguously in the queue, using
+/// [Producer::grant_max_remaining].
+///
+/// The queue never blocks: when it is full, an [io::ErrorKind::WouldBlock] error is
+/// returned, and the caller should retry once the consumer has released some space.
+/// Note that helpers such as [Write::write_all] or [io::copy] bail out on this error.
+impl<'a, B, I> Write for Producer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut grant = match self.grant_max_remaining(buf.len()) {
+            Ok(grant) => grant,
+            Err(Error::InsufficientSize { .. }) => return Err(io::ErrorKind::WouldBlock.into()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let len = grant.len();
+        grant.copy_from_slice(&buf[..len]);
+        grant.commit(len);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the first contiguous region of committed bytes, using [Consumer::read].
+///
+/// If the writer has wrapped around, only the bytes up to the end of the ring are
+/// returned, and the remaining bytes will be returned by the next call.
+///
+/// The queue never blocks: when it is empty, an [io::ErrorKind::WouldBlock] error is
+/// returned rather than `Ok(0)`, as the latter would signal the end of the stream.
+/// `Ok(0)` is only returned once the producer closed the queue, see [Producer::close].
+impl<'a, B, I> Read for Consumer<'a, B, I>
+where
+    B: StorageProvider,
+    I: BbqIndex,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // Before reading, so the bytes committed right before closing aren't missed
+        let closed = self.is_closed();
+        let grant = match Consumer::read(self) {
+            Ok(grant) => grant,
+            Err(Error::InsufficientSize { .. }) if closed => return Ok(0),
+            Err(Error::InsufficientSize { .. }) => return Err(io::ErrorKind::WouldBlock.into()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let len = grant.len().min(buf.len());
+        buf[..len].copy_from_slice(&grant[..len]);
+        grant.release(len);
+        Ok(len)
+    }
+}
@@ -1,12 +1,47 @@
-use core::{cell::UnsafeCell, marker::PhantomData, ptr::NonNull};
+use core::{cell::UnsafeCell, fmt::Debug, marker::PhantomData, ptr::NonNull};
 
 /// Trait for a buffer provider.
 /// The Buffer provider allows abstraction over the memory
 /// The memory can be statically allocated, on the heap or on the stack
 pub trait StorageProvider: PartialEq {
-    /// Returns a reference to the provided buffer
+    /// Returns a pointer to the provided buffer
     /// The buffer **HAS NO GARANTEE** on it's state or initialization
+    ///
+    /// The queue only accesses the buffer through this pointer, so it must be valid for
+    /// reads and writes for the whole lifetime of the provider, even while it is borrowed
+    /// immutably. Storage behind a shared reference must be wrapped in an
+    /// [UnsafeCell](core::cell::UnsafeCell).
     fn storage(&self) -> NonNull<[u8]>;
+
+    /// Returns the size of the buffer, in bytes
+    ///
+    /// This is the capacity of a [BBQueue](crate::BBQueue) created from this provider.
+    /// It must not be larger than the length of the buffer returned by [Self::storage].
+    /// Defaults to the length of that buffer, providers knowing their size up front
+    /// may return it directly.
+    fn capacity(&self) -> usize {
+        self.storage().len()
+    }
+
+    /// Returns `true` if the buffer is known to contain only zeroes until it is
+    /// first written to.
+    ///
+    /// This is a hint allowing the [BBQueue](crate::BBQueue) to skip zeroing the buffer
+    /// when it is split. The queue zeroes the buffer again when it is released, so the
+    /// hint stays valid. Defaults to `false`.
+    fn is_zeroed(&self) -> bool {
+        false
+    }
+}
+
+/// A [StorageProvider] whose capacity is known at compile time
+///
+/// As [StorageProvider::capacity] can't be called in a `const` context, this allows
+/// to create a [BBQueue](crate::BBQueue) from the provider in a `const` or `static`
+/// initializer, with [BBQueue::new_const](crate::BBQueue::new_const).
+pub trait ConstStorageProvider: StorageProvider {
+    /// The size of the buffer, in bytes. It must be the same as [StorageProvider::capacity].
+    const CAPACITY: usize;
 }
 
 /// A statically allocated buffer
@@ -34,10 +69,134 @@ impl<const N: usize> StaticStorageProvider<N> {
     }
 }
 
+impl<const N: usize> Default for StaticStorageProvider<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<const N: usize> StorageProvider for StaticStorageProvider<N> {
     fn storage(&self) -> NonNull<[u8]> {
         NonNull::new(self.buf.get()).unwrap()
     }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    // Only ever created zeroed, by `new`
+    fn is_zeroed(&self) -> bool {
+        true
+    }
+}
+
+impl<const N: usize> ConstStorageProvider for StaticStorageProvider<N> {
+    const CAPACITY: usize = N;
+}
+
+/// The alignment of an [AlignedStorageProvider], in bytes
+#[derive(Debug)]
+pub struct Align<const ALIGN: usize>;
+
+/// Implemented by [Align] for the supported alignments, which are
+/// the powers of two from 1 to 4096 bytes
+pub trait Alignment {
+    /// A zero sized type, aligned to the requested alignment
+    type Archetype: Debug;
+}
+
+macro_rules! impl_alignment {
+    ($($align:literal => $archetype:ident),*) => {
+        $(
+            #[doc(hidden)]
+            #[derive(Debug)]
+            #[repr(align($align))]
+            pub struct $archetype;
+
+            impl Alignment for Align<$align> {
+                type Archetype = $archetype;
+            }
+        )*
+    };
+}
+
+impl_alignment!(
+    1 => Align1, 2 => Align2, 4 => Align4, 8 => Align8, 16 => Align16, 32 => Align32,
+    64 => Align64, 128 => Align128, 256 => Align256, 512 => Align512, 1024 => Align1024,
+    2048 => Align2048, 4096 => Align4096
+);
+
+/// A statically allocated buffer, whose start is aligned to `ALIGN` bytes
+///
+/// This is useful with [Producer::grant_exact_aligned](crate::Producer::grant_exact_aligned),
+/// for instance for DMA transfers which require aligned buffers.
+#[derive(Debug)]
+pub struct AlignedStorageProvider<const N: usize, const ALIGN: usize>
+where
+    Align<ALIGN>: Alignment,
+{
+    _align: [<Align<ALIGN> as Alignment>::Archetype; 0],
+    buf: UnsafeCell<[u8; N]>,
+}
+
+impl<const N: usize, const ALIGN: usize> PartialEq for AlignedStorageProvider<N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn eq(&self, other: &Self) -> bool {
+        unsafe {
+            let r = &*self.buf.get();
+            let l = &*other.buf.get();
+            r.eq(l)
+        }
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> AlignedStorageProvider<N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    /// A buffer with internal aligned allocation
+    pub const fn new() -> Self {
+        Self {
+            _align: [],
+            buf: UnsafeCell::new([0; N]),
+        }
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> Default for AlignedStorageProvider<N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> StorageProvider for AlignedStorageProvider<N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn storage(&self) -> NonNull<[u8]> {
+        NonNull::new(self.buf.get()).unwrap()
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    // Only ever created zeroed, by `new`
+    fn is_zeroed(&self) -> bool {
+        true
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> ConstStorageProvider for AlignedStorageProvider<N, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    const CAPACITY: usize = N;
 }
 
 /// A buffer allocated from userspace
@@ -62,3 +221,11 @@ impl StorageProvider for SliceStorageProvider<'_> {
         self.nn
     }
 }
+
+/// A statically allocated buffer
+#[deprecated(note = "Use `StaticStorageProvider` instead")]
+pub type StaticBufferProvider<const N: usize> = StaticStorageProvider<N>;
+
+/// A buffer allocated from userspace
+#[deprecated(note = "Use `SliceStorageProvider` instead")]
+pub type SliceBufferProvider<'a> = SliceStorageProvider<'a>;
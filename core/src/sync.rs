@@ -0,0 +1,72 @@
+//! The atomic types used by the queue
+//!
+//! With the `loom` feature, the atomics of the [BBQueue](crate::BBQueue) are replaced
+//! by the [loom](https://docs.rs/loom) ones, so the producer/consumer protocol can be
+//! model checked. As the loom atomics can't be created in a `const` context, the
+//! `const` constructors of the queue are then regular functions, see `const_fn`.
+//!
+//! The `loom` feature is only meant for testing this crate.
+
+#[cfg(not(feature = "loom"))]
+pub(crate) use core::sync::atomic::{AtomicBool, AtomicU16, AtomicU8, AtomicUsize};
+
+#[cfg(feature = "loom")]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicU16, AtomicU8, AtomicUsize};
+
+/// Declares a `const fn`, which is a regular function with the `loom` feature
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis const fn $($rest:tt)*) => {
+        #[cfg(not(feature = "loom"))]
+        $(#[$attr])*
+        $vis const fn $($rest)*
+
+        #[cfg(feature = "loom")]
+        $(#[$attr])*
+        $vis fn $($rest)*
+    };
+}
+pub(crate) use const_fn;
+
+/// An atomic flag, either one of the queue or a `core` one
+pub(crate) trait AtomicFlag {
+    #[cfg(feature = "portable-cs")]
+    fn load(&self, order: core::sync::atomic::Ordering) -> bool;
+
+    #[cfg(feature = "portable-cs")]
+    fn store(&self, val: bool, order: core::sync::atomic::Ordering);
+
+    #[cfg(not(feature = "portable-cs"))]
+    fn swap(&self, val: bool, order: core::sync::atomic::Ordering) -> bool;
+}
+
+macro_rules! impl_flag {
+    ($($ty:ty),*) => {
+        $(
+            impl AtomicFlag for $ty {
+                #[cfg(feature = "portable-cs")]
+                #[inline(always)]
+                fn load(&self, order: core::sync::atomic::Ordering) -> bool {
+                    <$ty>::load(self, order)
+                }
+
+                #[cfg(feature = "portable-cs")]
+                #[inline(always)]
+                fn store(&self, val: bool, order: core::sync::atomic::Ordering) {
+                    <$ty>::store(self, val, order)
+                }
+
+                #[cfg(not(feature = "portable-cs"))]
+                #[inline(always)]
+                fn swap(&self, val: bool, order: core::sync::atomic::Ordering) -> bool {
+                    <$ty>::swap(self, val, order)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(not(feature = "loom"))]
+impl_flag!(AtomicBool);
+
+#[cfg(feature = "loom")]
+impl_flag!(AtomicBool, core::sync::atomic::AtomicBool);
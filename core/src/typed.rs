@@ -0,0 +1,375 @@
+//! A Typed flavor of BBQueue, useful for fixed size elements
+//!
+//! This module allows for a `Typed` mode of operation, where
+//! the queue is used to pass elements of a single type `T`,
+//! rather than just a stream of bytes. This is convenient when
+//! passing samples or small structures between contexts, without
+//! manually serializing them to bytes.
+//!
+//! All sizes used by the typed interfaces, such as the size of a
+//! grant or the amount of data to commit or release, are counted
+//! in elements of `T` rather than in bytes.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # // bbqueue test shim!
+//! # fn bbqtest() {
+//! use bbqueue::BBQueue;
+//!
+//! // The backing storage must be aligned for the element type
+//! #[repr(align(2))]
+//! struct Aligned([u8; 64]);
+//!
+//! let mut memory = Aligned([0; 64]);
+//! let bb = BBQueue::new_from_slice(&mut memory.0);
+//! let (mut prod, mut cons) = bb.try_split_typed::<u16>().unwrap();
+//!
+//! // The capacity is counted in elements
+//! assert_eq!(prod.capacity(), 32);
+//!
+//! // Four elements in...
+//! let mut wgrant = prod.grant_exact(4).unwrap();
+//! wgrant.copy_from_slice(&[1000, 2000, 3000, 4000]);
+//! wgrant.commit(4);
+//!
+//! // ...four elements out
+//! let rgrant = cons.read().unwrap();
+//! assert_eq!(&*rgrant, &[1000, 2000, 3000, 4000]);
+//! rgrant.release(4);
+//! # // bbqueue test shim!
+//! # }
+//! #
+//! # fn main() {
+//! # #[cfg(not(feature = "thumbv6"))]
+//! # bbqtest();
+//! # }
+//! ```
+//!
+//! ## Alignment
+//!
+//! Grants are handed out as slices of `T`, which requires the backing
+//! storage to be aligned to `align_of::<T>()`. Splitting a queue whose
+//! storage is not suitably aligned returns [Error::Misaligned].
+//!
+//! If the capacity of the queue is not a multiple of `size_of::<T>()`,
+//! the trailing bytes at the end of the ring will never be used.
+
+use crate::{BbqIndex, Consumer, Error, GrantR, GrantW, Producer, Result, StorageProvider};
+
+use core::{
+    marker::PhantomData,
+    mem::size_of,
+    ops::{Deref, DerefMut},
+    slice::{from_raw_parts, from_raw_parts_mut},
+};
+
+/// A type that can be passed through a typed queue
+///
+/// # Safety
+///
+/// Implementors must be plain data types for which any bit pattern is a
+/// valid value (including all zeros), such as integers or `#[repr(C)]`
+/// structures made only of such types, without any padding bytes.
+/// Implementors must not be zero sized.
+pub unsafe trait Element: Copy {}
+
+macro_rules! impl_element {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl Element for $ty {}
+        )*
+    };
+}
+
+impl_element!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T, const N: usize> Element for [T; N] where T: Element {}
+
+/// A producer of Typed data
+pub struct TypedProducer<'a, B, T, I = usize>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    pub(crate) producer: Producer<'a, B, I>,
+    pub(crate) pd: PhantomData<T>,
+}
+
+impl<'a, B, T, I> TypedProducer<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    /// Request a writable, contiguous section of memory of exactly
+    /// `sz` elements. If the buffer size requested is not available,
+    /// an error will be returned.
+    ///
+    /// See [Producer::grant_exact] for details.
+    pub fn grant_exact(&mut self, sz: usize) -> Result<TypedGrantW<'a, B, T, I>> {
+        let grant_w = self.producer.grant_exact(elems_to_bytes::<T>(sz)?)?;
+        Ok(TypedGrantW {
+            grant_w,
+            pd: PhantomData,
+        })
+    }
+
+    /// Request a writable, contiguous section of memory of up to
+    /// `sz` elements.
+    ///
+    /// See [Producer::grant_max_remaining] for details. If less than one element
+    /// is available at the end of the ring, the grant will wrap around to the
+    /// beginning of the ring.
+    pub fn grant_max_remaining(&mut self, sz: usize) -> Result<TypedGrantW<'a, B, T, I>> {
+        let sz = sz.saturating_mul(size_of::<T>());
+        let grant_w = self
+            .producer
+            .grant_max_remaining_inner(sz, size_of::<T>())?;
+        Ok(TypedGrantW {
+            grant_w,
+            pd: PhantomData,
+        })
+    }
+
+    /// Async version of [Self::grant_exact]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_exact_async(&mut self, sz: usize) -> Result<TypedGrantW<'a, B, T, I>> {
+        let grant_w = self
+            .producer
+            .grant_exact_async(elems_to_bytes::<T>(sz)?)
+            .await?;
+        Ok(TypedGrantW {
+            grant_w,
+            pd: PhantomData,
+        })
+    }
+
+    /// Async version of [Self::grant_max_remaining]
+    #[cfg(feature = "async-api")]
+    pub async fn grant_max_remaining_async(
+        &mut self,
+        sz: usize,
+    ) -> Result<TypedGrantW<'a, B, T, I>> {
+        let mut fut = self
+            .producer
+            .grant_max_remaining_async(sz.saturating_mul(size_of::<T>()));
+        fut.unit = size_of::<T>();
+        Ok(TypedGrantW {
+            grant_w: fut.await?,
+            pd: PhantomData,
+        })
+    }
+
+    /// Returns the maximum number of elements that can be stored in the queue
+    pub fn capacity(&self) -> usize {
+        unsafe { self.producer.bbq.as_ref().capacity() / size_of::<T>() }
+    }
+}
+
+/// A consumer of Typed data
+pub struct TypedConsumer<'a, B, T, I = usize>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    pub(crate) consumer: Consumer<'a, B, I>,
+    pub(crate) pd: PhantomData<T>,
+}
+
+impl<'a, B, T, I> TypedConsumer<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    /// Obtains a contiguous slice of committed elements.
+    ///
+    /// See [Consumer::read] for details.
+    pub fn read(&mut self) -> Result<TypedGrantR<'a, B, T, I>> {
+        Ok(TypedGrantR::new(self.consumer.read()?))
+    }
+
+    /// Async version of [Self::read]
+    #[cfg(feature = "async-api")]
+    pub async fn read_async(&mut self) -> Result<TypedGrantR<'a, B, T, I>> {
+        Ok(TypedGrantR::new(self.consumer.read_async().await?))
+    }
+
+    /// Returns the maximum number of elements that can be stored in the queue
+    pub fn capacity(&self) -> usize {
+        unsafe { self.consumer.bbq.as_ref().capacity() / size_of::<T>() }
+    }
+}
+
+/// A write grant for a contiguous section of elements
+///
+/// NOTE: If the grant is dropped without explicitly commiting
+/// the contents, or without first calling `to_commit()`, then no
+/// elements will be comitted for writing.
+#[must_use = "a write grant commits nothing when dropped, unless configured with `to_commit`"]
+#[derive(Debug, PartialEq)]
+pub struct TypedGrantW<'a, B, T, I = usize>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    grant_w: GrantW<'a, B, I>,
+    pd: PhantomData<T>,
+}
+
+/// A read grant for a contiguous section of elements
+///
+/// NOTE: If the grant is dropped without explicitly releasing
+/// the contents, or without first calling `to_release()`, then no
+/// elements will be released.
+#[must_use = "a read grant releases nothing when dropped, unless configured with `to_release`"]
+#[derive(Debug, PartialEq)]
+pub struct TypedGrantR<'a, B, T, I = usize>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    grant_r: GrantR<'a, B, I>,
+    pd: PhantomData<T>,
+}
+
+/// Compact representation of the grant, with its length in elements.
+#[cfg(feature = "defmt")]
+impl<'a, B, T, I> defmt::Format for TypedGrantW<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "TypedGrantW {{ len: {=usize} }}", self.len());
+    }
+}
+
+/// Compact representation of the grant, with its length in elements.
+#[cfg(feature = "defmt")]
+impl<'a, B, T, I> defmt::Format for TypedGrantR<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "TypedGrantR {{ len: {=usize} }}", self.len());
+    }
+}
+
+impl<'a, B, T, I> Deref for TypedGrantW<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        let len = self.grant_w.buf.len() / size_of::<T>();
+        unsafe { from_raw_parts(self.grant_w.buf.as_ptr() as *const T, len) }
+    }
+}
+
+impl<'a, B, T, I> DerefMut for TypedGrantW<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    fn deref_mut(&mut self) -> &mut [T] {
+        let len = self.grant_w.buf.len() / size_of::<T>();
+        unsafe { from_raw_parts_mut(self.grant_w.buf.as_ptr() as *mut T, len) }
+    }
+}
+
+impl<'a, B, T, I> Deref for TypedGrantR<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        let len = self.grant_r.buf.len() / size_of::<T>();
+        unsafe { from_raw_parts(self.grant_r.buf.as_ptr() as *const T, len) }
+    }
+}
+
+impl<'a, B, T, I> DerefMut for TypedGrantR<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    fn deref_mut(&mut self) -> &mut [T] {
+        let len = self.grant_r.buf.len() / size_of::<T>();
+        unsafe { from_raw_parts_mut(self.grant_r.buf.as_ptr() as *mut T, len) }
+    }
+}
+
+impl<'a, B, T, I> TypedGrantW<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    /// Commit `used` elements to make them available to the Consumer half.
+    ///
+    /// If `used` is larger than the given grant, the maximum amount will
+    /// be commited
+    pub fn commit(self, used: usize) {
+        self.grant_w.commit(used.saturating_mul(size_of::<T>()));
+    }
+
+    /// Configures the amount of elements to be commited on drop.
+    pub fn to_commit(&mut self, amt: usize) {
+        self.grant_w.to_commit(amt.saturating_mul(size_of::<T>()));
+    }
+}
+
+impl<'a, B, T, I> TypedGrantR<'a, B, T, I>
+where
+    B: StorageProvider,
+    T: Element,
+    I: BbqIndex,
+{
+    fn new(mut grant_r: GrantR<'a, B, I>) -> Self {
+        // We only ever commit whole elements, so the readable region
+        // should always contain whole elements
+        let len = grant_r.buf.len();
+        debug_assert_eq!(len % size_of::<T>(), 0);
+        grant_r.shrink(len - (len % size_of::<T>()));
+
+        TypedGrantR {
+            grant_r,
+            pd: PhantomData,
+        }
+    }
+
+    /// Release `used` elements, allowing the space to be used by later writes.
+    ///
+    /// If `used` is larger than the given grant, the full grant will
+    /// be released.
+    pub fn release(self, used: usize) {
+        self.grant_r.release(used.saturating_mul(size_of::<T>()));
+    }
+
+    /// Configures the amount of elements to be released on drop.
+    pub fn to_release(&mut self, amt: usize) {
+        self.grant_r.to_release(amt.saturating_mul(size_of::<T>()));
+    }
+}
+
+/// Convert a number of elements to a number of bytes, failing if it overflows
+fn elems_to_bytes<T>(sz: usize) -> Result<usize> {
+    sz.checked_mul(size_of::<T>()).ok_or(Error::RequestTooLarge)
+}
@@ -0,0 +1,102 @@
+//! The storage of the wakers of the async API
+//!
+//! By default, with the `async` feature, the wakers are stored in an [AtomicWaker]. With the
+//! `cs-waker` feature, they are stored behind a critical section instead, provided by the
+//! [critical-section](https://docs.rs/critical-section) crate. This doesn't require the
+//! `atomic-waker` crate, nor atomic read-modify-write operations, which is enough for
+//! applications with a single executor.
+//!
+//! Both storages implement [WakerCell], used by the queue and its futures.
+
+#[cfg(not(feature = "cs-waker"))]
+use atomic_waker::AtomicWaker;
+#[cfg(feature = "cs-waker")]
+use core::cell::Cell;
+use core::task::Waker;
+#[cfg(feature = "cs-waker")]
+use critical_section::Mutex;
+
+#[cfg(not(any(feature = "async", feature = "cs-waker")))]
+compile_error!("the async API requires a waker storage, enable the `async` or `cs-waker` feature");
+
+/// The operations on the storage of a waker
+pub(crate) trait WakerCell {
+    /// Registers `waker` to be woken up by the next call to [Self::wake], replacing the
+    /// previously registered waker, if any
+    fn register(&self, waker: &Waker);
+
+    /// Wakes up the registered waker, if any, and removes it
+    fn wake(&self);
+
+    /// Removes the registered waker, if any, without waking it up
+    fn take(&self) -> Option<Waker>;
+}
+
+/// The storage of a waker of the queue, see the module documentation
+pub(crate) struct WakerStorage {
+    #[cfg(not(feature = "cs-waker"))]
+    waker: AtomicWaker,
+
+    #[cfg(feature = "cs-waker")]
+    waker: Mutex<Cell<Option<Waker>>>,
+}
+
+impl WakerStorage {
+    /// Creates a storage without a registered waker
+    pub(crate) const fn new() -> Self {
+        Self {
+            #[cfg(not(feature = "cs-waker"))]
+            waker: AtomicWaker::new(),
+
+            #[cfg(feature = "cs-waker")]
+            waker: Mutex::new(Cell::new(None)),
+        }
+    }
+}
+
+#[cfg(not(feature = "cs-waker"))]
+impl WakerCell for WakerStorage {
+    fn register(&self, waker: &Waker) {
+        self.waker.register(waker);
+    }
+
+    fn wake(&self) {
+        self.waker.wake();
+    }
+
+    fn take(&self) -> Option<Waker> {
+        self.waker.take()
+    }
+}
+
+#[cfg(feature = "cs-waker")]
+impl WakerCell for WakerStorage {
+    fn register(&self, waker: &Waker) {
+        // Don't clone the waker if it is already registered. The replaced waker is
+        // dropped outside of the critical section, as dropping it may run arbitrary code
+        let _replaced = critical_section::with(|cs| {
+            let cell = self.waker.borrow(cs);
+            match cell.take() {
+                Some(old) if old.will_wake(waker) => {
+                    cell.set(Some(old));
+                    None
+                }
+                old => {
+                    cell.set(Some(waker.clone()));
+                    old
+                }
+            }
+        });
+    }
+
+    fn wake(&self) {
+        // Same as `register`, wake outside of the critical section
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+
+    fn take(&self) -> Option<Waker> {
+        critical_section::with(|cs| self.waker.borrow(cs).take())
+    }
+}
@@ -0,0 +1,76 @@
+use core::fmt;
+
+/// Writes formatted text into the buffer of a grant
+///
+/// Created by [GrantW::writer](crate::GrantW::writer) or
+/// [FrameGrantW::writer](crate::framed::FrameGrantW::writer). Text that does not
+/// fit in the remaining space of the grant is not written, and `fmt::Error` is
+/// returned instead of truncating it. Use [Self::written] to find out how many
+/// bytes to commit.
+///
+/// ```rust
+/// # // bbqueue test shim!
+/// # fn bbqtest() {
+/// use bbqueue::{BBQueue, StaticStorageProvider};
+/// use core::fmt::Write;
+///
+/// let bb: BBQueue<StaticStorageProvider<16>> = BBQueue::new_static();
+/// let (mut prod, mut cons) = bb.try_split().unwrap();
+///
+/// let mut grant = prod.grant_exact(8).unwrap();
+/// let mut writer = grant.writer();
+/// write!(writer, "{}-{}", 4, 2).unwrap();
+/// let written = writer.written();
+/// grant.commit(written);
+///
+/// assert_eq!(cons.read().unwrap().buf(), b"4-2");
+/// # // bbqueue test shim!
+/// # }
+/// #
+/// # fn main() {
+/// # #[cfg(not(feature = "thumbv6"))]
+/// # bbqtest();
+/// # }
+/// ```
+pub struct GrantWriter<'g> {
+    buf: &'g mut [u8],
+    written: usize,
+}
+
+impl<'g> GrantWriter<'g> {
+    pub(crate) fn new(buf: &'g mut [u8]) -> Self {
+        Self { buf, written: 0 }
+    }
+
+    /// The number of bytes written so far
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// The number of bytes that can still be written
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.written
+    }
+}
+
+impl<'g> fmt::Write for GrantWriter<'g> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = &mut self.buf[self.written..];
+        if s.len() > remaining.len() {
+            return Err(fmt::Error);
+        }
+
+        remaining[..s.len()].copy_from_slice(s.as_bytes());
+        self.written += s.len();
+        Ok(())
+    }
+}
+
+impl<'g> fmt::Debug for GrantWriter<'g> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrantWriter")
+            .field("written", &self.written)
+            .field("capacity", &self.buf.len())
+            .finish()
+    }
+}